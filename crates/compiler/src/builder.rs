@@ -1,8 +1,17 @@
 //! Function Builder.
-use anyhow::Result;
-use jac_translate::{quickpars::BinaryReader, FunctionTranslation};
+use crate::nanbox;
+use crate::runtime::{RuntimeFunc, RuntimeImports};
+use anyhow::{anyhow, bail, Result};
+use jac_translate::{
+    quickpars::{CopyDataPropertiesMask, Opcode, SpecialObjectKind},
+    FunctionTranslation,
+};
 use std::collections::{HashMap, HashSet};
-use waffle::{Block, FunctionBody, Local, Signature, Type, Value};
+use std::fmt;
+use waffle::{
+    Block, BlockTarget, FunctionBody, Local, Module, Operator, Signature, SignatureData,
+    Terminator, Type, Value,
+};
 
 /// The current block.
 #[derive(Default)]
@@ -13,10 +22,21 @@ pub(crate) struct CurrentBlock {
     locals: HashMap<Local, Type>,
 }
 
+/// An active exception handler, installed by a `Catch` operator.
+struct Handler {
+    /// The bytecode offset of the handler, which also marks the end of the
+    /// protected region.
+    offset: u32,
+    /// The operand stack depth at the `Catch` operator.
+    depth: usize,
+}
+
 /// An IR builder.
 pub(crate) struct FunctionBuilder<'a, 'data> {
     /// The QuickJS bytecode function translation.
     translation: &'a FunctionTranslation<'data>,
+    /// The runtime functions available to the function.
+    imports: &'a RuntimeImports,
     /// The funciton signature.
     signature: Signature,
     /// The resulting function body.
@@ -29,28 +49,958 @@ pub(crate) struct FunctionBuilder<'a, 'data> {
     sealed: HashSet<Block>,
     /// Placeholder locals used to calculate block params.
     placeholders: HashMap<Block, Vec<(Local, Value)>>,
+    /// The current value of each argument, followed by each variable.
+    frame: Vec<Value>,
+    /// The shadow operand stack.
+    stack: Vec<Value>,
+    /// Bytecode offsets which are the target of a branch.
+    targets: HashSet<u32>,
+    /// Blocks starting at a branch target, keyed by bytecode offset.
+    target_blocks: HashMap<u32, Block>,
+    /// The frame and the operand stack at the entry of each branch target
+    /// block, as block params.
+    target_params: HashMap<Block, Vec<Value>>,
+    /// The blocks which are not terminated yet.
+    open: HashSet<Block>,
+    /// The active exception handlers, innermost last.
+    handlers: Vec<Handler>,
+    /// The offsets following each `GoSub` operator, keyed by the offset of
+    /// the `finally` block they enter.
+    continuations: HashMap<u32, Vec<u32>>,
 }
 
 impl<'a, 'data> FunctionBuilder<'a, 'data> {
-    pub fn new(translation: &'a FunctionTranslation<'data>) -> Self {
+    pub fn new(
+        translation: &'a FunctionTranslation<'data>,
+        imports: &'a RuntimeImports,
+        module: &Module,
+        signature: Signature,
+    ) -> Self {
         Self {
             translation,
-            signature: Default::default(),
-            result: Default::default(),
+            imports,
+            signature,
+            result: FunctionBody::new(module, signature),
             decl: Default::default(),
             block: None,
             sealed: Default::default(),
             placeholders: Default::default(),
+            frame: Default::default(),
+            stack: Default::default(),
+            targets: Default::default(),
+            target_blocks: Default::default(),
+            target_params: Default::default(),
+            open: Default::default(),
+            handlers: Default::default(),
+            continuations: Default::default(),
         }
     }
 
     pub fn build(mut self) -> Result<(Signature, FunctionBody)> {
-        let mut reader = &mut self.translation.operators.clone();
-        self.handle_operator(&mut reader)?;
+        let mut reader = self.translation.operators_reader();
+        let mut operators = vec![];
+        while !reader.done() {
+            operators.push(Opcode::from_reader(&mut reader)?);
+        }
+        // `with` statements make variable resolution dynamic, which is not
+        // supported yet.
+        if operators.iter().any(|(_, op)| is_with_op(op)) {
+            bail!("`with` statements are not supported");
+        }
+        // Direct `eval` needs the source and the scope at runtime, which
+        // can't be compiled ahead of time.
+        if operators
+            .iter()
+            .any(|(_, op)| matches!(op, Opcode::Eval { .. } | Opcode::ApplyEval { .. }))
+        {
+            bail!("Direct `eval` is not supported");
+        }
+        self.targets = operators
+            .iter()
+            .filter_map(|(pc, op)| branch_target(*pc, op))
+            .collect();
+        // `Ret` branches back to the operator following the `GoSub` that
+        // entered the `finally` block.
+        for (pc, op) in &operators {
+            if let Opcode::GoSub { .. } = op {
+                let finally = branch_target(*pc, op).unwrap();
+                let continuation = pc + GOSUB_LEN;
+                self.continuations
+                    .entry(finally)
+                    .or_default()
+                    .push(continuation);
+                self.targets.insert(continuation);
+            }
+        }
+
+        self.open.insert(self.result.entry);
+        self.switch_to_block(self.result.entry);
+        self.init_frame()?;
+
+        for (pc, op) in operators {
+            // The protected region of a handler ends at the handler itself.
+            self.handlers.retain(|h| pc < h.offset);
+            self.enter_offset(pc);
+            // Skip unreachable operators, which are not the target of any
+            // branch.
+            if self.block.is_none() {
+                continue;
+            }
+            self.handle_operator(pc, op)?;
+        }
+        // Blocks left open, e.g., the fallthrough of a branch at the end of
+        // the function, are never reached.
+        for block in std::mem::take(&mut self.open) {
+            self.result.set_terminator(block, Terminator::Unreachable);
+        }
         Ok((self.signature, self.result))
     }
 
-    fn handle_operator(&mut self, _reader: &mut BinaryReader<'data>) -> Result<()> {
+    /// Initializes the frame: defined arguments are read from the params of
+    /// the entry block, while the remaining arguments and every variable
+    /// start as `undefined`.
+    fn init_frame(&mut self) -> Result<()> {
+        let params = self.result.blocks[self.result.entry]
+            .params
+            .iter()
+            .map(|(_, value)| *value)
+            .collect::<Vec<_>>();
+        let undefined = self.i64_const(nanbox::UNDEFINED)?;
+        self.frame = (0..self.translation.header.local_count as usize)
+            .map(|i| params.get(i).copied().unwrap_or(undefined))
+            .collect();
+        Ok(())
+    }
+
+    fn handle_operator(&mut self, pc: u32, op: Opcode) -> Result<()> {
+        use Opcode::*;
+
+        let arg_count = self.translation.header.arg_count as usize;
+        if let Some((access, slot)) = frame_access(&op, arg_count) {
+            return self.access_frame(access, slot);
+        }
+
+        match op {
+            // Constants.
+            Undefined => self.push_const(nanbox::UNDEFINED)?,
+            Null => self.push_const(nanbox::NULL)?,
+            PushTrue => self.push_const(nanbox::TRUE)?,
+            PushFalse => self.push_const(nanbox::FALSE)?,
+            PushMinus1 => self.push_const(nanbox::int(-1))?,
+            Push0 => self.push_const(nanbox::int(0))?,
+            Push1 => self.push_const(nanbox::int(1))?,
+            Push2 => self.push_const(nanbox::int(2))?,
+            Push3 => self.push_const(nanbox::int(3))?,
+            Push4 => self.push_const(nanbox::int(4))?,
+            Push5 => self.push_const(nanbox::int(5))?,
+            Push6 => self.push_const(nanbox::int(6))?,
+            Push7 => self.push_const(nanbox::int(7))?,
+            PushI8 { val } => self.push_const(nanbox::int(val as i32))?,
+            PushI16 { val } => self.push_const(nanbox::int(val as i32))?,
+            PushI32 { value } => self.push_const(nanbox::int(value))?,
+            PushEmptyString => {
+                let value = self.call(RuntimeFunc::EmptyString, &[])?;
+                self.stack.push(value);
+            }
+            PushAtomValue { atom } => {
+                let atom = self.i32_const(atom.as_u32())?;
+                let value = self.call(RuntimeFunc::AtomValue, &[atom])?;
+                self.stack.push(value);
+            }
+            // Stack manipulation.
+            Drop => {
+                self.pop()?;
+            }
+            Nip => {
+                let value = self.pop()?;
+                self.pop()?;
+                self.stack.push(value);
+            }
+            Dup => {
+                let value = self.peek(1)?;
+                self.stack.push(value);
+            }
+            Swap => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.stack.extend([b, a]);
+            }
+            // Locals.
+            SetLocUninit { index } => {
+                let slot = arg_count + index.as_u32() as usize;
+                self.frame_value(slot)?;
+                self.frame[slot] = self.i64_const(nanbox::UNINITIALIZED)?;
+            }
+            // Closures don't capture locals yet, so there's nothing to
+            // detach.
+            CloseLoc { .. } => {}
+            IncLoc { index } | DecLoc { index } | AddLoc { index } => {
+                let slot = arg_count + index.as_u32() as usize;
+                let local = self.frame_value(slot)?;
+                let (func, rhs) = match op {
+                    AddLoc { .. } => (RuntimeFunc::Add, self.pop()?),
+                    IncLoc { .. } => (RuntimeFunc::Add, self.i64_const(nanbox::int(1))?),
+                    _ => (RuntimeFunc::Sub, self.i64_const(nanbox::int(1))?),
+                };
+                let value = self.call(func, &[local, rhs])?;
+                self.frame[slot] = value;
+            }
+            // Variables.
+            GetVar { atom } | GetVarUndef { atom } => {
+                let atom = self.i32_const(atom.as_u32())?;
+                // Only `typeof` evaluates undeclared variables to
+                // `undefined` instead of throwing a `ReferenceError`.
+                let throws = self.i32_const(matches!(op, GetVar { .. }) as u32)?;
+                let value = self.call(RuntimeFunc::GetVar, &[atom, throws])?;
+                self.stack.push(value);
+            }
+            // Arithmetic.
+            Add | Sub | Mul => {
+                let rhs = self.pop()?;
+                let lhs = self.pop()?;
+                let func = match op {
+                    Add => RuntimeFunc::Add,
+                    Sub => RuntimeFunc::Sub,
+                    _ => RuntimeFunc::Mul,
+                };
+                let value = self.call(func, &[lhs, rhs])?;
+                self.stack.push(value);
+            }
+            Inc | Dec => {
+                let value = self.pop()?;
+                let one = self.i64_const(nanbox::int(1))?;
+                let func = match op {
+                    Inc => RuntimeFunc::Add,
+                    _ => RuntimeFunc::Sub,
+                };
+                let value = self.call(func, &[value, one])?;
+                self.stack.push(value);
+            }
+            // Calls.
+            Call { .. } | Call0 | Call1 | Call2 | Call3 => {
+                let argc = match op {
+                    Call { argc } => argc as usize,
+                    Call0 => 0,
+                    Call1 => 1,
+                    Call2 => 2,
+                    _ => 3,
+                };
+                let args = self.pop_n(argc)?;
+                let func = self.pop()?;
+                let this = self.i64_const(nanbox::UNDEFINED)?;
+                self.call_function(func, this, &args)?;
+            }
+            CallMethod { argc } => {
+                let args = self.pop_n(argc as usize)?;
+                let func = self.pop()?;
+                let this = self.pop()?;
+                self.call_function(func, this, &args)?;
+            }
+            Return => {
+                let value = self.pop()?;
+                let values = self.return_values(value)?;
+                self.terminate(Terminator::Return { values })?;
+            }
+            ReturnUndef => {
+                let value = self.i64_const(nanbox::UNDEFINED)?;
+                let values = self.return_values(value)?;
+                self.terminate(Terminator::Return { values })?;
+            }
+            IfTrue { .. } | IfTrue8 { .. } | IfFalse { .. } | IfFalse8 { .. } => {
+                let target = branch_target(pc, &op).unwrap();
+                let value = self.pop()?;
+                let cond = self.call(RuntimeFunc::ToBool, &[value])?;
+                let taken = self.target(target);
+                // The fallthrough block is only reachable from the current
+                // block, so it can keep using the current operand stack.
+                let fallthrough = self.add_block();
+                let not_taken = BlockTarget {
+                    block: fallthrough,
+                    args: vec![],
+                };
+                let (if_true, if_false) = match op {
+                    IfTrue { .. } | IfTrue8 { .. } => (taken, not_taken),
+                    _ => (not_taken, taken),
+                };
+                self.terminate(Terminator::CondBr {
+                    cond,
+                    if_true,
+                    if_false,
+                })?;
+                self.switch_to_block(fallthrough);
+            }
+            GoTo { .. } | GoTo8 { .. } | GoTo16 { .. } => {
+                let target = branch_target(pc, &op).unwrap();
+                let target = self.target(target);
+                self.terminate(Terminator::Br { target })?;
+            }
+            // Bitwise operators.
+            And | Or | Xor | Shl | Sar | Shr => {
+                let rhs = self.pop()?;
+                let lhs = self.pop()?;
+                let lhs = self.call(RuntimeFunc::ToInt32, &[lhs])?;
+                let rhs = self.call(RuntimeFunc::ToInt32, &[rhs])?;
+                // Wasm shifts, as JS ones, only use the low 5 bits of the
+                // shift count.
+                let operator = match op {
+                    And => Operator::I32And,
+                    Or => Operator::I32Or,
+                    Xor => Operator::I32Xor,
+                    Shl => Operator::I32Shl,
+                    Sar => Operator::I32ShrS,
+                    _ => Operator::I32ShrU,
+                };
+                let block = self.current_block()?;
+                let result = self
+                    .result
+                    .add_op(block, operator, &[lhs, rhs], &[Type::I32]);
+                let value = match op {
+                    // `>>>` produces an unsigned integer.
+                    Shr => self.box_uint32(result)?,
+                    _ => self.box_int32(result)?,
+                };
+                self.stack.push(value);
+            }
+            // Exception handling.
+            Catch { .. } => {
+                let handler = branch_target(pc, &op).unwrap();
+                self.handlers.push(Handler {
+                    offset: handler,
+                    depth: self.stack.len(),
+                });
+                // The handler is entered with the exception in place of the
+                // catch offset, so its block is created with the same
+                // params as the stack after pushing the catch offset.
+                let catch_offset = self.i64_const(0)?;
+                self.stack.push(catch_offset);
+                self.target(handler);
+            }
+            NipCatch => {
+                let depth = self
+                    .handlers
+                    .last()
+                    .map(|h| h.depth)
+                    .ok_or_else(|| anyhow!("NipCatch outside of a try block"))?;
+                let value = self.pop()?;
+                self.stack.truncate(depth);
+                self.stack.push(value);
+            }
+            Throw => {
+                let exception = self.pop()?;
+                match self.handler_target(exception)? {
+                    Some(target) => self.terminate(Terminator::Br { target })?,
+                    // Without a handler, the exception is left pending and
+                    // propagated to the caller.
+                    None => {
+                        self.call(RuntimeFunc::Throw, &[exception])?;
+                        let values = self.return_values(exception)?;
+                        self.terminate(Terminator::Return { values })?;
+                    }
+                }
+            }
+            GoSub { .. } => {
+                let finally = branch_target(pc, &op).unwrap();
+                let continuation = pc + GOSUB_LEN;
+                let index = self.continuations[&finally]
+                    .iter()
+                    .position(|c| *c == continuation)
+                    .unwrap();
+                // The continuation is entered with the operand stack at the
+                // `GoSub` operator, once the return address is popped.
+                self.target(continuation);
+                let address = self.i64_const(index as u64)?;
+                self.stack.push(address);
+                let target = self.target(finally);
+                self.terminate(Terminator::Br { target })?;
+            }
+            Ret => {
+                let finally = self
+                    .continuations
+                    .keys()
+                    .filter(|offset| **offset <= pc)
+                    .max()
+                    .copied()
+                    .ok_or_else(|| anyhow!("Ret outside of a finally block"))?;
+                let address = self.pop()?;
+                let block = self.current_block()?;
+                let value =
+                    self.result
+                        .add_op(block, Operator::I32WrapI64, &[address], &[Type::I32]);
+                let mut targets = self.continuations[&finally]
+                    .clone()
+                    .into_iter()
+                    .map(|c| self.target(c))
+                    .collect::<Vec<_>>();
+                let default = targets.pop().unwrap();
+                self.terminate(Terminator::Select {
+                    value,
+                    targets,
+                    default,
+                })?;
+            }
+            // Property access.
+            GetArrayEl => {
+                let prop = self.pop()?;
+                let obj = self.pop()?;
+                let value = self.call(RuntimeFunc::GetArrayEl, &[obj, prop])?;
+                self.stack.push(value);
+            }
+            GetArrayEl2 => {
+                // Keeps the object on the stack, e.g., to be used as the
+                // `this` value of a call.
+                let prop = self.pop()?;
+                let obj = self.peek(1)?;
+                let value = self.call(RuntimeFunc::GetArrayEl, &[obj, prop])?;
+                self.stack.push(value);
+            }
+            GetLength => {
+                // `.length` is common enough, e.g., in loop conditions, to
+                // get its own runtime function instead of a generic
+                // property lookup.
+                let obj = self.pop()?;
+                let value = self.call(RuntimeFunc::GetLength, &[obj])?;
+                self.stack.push(value);
+            }
+            // `super` references.
+            //
+            // The runtime keeps the home object of each method, set through
+            // `SetHomeObject` when the method is defined. Methods read it
+            // back, through a call, each time they reference `super`, and
+            // `GetSuper` pops it from the stack to resolve the prototype.
+            SetHomeObject => {
+                // Keeps both the home object and the method on the stack.
+                let method = self.peek(1)?;
+                let home_object = self.peek(2)?;
+                self.emit_call(RuntimeFunc::SetHomeObject, &[method, home_object])?;
+            }
+            SpecialObject { argument } => match SpecialObjectKind::from_argument(argument) {
+                Some(SpecialObjectKind::HomeObject) => {
+                    let home_object = self.emit_call(RuntimeFunc::HomeObject, &[])?;
+                    self.stack.push(home_object);
+                }
+                _ => return Err(UnsupportedOperator(format!("special object {}", argument)).into()),
+            },
+            GetSuper => {
+                let home_object = self.pop()?;
+                let proto = self.call(RuntimeFunc::GetSuper, &[home_object])?;
+                self.stack.push(proto);
+            }
+            GetSuperValue => {
+                let prop = self.pop()?;
+                let obj = self.pop()?;
+                let this = self.pop()?;
+                let value = self.call(RuntimeFunc::GetSuperValue, &[obj, prop, this])?;
+                self.stack.push(value);
+            }
+            PutSuperValue => {
+                let value = self.pop()?;
+                let prop = self.pop()?;
+                let obj = self.pop()?;
+                let this = self.pop()?;
+                self.call(RuntimeFunc::PutSuperValue, &[obj, prop, value, this])?;
+            }
+            CopyDataProperties { mask } => {
+                let operands = CopyDataPropertiesMask::from_mask(mask);
+                let target = self.peek(operands.target as usize + 1)?;
+                let source = self.peek(operands.source as usize + 1)?;
+                let excluded = self.peek(operands.excluded as usize + 1)?;
+                let mask = self.i32_const(mask as u32)?;
+                self.call(
+                    RuntimeFunc::CopyDataProperties,
+                    &[target, source, excluded, mask],
+                )?;
+            }
+            PutArrayEl => {
+                let value = self.pop()?;
+                let prop = self.pop()?;
+                let obj = self.pop()?;
+                self.call(RuntimeFunc::PutArrayEl, &[obj, prop, value])?;
+            }
+            DefineArrayEl => {
+                let value = self.pop()?;
+                let prop = self.peek(1)?;
+                let obj = self.peek(2)?;
+                self.call(RuntimeFunc::DefineArrayEl, &[obj, prop, value])?;
+            }
+            // Modules.
+            Import => {
+                let specifier = self.pop()?;
+                let promise = self.call(RuntimeFunc::DynamicImport, &[specifier])?;
+                self.stack.push(promise);
+            }
+            // Iteration.
+            //
+            // The iterator slot pushed by `ForOfStart` holds an iterator
+            // record managed by the runtime, which also tracks the `next`
+            // method and the completion state; the `next` method and catch
+            // offset slots are kept only to preserve the stack layout of the
+            // bytecode.
+            ForOfStart => {
+                let iterable = self.pop()?;
+                let iterator = self.call(RuntimeFunc::ForOfStart, &[iterable])?;
+                let next = self.i64_const(0)?;
+                let catch_offset = self.i64_const(0)?;
+                self.stack.extend([iterator, next, catch_offset]);
+            }
+            ForOfNext { offset } => {
+                // The iterator record is located below the iterator's
+                // `next` method, the catch offset and `offset` other
+                // values.
+                let depth = 3 + offset as usize;
+                let iterator = self.peek(depth)?;
+                let next = self.peek(depth - 1)?;
+                let value = self.call(RuntimeFunc::ForOfNext, &[iterator, next])?;
+                let done = self.call(RuntimeFunc::IteratorDone, &[iterator])?;
+                self.stack.extend([value, done]);
+            }
+            IteratorNext => {
+                let value = self.pop()?;
+                let iterator = self.peek(3)?;
+                let next = self.peek(2)?;
+                let result = self.call(RuntimeFunc::IteratorNext, &[iterator, next, value])?;
+                self.stack.push(result);
+            }
+            IteratorGetValueDone => {
+                let result = self.pop()?;
+                let value = self.call(RuntimeFunc::IteratorGetValue, &[result])?;
+                let done = self.call(RuntimeFunc::IteratorGetDone, &[result])?;
+                self.stack.extend([value, done]);
+            }
+            IteratorClose => {
+                let _catch_offset = self.pop()?;
+                let _next = self.pop()?;
+                let iterator = self.pop()?;
+                self.call(RuntimeFunc::IteratorClose, &[iterator])?;
+            }
+            op => {
+                return Err(UnsupportedOperator(Opcode::name_from_byte(op.discriminant())).into())
+            }
+        }
+
         Ok(())
     }
+
+    /// Prepares the builder to handle the operator at the given offset.
+    ///
+    /// If the offset is the target of a branch, the current block falls
+    /// through to the block starting at the offset, which becomes the current
+    /// block.
+    fn enter_offset(&mut self, pc: u32) {
+        if !self.targets.contains(&pc) {
+            return;
+        }
+
+        let block = if self.block.is_some() {
+            let target = self.target(pc);
+            let block = target.block;
+            // The current block is known to be present.
+            self.terminate(Terminator::Br { target }).unwrap();
+            block
+        } else {
+            match self.target_blocks.get(&pc) {
+                Some(block) => *block,
+                // The offset is not reachable through fallthrough nor
+                // through any of the previous branches; the operand stack
+                // is assumed to be empty.
+                None => {
+                    self.stack.clear();
+                    self.target(pc).block
+                }
+            }
+        };
+
+        let (frame, stack) = self.target_params[&block].split_at(self.frame.len());
+        self.frame = frame.to_vec();
+        self.stack = stack.to_vec();
+        self.switch_to_block(block);
+    }
+
+    /// Returns a branch target to the block starting at the given offset,
+    /// passing the current frame and operand stack as block arguments.
+    ///
+    /// The block is created on first use, with one param per value in the
+    /// frame and in the operand stack.
+    fn target(&mut self, pc: u32) -> BlockTarget {
+        let block = match self.target_blocks.get(&pc) {
+            Some(block) => *block,
+            None => {
+                let block = self.add_block();
+                let params = (0..self.frame.len() + self.stack.len())
+                    .map(|_| self.result.add_blockparam(block, Type::I64))
+                    .collect();
+                self.target_params.insert(block, params);
+                self.target_blocks.insert(pc, block);
+                block
+            }
+        };
+
+        BlockTarget {
+            block,
+            args: self.frame.iter().chain(&self.stack).copied().collect(),
+        }
+    }
+
+    /// Returns a branch target to the innermost active handler, passing the
+    /// current frame, the operand stack at the `Catch` operator and the
+    /// exception as block arguments.
+    fn handler_target(&self, exception: Value) -> Result<Option<BlockTarget>> {
+        let Some(handler) = self.handlers.last() else {
+            return Ok(None);
+        };
+        let stack = self
+            .stack
+            .get(..handler.depth)
+            .ok_or_else(|| anyhow!("Operand stack underflow"))?;
+        let mut args = [self.frame.as_slice(), stack].concat();
+        args.push(exception);
+        Ok(Some(BlockTarget {
+            block: self.target_blocks[&handler.offset],
+            args,
+        }))
+    }
+
+    /// Branches to the innermost active handler if the last runtime call
+    /// left an exception pending.
+    fn check_exception(&mut self) -> Result<()> {
+        if self.handlers.is_empty() {
+            return Ok(());
+        }
+        let pending = self.emit_call(RuntimeFunc::HasException, &[])?;
+        let landing = self.add_block();
+        let fallthrough = self.add_block();
+        self.terminate(Terminator::CondBr {
+            cond: pending,
+            if_true: BlockTarget {
+                block: landing,
+                args: vec![],
+            },
+            if_false: BlockTarget {
+                block: fallthrough,
+                args: vec![],
+            },
+        })?;
+
+        self.switch_to_block(landing);
+        let exception = self.emit_call(RuntimeFunc::TakeException, &[])?;
+        // A handler is known to be active.
+        let target = self.handler_target(exception)?.unwrap();
+        self.terminate(Terminator::Br { target })?;
+
+        self.switch_to_block(fallthrough);
+        Ok(())
+    }
+
+    /// Adds a block to the function, which must be terminated before the
+    /// function is built.
+    fn add_block(&mut self) -> Block {
+        let block = self.result.add_block();
+        self.open.insert(block);
+        block
+    }
+
+    /// Switches the current block.
+    fn switch_to_block(&mut self, block: Block) {
+        self.block = Some(CurrentBlock {
+            block,
+            locals: Default::default(),
+        });
+    }
+
+    /// Returns the current block.
+    fn current_block(&self) -> Result<Block> {
+        self.block
+            .as_ref()
+            .map(|b| b.block)
+            .ok_or_else(|| anyhow!("Expected a current block"))
+    }
+
+    /// Terminates the current block.
+    fn terminate(&mut self, terminator: Terminator) -> Result<()> {
+        let block = self.current_block()?;
+        self.result.set_terminator(block, terminator);
+        self.open.remove(&block);
+        self.block = None;
+        Ok(())
+    }
+
+    /// Returns the value of the given frame slot.
+    fn frame_value(&self, slot: usize) -> Result<Value> {
+        self.frame
+            .get(slot)
+            .copied()
+            .ok_or_else(|| anyhow!("Invalid local: {}", slot))
+    }
+
+    /// Reads or writes the given frame slot.
+    fn access_frame(&mut self, access: FrameAccess, slot: usize) -> Result<()> {
+        let current = self.frame_value(slot)?;
+        match access {
+            FrameAccess::Get => self.stack.push(current),
+            FrameAccess::Put => self.frame[slot] = self.pop()?,
+            FrameAccess::Set => self.frame[slot] = self.peek(1)?,
+        }
+        Ok(())
+    }
+
+    /// Returns the values returned by the compiled function for the given
+    /// value, see [FunctionTranslation::return_arity].
+    fn return_values(&mut self, value: Value) -> Result<Vec<Value>> {
+        let mut values = vec![value];
+        if self.translation.return_arity() == 2 {
+            // The function completed, instead of suspending.
+            values.push(self.i64_const(nanbox::TRUE)?);
+        }
+        Ok(values)
+    }
+
+    /// Calls a JavaScript function with the given `this` value and
+    /// arguments, pushing its result onto the operand stack.
+    ///
+    /// Arguments are passed one by one to the runtime, which collects them
+    /// for the next `call`.
+    fn call_function(&mut self, func: Value, this: Value, args: &[Value]) -> Result<()> {
+        for arg in args {
+            self.emit_call(RuntimeFunc::PushArg, &[*arg])?;
+        }
+        let argc = self.i32_const(args.len() as u32)?;
+        let value = self.call(RuntimeFunc::Call, &[func, this, argc])?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Emits a call to a runtime function in the current block.
+    ///
+    /// Inside a try block, the call is followed by a branch to the handler
+    /// if the function left an exception pending.
+    fn call(&mut self, func: RuntimeFunc, args: &[Value]) -> Result<Value> {
+        let value = self.emit_call(func, args)?;
+        if func.can_throw() {
+            self.check_exception()?;
+        }
+        Ok(value)
+    }
+
+    /// Emits a call to a runtime function in the current block, without
+    /// checking for exceptions.
+    fn emit_call(&mut self, func: RuntimeFunc, args: &[Value]) -> Result<Value> {
+        let block = self.current_block()?;
+        let function_index = self.imports.get(func);
+        Ok(self.result.add_op(
+            block,
+            Operator::Call { function_index },
+            args,
+            func.returns(),
+        ))
+    }
+
+    /// Emits an `i64` constant in the current block.
+    fn i64_const(&mut self, value: u64) -> Result<Value> {
+        let block = self.current_block()?;
+        Ok(self
+            .result
+            .add_op(block, Operator::I64Const { value }, &[], &[Type::I64]))
+    }
+
+    /// Pushes a NaN-boxed constant onto the operand stack.
+    fn push_const(&mut self, value: u64) -> Result<()> {
+        let value = self.i64_const(value)?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Boxes a 32-bit integer.
+    fn box_int32(&mut self, value: Value) -> Result<Value> {
+        let block = self.current_block()?;
+        // The tag of integers is `0`, so zero extending the payload is
+        // enough.
+        Ok(self
+            .result
+            .add_op(block, Operator::I64ExtendI32U, &[value], &[Type::I64]))
+    }
+
+    /// Boxes an unsigned 32-bit integer, as an integer if it fits in an
+    /// `i32`, or as a double otherwise.
+    fn box_uint32(&mut self, value: Value) -> Result<Value> {
+        let int = self.box_int32(value)?;
+        let block = self.current_block()?;
+        let double = self
+            .result
+            .add_op(block, Operator::F64ConvertI32U, &[value], &[Type::F64]);
+        let bits = self
+            .result
+            .add_op(block, Operator::I64ReinterpretF64, &[double], &[Type::I64]);
+        let addend = self.i64_const(nanbox::FLOAT64_TAG_ADDEND)?;
+        let float = self
+            .result
+            .add_op(block, Operator::I64Sub, &[bits, addend], &[Type::I64]);
+        let zero = self.i32_const(0)?;
+        let fits = self
+            .result
+            .add_op(block, Operator::I32GeS, &[value, zero], &[Type::I32]);
+        Ok(self
+            .result
+            .add_op(block, Operator::Select, &[int, float, fits], &[Type::I64]))
+    }
+
+    /// Emits an `i32` constant.
+    fn i32_const(&mut self, value: u32) -> Result<Value> {
+        let block = self.current_block()?;
+        Ok(self
+            .result
+            .add_op(block, Operator::I32Const { value }, &[], &[Type::I32]))
+    }
+
+    /// Pops a value from the operand stack.
+    fn pop(&mut self) -> Result<Value> {
+        self.stack
+            .pop()
+            .ok_or_else(|| anyhow!("Operand stack underflow"))
+    }
+
+    /// Pops the given number of values from the operand stack, in the order
+    /// they were pushed.
+    fn pop_n(&mut self, n: usize) -> Result<Vec<Value>> {
+        let len = self
+            .stack
+            .len()
+            .checked_sub(n)
+            .ok_or_else(|| anyhow!("Operand stack underflow"))?;
+        Ok(self.stack.split_off(len))
+    }
+
+    /// Returns the value at the given depth of the operand stack, where `1`
+    /// is the top of the stack.
+    fn peek(&self, depth: usize) -> Result<Value> {
+        self.stack
+            .len()
+            .checked_sub(depth)
+            .map(|i| self.stack[i])
+            .ok_or_else(|| anyhow!("Operand stack underflow"))
+    }
+}
+
+/// The error of functions using an operator that the builder doesn't support
+/// yet.
+#[derive(Debug)]
+pub(crate) struct UnsupportedOperator(pub String);
+
+impl fmt::Display for UnsupportedOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unsupported opcode: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedOperator {}
+
+/// Returns the body of a function which traps when called, standing in for
+/// a function that can't be compiled yet.
+pub(crate) fn trap_body(module: &Module, signature: Signature) -> FunctionBody {
+    let mut body = FunctionBody::new(module, signature);
+    body.set_terminator(body.entry, Terminator::Unreachable);
+    body
+}
+
+/// How an operator accesses a frame slot.
+enum FrameAccess {
+    /// Pushes the value of the slot.
+    Get,
+    /// Pops a value into the slot.
+    Put,
+    /// Copies the top of the operand stack into the slot.
+    Set,
+}
+
+/// Returns how the operator accesses the frame, along with the accessed
+/// slot, i.e., the index of the argument or `arg_count` plus the index of the
+/// variable.
+///
+/// The TDZ checks of `let` and `const` variables are not performed yet.
+fn frame_access(op: &Opcode, arg_count: usize) -> Option<(FrameAccess, usize)> {
+    use FrameAccess::*;
+    use Opcode::*;
+
+    let var = |index: u32| arg_count + index as usize;
+    let access = match *op {
+        GetLoc { index } | GetLoc8 { index } | GetLocCheck { index } => (Get, var(index.as_u32())),
+        GetLoc0 => (Get, var(0)),
+        GetLoc1 => (Get, var(1)),
+        GetLoc2 => (Get, var(2)),
+        GetLoc3 => (Get, var(3)),
+        PutLoc { index }
+        | PutLoc8 { index }
+        | PutLocCheck { index }
+        | PutLocCheckInit { index } => (Put, var(index.as_u32())),
+        PutLoc0 => (Put, var(0)),
+        PutLoc1 => (Put, var(1)),
+        PutLoc2 => (Put, var(2)),
+        PutLoc3 => (Put, var(3)),
+        SetLoc { index } | SetLoc8 { index } => (Set, var(index.as_u32())),
+        SetLoc0 => (Set, var(0)),
+        SetLoc1 => (Set, var(1)),
+        SetLoc2 => (Set, var(2)),
+        SetLoc3 => (Set, var(3)),
+        GetArg { index } => (Get, index.as_u32() as usize),
+        GetArg0 => (Get, 0),
+        GetArg1 => (Get, 1),
+        GetArg2 => (Get, 2),
+        GetArg3 => (Get, 3),
+        PutArg { index } => (Put, index.as_u32() as usize),
+        PutArg0 => (Put, 0),
+        PutArg1 => (Put, 1),
+        PutArg2 => (Put, 2),
+        PutArg3 => (Put, 3),
+        SetArg { index } => (Set, index.as_u32() as usize),
+        SetArg0 => (Set, 0),
+        SetArg1 => (Set, 1),
+        SetArg2 => (Set, 2),
+        SetArg3 => (Set, 3),
+        _ => return None,
+    };
+    Some(access)
+}
+
+/// The length of the `GoSub` operator, including its immediate.
+const GOSUB_LEN: u32 = 5;
+
+/// Returns the signature of a compiled function.
+///
+/// Every defined argument is a NaN-boxed `i64` param, and the function returns
+/// [FunctionTranslation::return_arity] NaN-boxed `i64`s.
+pub(crate) fn signature_data(translation: &FunctionTranslation) -> SignatureData {
+    SignatureData {
+        params: vec![Type::I64; translation.header.defined_arg_count as usize],
+        returns: vec![Type::I64; translation.return_arity()],
+    }
+}
+
+/// Whether the operator implements `with` statement semantics.
+fn is_with_op(op: &Opcode) -> bool {
+    use Opcode::*;
+
+    matches!(
+        op,
+        WithGetVar { .. }
+            | WithPutVar { .. }
+            | WithDeleteVar { .. }
+            | WithMakeRef { .. }
+            | WithGetRef { .. }
+            | WithGetRefUndef { .. }
+    )
+}
+
+/// Returns the bytecode offset targeted by a branching operator at `pc`.
+///
+/// Branch offsets are relative to the operator's immediate, i.e., the byte
+/// after the opcode.
+fn branch_target(pc: u32, op: &Opcode) -> Option<u32> {
+    use Opcode::*;
+
+    let offset = match *op {
+        IfFalse { offset } | IfTrue { offset } | GoTo { offset } => offset,
+        IfFalse8 { offset } | IfTrue8 { offset } | GoTo8 { offset } => offset as i32,
+        GoTo16 { offset } => offset as i32,
+        Catch { diff } | GoSub { diff } => diff as i32,
+        _ => return None,
+    };
+    Some((pc as i64 + 1 + offset as i64) as u32)
 }
@@ -1,31 +1,201 @@
-use crate::builder::FunctionBuilder;
+use crate::builder::{signature_data, trap_body, FunctionBuilder, UnsupportedOperator};
+use crate::linetable::encode_bytecode_lines_section;
+use crate::options::CompileOptions;
+use crate::runtime::RuntimeImports;
+use crate::sourcemap::{SourceMap, SourceMapEntry};
 use anyhow::Result;
-use jac_translate::{quickpars::Opcode, FunctionTranslation, Translation};
-use waffle::{Block, FunctionBody, Module};
+use jac_translate::{
+    quickpars::{AtomIndex, Opcode, ATOM_NAMES},
+    FunctionTranslation, Translation,
+};
+use waffle::{Block, FuncDecl, FunctionBody, Module};
 
 /// QuickJS-bytecode-to-Wasm compiler.
-pub(crate) struct Compiler<'data> {
+pub struct Compiler<'data> {
     /// QuickJS bytecode in memory representation.
     translation: Translation<'data>,
     /// The resulting Wasm module.
     module: Module<'data>,
+    /// The runtime functions imported by the module.
+    imports: RuntimeImports,
+    /// Mapping from the compiled functions to their source.
+    source_map: SourceMap,
+    /// The index and name of each compiled function.
+    symbols: Vec<(u32, String)>,
+    /// The Wasm features that the compiled module can use.
+    options: CompileOptions,
+    /// Whether the target runtime is compiled with BIGNUM support.
+    bignum_runtime: bool,
+    /// The warnings found during compilation.
+    warnings: Vec<String>,
 }
 
 impl<'data> Compiler<'data> {
     /// Create a new compiler from the translated QuickJS bytecode.
     pub fn new(translation: Translation<'data>) -> Self {
+        let mut module = Module::empty();
+        let imports = RuntimeImports::declare(&mut module);
         Self {
             translation,
-            module: Module::empty(),
+            module,
+            imports,
+            source_map: Default::default(),
+            symbols: vec![],
+            options: Default::default(),
+            bignum_runtime: true,
+            warnings: vec![],
         }
     }
 
+    /// Sets the Wasm features that the compiled module can use.
+    pub fn with_options(mut self, options: CompileOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets whether the target runtime is compiled with BIGNUM support,
+    /// which is assumed by default.
+    ///
+    /// Compiling bytecode that uses BIGNUM-only opcodes for a runtime
+    /// without BIGNUM support produces a warning, see
+    /// [`Compiler::warnings`].
+    pub fn with_bignum_runtime(mut self, enabled: bool) -> Self {
+        self.bignum_runtime = enabled;
+        self
+    }
+
     /// Perform compilation into Wasm bytes.
+    ///
+    /// Functions using operators that are not supported yet are compiled to
+    /// a trap, with a warning, see [`Compiler::warnings`].
+    ///
+    /// Errors if a function requires a Wasm proposal disabled through
+    /// [`CompileOptions`], or if the compiled module doesn't validate.
     pub fn compile(&mut self) -> Result<Vec<u8>> {
-        for func in &self.translation.module.functions {
-            let mut fbuilder = FunctionBuilder::new(&func);
-            let (signature, body) = fbuilder.build()?;
+        if !self.bignum_runtime && self.translation.uses_bignum_opcodes() {
+            self.warnings.push(
+                "The bytecode uses BIGNUM opcodes, which the target runtime doesn't support"
+                    .to_string(),
+            );
+        }
+        let names = function_names(&self.translation);
+        for (func, name) in self.translation.module.functions.iter().zip(names) {
+            self.options.check_function(func, &name)?;
+            let signature = self.module.signatures.push(signature_data(func));
+            let fbuilder = FunctionBuilder::new(func, &self.imports, &self.module, signature);
+            let body = match fbuilder.build() {
+                Ok((_, body)) => body,
+                // Functions using operators that aren't supported yet trap
+                // when called, instead of failing the whole compilation.
+                Err(e) if e.is::<UnsupportedOperator>() => {
+                    self.warnings
+                        .push(format!("Function `{}` compiles to a trap: {}", name, e));
+                    trap_body(&self.module, signature)
+                }
+                Err(e) => return Err(e),
+            };
+            // Defined functions are indexed after the runtime imports.
+            let func_index = self
+                .module
+                .funcs
+                .push(FuncDecl::Body(signature, name.clone(), body))
+                .index() as u32;
+            self.source_map.push(source_map_entry(
+                &self.translation,
+                func,
+                func_index,
+                name.clone(),
+            ));
+            self.symbols.push((func_index, name));
         }
-        self.module.to_wasm_bytes()
+        let wasm = self.module.to_wasm_bytes()?;
+        self.options.validate(&wasm)?;
+        Ok(wasm)
+    }
+
+    /// Returns the name map of the compiled functions in the `wasm-tools`
+    /// symbol map format, i.e., one `<func_index>:<name>` line per function.
+    ///
+    /// Anonymous functions are named `lambda_fn_N`, in module order.
+    ///
+    /// Only populated after [`Compiler::compile`].
+    pub fn emit_symbol_map(&self) -> String {
+        self.symbols
+            .iter()
+            .map(|(func_index, name)| format!("{}:{}\n", func_index, name))
+            .collect()
+    }
+
+    /// Returns the bytecode line tables of the compiled functions, mapping
+    /// their QuickJS bytecode offsets to source lines and columns, as a
+    /// [`BYTECODE_LINES_SECTION`](crate::BYTECODE_LINES_SECTION) custom
+    /// section to append to the compiled module.
+    ///
+    /// Functions without debug information are omitted.
+    pub fn emit_bytecode_lines_section(&self) -> Vec<u8> {
+        encode_bytecode_lines_section(&self.translation, self.imports.len() as u32)
+    }
+
+    /// Returns the warnings found during compilation.
+    ///
+    /// Only populated after [`Compiler::compile`].
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Returns the mapping from compiled functions to their JavaScript
+    /// source.
+    ///
+    /// Only populated after [`Compiler::compile`].
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+}
+
+/// Returns the name of each function in the module, naming anonymous
+/// functions `lambda_fn_N`, in module order.
+fn function_names(translation: &Translation) -> Vec<String> {
+    let mut lambdas = 0;
+    translation
+        .module
+        .functions
+        .iter()
+        .map(|func| {
+            let name = translation.resolve_func_name(func.index, None);
+            if name.is_empty() || name == ATOM_NAMES[0] {
+                lambdas += 1;
+                format!("lambda_fn_{}", lambdas - 1)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Creates the [`SourceMapEntry`] of a function, named as in
+/// [`function_names`].
+fn source_map_entry(
+    translation: &Translation,
+    func: &FunctionTranslation,
+    func_index: u32,
+    name: String,
+) -> SourceMapEntry {
+    let (file, line) = match &func.debug {
+        Some(debug) => (
+            Some(
+                translation
+                    .resolve_atom_name(AtomIndex::from_u32(debug.filename))
+                    .to_string(),
+            ),
+            Some(debug.lineno),
+        ),
+        None => (None, None),
+    };
+
+    SourceMapEntry {
+        func_index,
+        name,
+        file,
+        line,
     }
 }
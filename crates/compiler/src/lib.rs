@@ -3,14 +3,51 @@
 
 //! JAC - The Javy Ahead-of-Time Compiler.
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use jac_translate::TranslationBuilder;
+use javy::{Config, Runtime};
 mod builder;
 mod compiler;
+mod linetable;
+mod nanbox;
+mod options;
+mod runtime;
+mod sourcemap;
 
+pub use compiler::Compiler;
+pub use linetable::BYTECODE_LINES_SECTION;
+pub use nanbox::FLOAT64_TAG_ADDEND;
+pub use options::CompileOptions;
+pub use sourcemap::{SourceMap, SourceMapEntry};
+
+/// Compiles QuickJS bytecode to Wasm.
+///
+/// Errors if the bytecode is empty or invalid, including bytecode which
+/// ends before its first function.
 pub fn compile(bytes: &[u8]) -> Result<Vec<u8>> {
-    let mut builder = TranslationBuilder::new();
-    let _translation = builder.translate(bytes)?;
+    ensure!(!bytes.is_empty(), "Expected non-empty bytecode");
+    let builder = TranslationBuilder::new();
+    let translation = builder.translate(bytes)?;
+    ensure!(
+        !translation.module.functions.is_empty(),
+        "Expected at least one function in the bytecode"
+    );
+
+    Compiler::new(translation).compile()
+}
+
+/// Compiles JavaScript source to QuickJS bytecode, through a Javy [`Runtime`]
+/// with the default configuration.
+pub fn compile_to_bytecode(source: &str, filename: &str) -> Result<Vec<u8>> {
+    let runtime = Runtime::new(Config::default())?;
+    runtime.compile_to_bytecode(filename, source)
+}
 
-    Ok(vec![])
+/// Compiles JavaScript source to Wasm.
+///
+/// The source is first compiled to QuickJS bytecode through
+/// [`compile_to_bytecode`], then fed through [`compile`].
+pub fn compile_js(source: &str, filename: &str) -> Result<Vec<u8>> {
+    let bytecode = compile_to_bytecode(source, filename)?;
+    compile(&bytecode)
 }
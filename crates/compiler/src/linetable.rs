@@ -0,0 +1,71 @@
+//! Bytecode line tables of compiled functions.
+//!
+//! The line tables are emitted as a `jac.bytecode_lines` custom section,
+//! with the following layout, where every integer is an unsigned LEB-128:
+//!
+//! ```text
+//! function_count
+//! function_count * (func_index, file_len, file, row_count, row_count * (pc, line, col))
+//! ```
+//!
+//! Rows map the QuickJS bytecode offsets of each function, not the offsets
+//! of the generated Wasm instructions, to its source lines and columns.
+//! Tools can relate them to the Wasm code through the bytecode of the
+//! function, e.g., as printed by `jac-utils print`.
+use jac_translate::{quickpars::AtomIndex, Translation};
+
+/// The name of the custom section holding the bytecode line tables.
+pub const BYTECODE_LINES_SECTION: &str = "jac.bytecode_lines";
+
+/// The id of Wasm custom sections.
+const CUSTOM_SECTION_ID: u8 = 0;
+
+/// Encodes the line tables of the functions with debug information as a
+/// custom section, ready to be appended to a Wasm module.
+///
+/// Functions are assumed to be defined in module order, starting at
+/// `first_func_index`.
+pub(crate) fn encode_bytecode_lines_section(
+    translation: &Translation,
+    first_func_index: u32,
+) -> Vec<u8> {
+    let mut functions = vec![];
+    let mut count = 0;
+    for (i, func) in translation.module.functions.iter().enumerate() {
+        let Some(debug) = &func.debug else {
+            continue;
+        };
+        let file = translation.resolve_atom_name(AtomIndex::from_u32(debug.filename));
+        let rows = debug.line_table();
+        write_u32(&mut functions, first_func_index + i as u32);
+        write_str(&mut functions, file);
+        write_u32(&mut functions, rows.len() as u32);
+        for (pc, line, col) in rows {
+            write_u32(&mut functions, pc);
+            write_u32(&mut functions, line);
+            write_u32(&mut functions, col);
+        }
+        count += 1;
+    }
+
+    let mut contents = vec![];
+    write_str(&mut contents, BYTECODE_LINES_SECTION);
+    write_u32(&mut contents, count);
+    contents.extend(functions);
+
+    let mut section = vec![CUSTOM_SECTION_ID];
+    write_u32(&mut section, contents.len() as u32);
+    section.extend(contents);
+    section
+}
+
+/// Writes a length prefixed string.
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_u32(out, s.len() as u32);
+    out.extend(s.as_bytes());
+}
+
+/// Writes an unsigned LEB-128.
+fn write_u32(out: &mut Vec<u8>, value: u32) {
+    leb128::write::unsigned(out, value as u64).expect("writing to a Vec can't fail");
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use jac_translate::TranslationBuilder;
+use jacc::Compiler;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "jacc", version, about = "The Javy Ahead-of-Time Compiler")]
+struct Cli {
+    /// Path to the JavaScript input file.
+    #[arg(value_name = "JS", required = true)]
+    pub input: PathBuf,
+
+    /// The directory and file where to place the Wasm module.
+    /// Defaults to `index.wasm`
+    #[arg(short = 'o', required = false, default_value = "index.wasm")]
+    pub out: PathBuf,
+
+    /// Additional artifacts to emit next to the Wasm module.
+    #[arg(long, value_enum)]
+    pub emit: Vec<Emit>,
+}
+
+/// Additional compilation artifacts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Emit {
+    /// JSON mapping from Wasm function index to JavaScript source location,
+    /// written to `<out>.map.json`.
+    Sourcemap,
+    /// `wasm-tools` compatible symbol map, with one `<func_index>:<name>`
+    /// line per function, written to `<out>.symbolmap`.
+    Symbolmap,
+    /// Bytecode line tables of the compiled functions, appended to the Wasm
+    /// module as a `jac.bytecode_lines` custom section.
+    BytecodeLines,
+}
+
+fn main() -> Result<()> {
+    let args = Cli::parse();
+
+    let source = std::fs::read_to_string(&args.input)?;
+    let name = args
+        .input
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("index.js");
+    let bytecode = jacc::compile_to_bytecode(&source, name)?;
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation);
+    let mut wasm = compiler.compile()?;
+    for warning in compiler.warnings() {
+        eprintln!("warning: {warning}");
+    }
+    if args.emit.contains(&Emit::BytecodeLines) {
+        wasm.extend(compiler.emit_bytecode_lines_section());
+    }
+    std::fs::write(&args.out, wasm)?;
+
+    for emit in &args.emit {
+        match emit {
+            Emit::Sourcemap => {
+                let json = compiler.source_map().to_json()?;
+                std::fs::write(args.out.with_extension("map.json"), json)?;
+            }
+            Emit::Symbolmap => {
+                let map = compiler.emit_symbol_map();
+                std::fs::write(args.out.with_extension("symbolmap"), map)?;
+            }
+            // Already appended to the module.
+            Emit::BytecodeLines => {}
+        }
+    }
+
+    Ok(())
+}
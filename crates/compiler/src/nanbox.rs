@@ -0,0 +1,79 @@
+//! NaN-boxed JavaScript values.
+//!
+//! Mirrors the `JSValue` representation of QuickJS builds with
+//! `JS_NAN_BOXING`: the tag is stored in the upper 32 bits and the payload
+//! in the lower 32 bits. Values whose tag is not a pointer tag can be
+//! materialized as constants without calling into the runtime.
+
+/// The first, i.e., lowest, pointer tag.
+pub(crate) const TAG_FIRST: i32 = -11;
+/// The tag of 32-bit integers.
+pub(crate) const TAG_INT: i32 = 0;
+/// The tag of booleans.
+pub(crate) const TAG_BOOL: i32 = 1;
+/// The tag of `null`.
+pub(crate) const TAG_NULL: i32 = 2;
+/// The tag of `undefined`.
+pub(crate) const TAG_UNDEFINED: i32 = 3;
+/// The tag of the value of `let` and `const` variables before their
+/// initialization.
+pub(crate) const TAG_UNINITIALIZED: i32 = 4;
+
+/// The offset subtracted from the upper 32 bits of a double to box it, so
+/// that doubles don't overlap with the tagged values, as
+/// `JS_FLOAT64_TAG_ADDEND`.
+pub const FLOAT64_TAG_ADDEND: u64 = ((0x7ff80000 - TAG_FIRST + 1) as u64) << 32;
+
+/// Boxes a payload with the given tag, as `JS_MKVAL`.
+pub(crate) const fn mkval(tag: i32, payload: i32) -> u64 {
+    ((tag as u32 as u64) << 32) | payload as u32 as u64
+}
+
+/// `undefined`.
+pub(crate) const UNDEFINED: u64 = mkval(TAG_UNDEFINED, 0);
+/// The value of uninitialized `let` and `const` variables.
+pub(crate) const UNINITIALIZED: u64 = mkval(TAG_UNINITIALIZED, 0);
+/// `null`.
+pub(crate) const NULL: u64 = mkval(TAG_NULL, 0);
+/// `false`.
+pub(crate) const FALSE: u64 = mkval(TAG_BOOL, 0);
+/// `true`.
+pub(crate) const TRUE: u64 = mkval(TAG_BOOL, 1);
+
+/// Boxes a 32-bit integer.
+pub(crate) const fn int(value: i32) -> u64 {
+    mkval(TAG_INT, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_match_quickjs() {
+        assert_eq!(UNDEFINED, 0x0000_0003_0000_0000);
+        assert_eq!(NULL, 0x0000_0002_0000_0000);
+        assert_eq!(FALSE, 0x0000_0001_0000_0000);
+        assert_eq!(TRUE, 0x0000_0001_0000_0001);
+        assert_eq!(UNINITIALIZED, 0x0000_0004_0000_0000);
+    }
+
+    #[test]
+    fn negative_ints_keep_the_int_tag() {
+        assert_eq!(int(-1), 0x0000_0000_ffff_ffff);
+        assert_eq!(int(i32::MIN), 0x0000_0000_8000_0000);
+    }
+
+    #[test]
+    fn boxed_doubles_do_not_overlap_tags() {
+        // Boxing subtracts the addend from the bits of the double, as
+        // `__JS_NewFloat64`.
+        let boxed = 4294967295f64.to_bits().wrapping_sub(FLOAT64_TAG_ADDEND);
+        let tag = (boxed >> 32) as u32 as i32;
+        assert!(tag < TAG_FIRST || tag > TAG_UNINITIALIZED);
+        assert_eq!(
+            f64::from_bits(boxed.wrapping_add(FLOAT64_TAG_ADDEND)),
+            4294967295.0
+        );
+    }
+}
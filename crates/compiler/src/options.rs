@@ -0,0 +1,79 @@
+//! Compilation options.
+use anyhow::{anyhow, ensure, Result};
+use jac_translate::FunctionTranslation;
+use wasmparser::{Validator, WasmFeatures};
+
+/// The Wasm proposals that the compiled module is allowed to use, on top of
+/// the MVP feature set.
+///
+/// Disabling proposals allows running the compiled module on older
+/// runtimes. All the proposals are enabled by default.
+///
+/// The backend only emits MVP operators, so bulk memory and SIMD are never
+/// used; functions returning multiple values can't be lowered without
+/// multi-value, and are rejected before code generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileOptions {
+    /// Bulk memory operations, e.g., `memory.copy` and `memory.fill`.
+    pub enable_bulk_memory: bool,
+    /// 128-bit SIMD.
+    pub enable_simd: bool,
+    /// Functions returning multiple values, used by generator and async
+    /// functions, see [`FunctionTranslation::return_arity`].
+    ///
+    /// [`FunctionTranslation::return_arity`]: jac_translate::FunctionTranslation::return_arity
+    pub enable_multi_value: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            enable_bulk_memory: true,
+            enable_simd: true,
+            enable_multi_value: true,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Options targeting the MVP feature set, without any of the proposals
+    /// above.
+    pub fn mvp() -> Self {
+        Self {
+            enable_bulk_memory: false,
+            enable_simd: false,
+            enable_multi_value: false,
+        }
+    }
+
+    /// The validator features matching the options.
+    pub(crate) fn features(&self) -> WasmFeatures {
+        // The MVP feature set, floats being the only flag that isn't a
+        // proposal.
+        let mut features = WasmFeatures::FLOATS;
+        features.set(WasmFeatures::BULK_MEMORY, self.enable_bulk_memory);
+        features.set(WasmFeatures::SIMD, self.enable_simd);
+        features.set(WasmFeatures::MULTI_VALUE, self.enable_multi_value);
+        features
+    }
+
+    /// Checks that the function can be compiled with the enabled
+    /// proposals, before generating its code.
+    pub(crate) fn check_function(&self, func: &FunctionTranslation, name: &str) -> Result<()> {
+        ensure!(
+            self.enable_multi_value || func.return_arity() == 1,
+            "Function `{}` returns {} values, which requires the multi-value proposal",
+            name,
+            func.return_arity()
+        );
+        Ok(())
+    }
+
+    /// Validates the compiled module against the enabled proposals.
+    pub(crate) fn validate(&self, wasm: &[u8]) -> Result<()> {
+        Validator::new_with_features(self.features())
+            .validate_all(wasm)
+            .map(|_| ())
+            .map_err(|err| anyhow!("Invalid compiled module: {err}"))
+    }
+}
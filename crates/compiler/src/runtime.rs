@@ -0,0 +1,196 @@
+//! Runtime imports.
+//!
+//! Operations that cannot be expressed directly in Wasm are delegated to the
+//! JAC runtime, which the compiled module imports from the `jacrt` module.
+//! JavaScript values cross the boundary as NaN-boxed `i64`s.
+use std::collections::HashMap;
+use waffle::{Func, FuncDecl, Import, ImportKind, Module, SignatureData, Type};
+
+/// The name of the module from which runtime functions are imported.
+pub(crate) const RUNTIME_MODULE: &str = "jacrt";
+
+macro_rules! runtime_funcs {
+    ($($(#[$doc:meta])* $variant:ident => $name:literal ($($param:ident),*) -> ($($ret:ident),*);)*) => {
+        /// A function provided by the runtime.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub(crate) enum RuntimeFunc {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl RuntimeFunc {
+            /// All the runtime functions.
+            const ALL: &'static [RuntimeFunc] = &[$(RuntimeFunc::$variant,)*];
+
+            /// The import name of the function.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(RuntimeFunc::$variant => $name,)*
+                }
+            }
+
+            /// The parameter types of the function.
+            pub fn params(&self) -> &'static [Type] {
+                match self {
+                    $(RuntimeFunc::$variant => &[$(Type::$param),*],)*
+                }
+            }
+
+            /// The result types of the function.
+            pub fn returns(&self) -> &'static [Type] {
+                match self {
+                    $(RuntimeFunc::$variant => &[$(Type::$ret),*],)*
+                }
+            }
+        }
+    };
+}
+
+runtime_funcs! {
+    /// Converts a value to a boolean, returning `1` or `0`.
+    ToBool => "to_bool" (I64) -> (I32);
+    /// Converts a value to a 32-bit integer, as the `ToInt32` abstract
+    /// operation.
+    ToInt32 => "to_int32" (I64) -> (I32);
+    /// Reads the variable named by the given atom from the global scope.
+    /// Receives whether undeclared variables throw a `ReferenceError`
+    /// (`1`) or evaluate to `undefined` (`0`).
+    GetVar => "get_var" (I32, I32) -> (I64);
+    /// Adds two values, as the `+` operator.
+    Add => "add" (I64, I64) -> (I64);
+    /// Subtracts two values, as the `-` operator.
+    Sub => "sub" (I64, I64) -> (I64);
+    /// Multiplies two values, as the `*` operator.
+    Mul => "mul" (I64, I64) -> (I64);
+    /// Appends an argument to the ones passed to the next `call`.
+    PushArg => "push_arg" (I64) -> ();
+    /// Calls a function with the given `this` value and the last `argc`
+    /// arguments passed through `push_arg`, returning its result.
+    Call => "call" (I64, I64, I32) -> (I64);
+    /// Creates an iterator record from an iterable.
+    ForOfStart => "for_of_start" (I64) -> (I64);
+    /// Steps the iterator record, returning the next value.
+    ForOfNext => "for_of_next" (I64, I64) -> (I64);
+    /// Whether the last step of the iterator record completed the iteration.
+    IteratorDone => "iterator_done" (I64) -> (I64);
+    /// Calls the `next` method of the iterator with the given value.
+    IteratorNext => "iterator_next" (I64, I64, I64) -> (I64);
+    /// Closes the iterator.
+    IteratorClose => "iterator_close" (I64) -> ();
+    /// Reads the `value` property of an iterator result.
+    IteratorGetValue => "iterator_get_value" (I64) -> (I64);
+    /// Reads the `done` property of an iterator result.
+    IteratorGetDone => "iterator_get_done" (I64) -> (I64);
+    /// Reads the property of an object, `obj[prop]`.
+    GetArrayEl => "get_array_el" (I64, I64) -> (I64);
+    /// Reads the `length` property of a value, `obj.length`.
+    GetLength => "get_length" (I64) -> (I64);
+    /// Writes the property of an object, `obj[prop] = value`.
+    PutArrayEl => "put_array_el" (I64, I64, I64) -> ();
+    /// Defines an own property of an object, as in array literals.
+    DefineArrayEl => "define_array_el" (I64, I64, I64) -> ();
+    /// Copies the own enumerable properties of `source` to `target`,
+    /// skipping the keys of `excluded`, as in object spread. Receives the
+    /// `target`, `source` and `excluded` values and the operator's mask.
+    CopyDataProperties => "copy_data_properties" (I64, I64, I64, I32) -> ();
+    /// Sets the given value as the pending exception.
+    Throw => "throw" (I64) -> ();
+    /// Whether there's a pending exception, returning `1` or `0`.
+    HasException => "has_exception" () -> (I32);
+    /// Clears the pending exception and returns it.
+    TakeException => "take_exception" () -> (I64);
+    /// Returns the empty string, which, as every string, is allocated by
+    /// the runtime.
+    EmptyString => "empty_string" () -> (I64);
+    /// Returns the string value of the atom with the given index, e.g., a
+    /// computed property key.
+    ///
+    /// Atoms are interned by the runtime, so the index is the one in the
+    /// atom table of the compiled bytecode, including the built-in atoms,
+    /// which the runtime maps to its own atoms.
+    AtomValue => "atom_value" (I32) -> (I64);
+    /// Sets the home object of a method, i.e., the object whose prototype
+    /// `super` refers to within the method. Receives the method and the
+    /// home object.
+    SetHomeObject => "set_home_object" (I64, I64) -> ();
+    /// Returns the home object of the function being executed, or
+    /// `undefined` if it has none.
+    HomeObject => "home_object" () -> (I64);
+    /// Returns the prototype of the given home object, which `super`
+    /// refers to.
+    GetSuper => "get_super" (I64) -> (I64);
+    /// Reads a property through `super`, `super[prop]`. Receives the
+    /// object returned by `get_super`, the property key and the `this`
+    /// value used as the receiver.
+    GetSuperValue => "get_super_value" (I64, I64, I64) -> (I64);
+    /// Writes a property through `super`, `super[prop] = value`. Receives
+    /// the object returned by `get_super`, the property key, the value and
+    /// the `this` value used as the receiver.
+    PutSuperValue => "put_super_value" (I64, I64, I64, I64) -> ();
+    /// Starts loading the module with the given specifier, as `import()`,
+    /// returning a promise for its namespace object.
+    ///
+    /// The runtime converts the specifier to a string and resolves it
+    /// against the name of the module being executed, through the module
+    /// loader of its context. Resolution, loading and evaluation errors
+    /// reject the promise instead of being thrown.
+    DynamicImport => "dynamic_import" (I64) -> (I64);
+}
+
+impl RuntimeFunc {
+    /// Whether the function may leave an exception pending.
+    pub fn can_throw(&self) -> bool {
+        !matches!(
+            self,
+            RuntimeFunc::ToBool
+                | RuntimeFunc::PushArg
+                | RuntimeFunc::Throw
+                | RuntimeFunc::HasException
+                | RuntimeFunc::TakeException
+                | RuntimeFunc::EmptyString
+                | RuntimeFunc::SetHomeObject
+                | RuntimeFunc::HomeObject
+        )
+    }
+}
+
+/// The runtime functions imported by a module.
+pub(crate) struct RuntimeImports {
+    funcs: HashMap<RuntimeFunc, Func>,
+}
+
+impl RuntimeImports {
+    /// Declares every runtime function as an import of the given module.
+    ///
+    /// Imports are declared before any function definition so that
+    /// definitions are assigned stable indices after the imports.
+    pub fn declare(module: &mut Module) -> Self {
+        let mut funcs = HashMap::new();
+        for f in RuntimeFunc::ALL {
+            let sig = module.signatures.push(SignatureData {
+                params: f.params().to_vec(),
+                returns: f.returns().to_vec(),
+            });
+            let func = module
+                .funcs
+                .push(FuncDecl::Import(sig, f.name().to_string()));
+            module.imports.push(Import {
+                module: RUNTIME_MODULE.to_string(),
+                name: f.name().to_string(),
+                kind: ImportKind::Func(func),
+            });
+            funcs.insert(*f, func);
+        }
+
+        Self { funcs }
+    }
+
+    /// Returns the imported function.
+    pub fn get(&self, f: RuntimeFunc) -> Func {
+        self.funcs[&f]
+    }
+
+    /// The number of imported functions.
+    pub fn len(&self) -> usize {
+        self.funcs.len()
+    }
+}
@@ -0,0 +1,43 @@
+//! Wasm function to JavaScript source mapping.
+use anyhow::Result;
+use serde::Serialize;
+
+/// A single source map entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceMapEntry {
+    /// The index of the generated Wasm function.
+    pub func_index: u32,
+    /// The name of the JavaScript function.
+    pub name: String,
+    /// The source file name, if the function carries debug information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The line at which the function is defined, if the function carries
+    /// debug information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// Mapping from each generated Wasm function to its JavaScript source.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(transparent)]
+pub struct SourceMap {
+    entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMap {
+    /// Push a new entry.
+    pub(crate) fn push(&mut self, entry: SourceMapEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns the entries in the map.
+    pub fn entries(&self) -> &[SourceMapEntry] {
+        &self.entries
+    }
+
+    /// Serializes the map to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use jac_testutil::{inspect_wasm, translation_from_ops};
+use jac_translate::quickpars::{AtomIndex, Opcode};
+use jacc::Compiler;
+
+#[test]
+fn push_atom_value_calls_the_runtime() -> Result<()> {
+    // `return "key"`, where `"key"` is the atom at index 1.
+    let mut translation = translation_from_ops(&[
+        Opcode::PushAtomValue {
+            atom: AtomIndex::from_u32(1),
+        },
+        Opcode::Return,
+    ]);
+    translation.header.atoms.push("key".to_string());
+    let module = inspect_wasm(&Compiler::new(translation).compile()?);
+
+    let function = &module.functions[0];
+    assert_eq!(function.calls, ["jacrt.atom_value"]);
+    // The atom index is passed as an `i32`.
+    assert!(function
+        .operators
+        .iter()
+        .any(|op| op == "I32Const { value: 1 }"));
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, translation_from_ops};
+use jac_translate::{quickpars::Opcode, TranslationBuilder};
+use jacc::Compiler;
+
+#[test]
+fn bigint_arithmetic_uses_regular_opcodes() -> Result<()> {
+    // `MulPow10` and `MathMod` are only emitted for BigFloat literals and
+    // math mode, which plain BigInt arithmetic doesn't need.
+    let bytecode = compile_fixture("let a = 10n ** 20n; let b = a % 7n; console.log(a * b);");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert!(!translation.uses_bignum_opcodes());
+    Ok(())
+}
+
+#[test]
+fn bignum_opcodes_are_detected() -> Result<()> {
+    let translation = translation_from_ops(&[
+        Opcode::PushI8 { val: 3 },
+        Opcode::PushI8 { val: 2 },
+        Opcode::MathMod,
+    ]);
+    assert!(translation.uses_bignum_opcodes());
+    Ok(())
+}
+
+#[test]
+fn bignum_opcodes_warn_without_bignum_runtime() -> Result<()> {
+    let ops = [
+        Opcode::PushI8 { val: 3 },
+        Opcode::PushI8 { val: 2 },
+        Opcode::MulPow10,
+    ];
+
+    let bignum_warnings = |compiler: &Compiler| {
+        compiler
+            .warnings()
+            .iter()
+            .filter(|w| w.contains("BIGNUM"))
+            .count()
+    };
+
+    // The BIGNUM opcodes aren't compiled yet, so the function compiles to
+    // a trap, with its own warning.
+    let mut compiler = Compiler::new(translation_from_ops(&ops));
+    compiler.compile()?;
+    assert_eq!(bignum_warnings(&compiler), 0);
+
+    let mut compiler = Compiler::new(translation_from_ops(&ops)).with_bignum_runtime(false);
+    compiler.compile()?;
+    assert_eq!(bignum_warnings(&compiler), 1);
+    Ok(())
+}
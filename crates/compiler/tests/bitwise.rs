@@ -0,0 +1,68 @@
+use anyhow::Result;
+use common::compile_function;
+use jac_testutil::export_functions;
+use jacc::FLOAT64_TAG_ADDEND;
+use wasmi::{Engine, ExternType, Func, Linker, Module, Store, Val};
+
+mod common;
+
+/// Unboxes a number.
+fn to_f64(value: i64) -> f64 {
+    let value = value as u64;
+    match (value >> 32) as i32 {
+        0 => value as u32 as i32 as f64,
+        _ => f64::from_bits(value.wrapping_add(FLOAT64_TAG_ADDEND)),
+    }
+}
+
+/// Calls the function with the given index with two boxed integers, with
+/// a runtime implementing `to_int32` for integers, and returns its result.
+fn call(wasm: &[u8], index: u32, a: i32, b: i32) -> f64 {
+    let engine = Engine::default();
+    let module = Module::new(&engine, &export_functions(wasm, &[index])).unwrap();
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    for import in module.imports() {
+        let ExternType::Func(ty) = import.ty() else {
+            continue;
+        };
+        let name = import.name().to_string();
+        let func = Func::new(&mut store, ty.clone(), move |_, params, results| {
+            match (name.as_str(), params) {
+                ("to_int32", [Val::I64(value)]) => {
+                    results[0] = Val::I32(to_f64(*value) as i64 as i32);
+                    Ok(())
+                }
+                _ => Err(wasmi::Error::new(format!("unexpected call to `{name}`"))),
+            }
+        });
+        linker.define(import.module(), import.name(), func).unwrap();
+    }
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let f = instance
+        .get_typed_func::<(i64, i64), i64>(&store, &format!("f{index}"))
+        .unwrap();
+    let boxed = |value: i32| value as u32 as i64;
+    to_f64(f.call(&mut store, (boxed(a), boxed(b))).unwrap())
+}
+
+#[test]
+fn bitwise_or_returns_an_int() -> Result<()> {
+    let (wasm, index) = compile_function("function f(a, b) { return a | b; }", "f")?;
+    assert_eq!(call(&wasm, index, 5, 3), 7.0);
+    assert_eq!(call(&wasm, index, -8, 1), -7.0);
+    Ok(())
+}
+
+#[test]
+fn unsigned_shift_returns_a_double_beyond_i32() -> Result<()> {
+    let (wasm, index) = compile_function("function f(a, b) { return a >>> b; }", "f")?;
+    assert_eq!(call(&wasm, index, 8, 1), 4.0);
+    // `-1 >>> 0` doesn't fit in an `i32`, so it's boxed as a double.
+    assert_eq!(call(&wasm, index, -1, 0), 4294967295.0);
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Helpers shared by the integration tests.
+
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::TranslationBuilder;
+use jacc::Compiler;
+
+/// Compiles the source, returning the compiled module and the index of the
+/// function with the given name.
+///
+/// Fails if the function compiles to a trap.
+pub fn compile_function(src: &str, name: &str) -> Result<(Vec<u8>, u32)> {
+    let bytecode = compile_fixture(src);
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation);
+    let wasm = compiler.compile()?;
+    let needle = format!("`{name}`");
+    assert!(
+        !compiler.warnings().iter().any(|w| w.contains(&needle)),
+        "{:?}",
+        compiler.warnings()
+    );
+    let index = compiler
+        .source_map()
+        .entries()
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap()
+        .func_index;
+    Ok((wasm, index))
+}
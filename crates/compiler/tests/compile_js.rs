@@ -0,0 +1,30 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, inspect_wasm};
+use jac_translate::quickpars::VERSION;
+
+#[test]
+fn compiles_one_line_function() -> Result<()> {
+    let wasm = jacc::compile_js("function add(a, b) { return a + b; }", "index.js")?;
+    assert!(wasm.starts_with(b"\0asm"));
+    assert!(!inspect_wasm(&wasm).functions.is_empty());
+    Ok(())
+}
+
+#[test]
+fn empty_bytecode_errors() {
+    let err = jacc::compile(&[]).unwrap_err();
+    assert!(err.to_string().contains("Expected non-empty bytecode"));
+}
+
+#[test]
+fn truncated_bytecode_errors() {
+    let err = jacc::compile(&[VERSION]).unwrap_err();
+    assert!(err.to_string().contains("Expected at least one function"));
+}
+
+#[test]
+fn valid_bytecode_compiles_to_a_module() -> Result<()> {
+    let wasm = jacc::compile(&compile_fixture("function f() { return 1; }"))?;
+    assert!(!wasm.is_empty());
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, inspect_wasm, translation_with_header};
+use jac_translate::{
+    quickpars::{FunctionKind, FunctionSectionHeader, Opcode},
+    TranslationBuilder,
+};
+use jacc::{CompileOptions, Compiler};
+use wasmparser::{Validator, WasmFeatures};
+
+#[test]
+fn disabled_bulk_memory_validates_as_mvp() -> Result<()> {
+    let options = CompileOptions {
+        enable_bulk_memory: false,
+        ..CompileOptions::mvp()
+    };
+    let bytecode = compile_fixture("function f(a, b) {\n  return (a | b) >>> 1;\n}\n");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation).with_options(options);
+    let wasm = compiler.compile()?;
+    // `f` is compiled, rather than replaced by a trap.
+    assert!(!compiler.warnings().iter().any(|w| w.contains("`f`")));
+    assert!(inspect_wasm(&wasm)
+        .functions
+        .iter()
+        .any(|f| f.calls.contains(&"jacrt.to_int32".to_string())));
+
+    let mvp = WasmFeatures::FLOATS;
+    Validator::new_with_features(mvp).validate_all(&wasm)?;
+    Ok(())
+}
+
+#[test]
+fn generators_require_multi_value() -> Result<()> {
+    let header = FunctionSectionHeader::builder()
+        .with_kind(FunctionKind::Generator)
+        .with_stack_size(1);
+    let translation = translation_with_header(header, &[Opcode::Undefined, Opcode::Return]);
+    let err = Compiler::new(translation.clone())
+        .with_options(CompileOptions::mvp())
+        .compile()
+        .expect_err("the generator to be rejected");
+    assert_eq!(
+        err.to_string(),
+        "Function `f` returns 2 values, which requires the multi-value proposal"
+    );
+
+    // Multi-value is the only proposal the generator needs.
+    let wasm = Compiler::new(translation).compile()?;
+    Validator::new_with_features(WasmFeatures::FLOATS | WasmFeatures::MULTI_VALUE)
+        .validate_all(&wasm)?;
+    Ok(())
+}
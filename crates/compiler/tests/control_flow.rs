@@ -0,0 +1,107 @@
+use anyhow::Result;
+use common::compile_function;
+use jac_testutil::{inspect_wasm, WasmFunction, WasmModule};
+
+mod common;
+
+/// Returns the position of the first call to the given import in the
+/// operators of the function.
+fn call_position(module: &WasmModule, function: &WasmFunction, import: &str) -> Option<usize> {
+    let index = module.imports.iter().position(|i| i == import)?;
+    let call = format!("Call {{ function_index: {index} }}");
+    function.operators.iter().position(|op| *op == call)
+}
+
+/// Returns the operator ranges of the loops in the function.
+fn loops(function: &WasmFunction) -> Vec<(usize, usize)> {
+    let mut open = vec![];
+    let mut loops = vec![];
+    for (i, op) in function.operators.iter().enumerate() {
+        if op.starts_with("Block") || op.starts_with("If") {
+            open.push(None);
+        } else if op.starts_with("Loop") {
+            open.push(Some(i));
+        } else if op == "End" {
+            if let Some(Some(start)) = open.pop() {
+                loops.push((start, i));
+            }
+        }
+    }
+    loops
+}
+
+#[test]
+fn for_of_loops_back_and_exits_when_done() -> Result<()> {
+    let (wasm, index) = compile_function(
+        "function sum(a) {\n  let s = 0;\n  for (const x of a) s += x;\n  return s;\n}\n",
+        "sum",
+    )?;
+    let module = inspect_wasm(&wasm);
+    let function = module.function(index);
+    let next = call_position(&module, function, "jacrt.for_of_next").unwrap();
+    let close = call_position(&module, function, "jacrt.iterator_close").unwrap();
+
+    // Stepping the iterator is part of a loop, which conditionally
+    // branches on whether the iteration is done.
+    let (_, end) = loops(function)
+        .into_iter()
+        .find(|(start, end)| (*start..*end).contains(&next))
+        .expect("a loop stepping the iterator");
+    assert!(function.operators[next..end]
+        .iter()
+        .any(|op| op.starts_with("BrIf") || op.starts_with("If") || op.starts_with("BrTable")));
+    // Once done, the iterator is closed.
+    assert!(close > next);
+    Ok(())
+}
+
+#[test]
+fn calls_in_try_blocks_branch_to_the_catch_block() -> Result<()> {
+    let (wasm, index) = compile_function(
+        "function f() {\n  try {\n    g();\n  } catch (e) {\n    return 1;\n  }\n}\n",
+        "f",
+    )?;
+    let module = inspect_wasm(&wasm);
+    let function = module.function(index);
+    let call = call_position(&module, function, "jacrt.call").unwrap();
+    let import = |name: &str| {
+        let index = module.imports.iter().position(|i| i == name).unwrap();
+        format!("Call {{ function_index: {index} }}")
+    };
+
+    // The call is followed by a check for a pending exception, which
+    // conditionally branches to the landing pad that takes the exception
+    // into the catch block.
+    let (pending, op) = function.operators[call + 1..]
+        .iter()
+        .enumerate()
+        .find(|(_, op)| op.starts_with("Call"))
+        .unwrap();
+    assert_eq!(*op, import("jacrt.has_exception"));
+    let after = &function.operators[call + 1 + pending..];
+    let branch = after
+        .iter()
+        .position(|op| op.starts_with("BrIf") || op.starts_with("If"))
+        .unwrap();
+    assert!(!after[1..branch].iter().any(|op| op.starts_with("Call")));
+    assert!(after.contains(&import("jacrt.take_exception")));
+    Ok(())
+}
+
+#[test]
+fn finally_blocks_return_to_each_gosub() -> Result<()> {
+    // `finally` is entered through `GoSub` both after the `try` block and
+    // from the handler, and `Ret` selects where to continue.
+    let (wasm, index) = compile_function(
+        "function f() {\n  try {\n    g();\n  } finally {\n    h();\n  }\n}\n",
+        "f",
+    )?;
+    let module = inspect_wasm(&wasm);
+    let function = module.function(index);
+    assert!(function
+        .operators
+        .iter()
+        .any(|op| op.starts_with("BrTable")));
+    assert!(call_position(&module, function, "jacrt.throw").is_some());
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use jac_testutil::translation_from_ops;
+use jac_translate::quickpars::Opcode;
+use jacc::Compiler;
+
+#[test]
+fn direct_eval_is_rejected() {
+    // The operators of `eval(s)`, simplified.
+    let translation = translation_from_ops(&[
+        Opcode::Undefined,
+        Opcode::GetArg0,
+        Opcode::Eval { scope: 0, argc: 1 },
+        Opcode::Return,
+    ]);
+    let err = Compiler::new(translation).compile().unwrap_err();
+    assert_eq!(err.to_string(), "Direct `eval` is not supported");
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use jac_testutil::{inspect_wasm, translation_with_header};
+use jac_translate::quickpars::{FunctionKind, FunctionSectionHeader, Opcode};
+use jacc::Compiler;
+
+#[test]
+fn compiles_hand_built_function() -> Result<()> {
+    let header = FunctionSectionHeader::builder()
+        .with_kind(FunctionKind::Normal)
+        .with_args(1, 1)
+        .with_stack_size(2);
+    let translation = translation_with_header(
+        header,
+        &[
+            Opcode::PushTrue,
+            Opcode::PushMinus1,
+            Opcode::Xor,
+            Opcode::Return,
+        ],
+    );
+    assert_eq!(translation.module.functions[0].header.local_count, 1);
+
+    let mut compiler = Compiler::new(translation);
+    let module = inspect_wasm(&compiler.compile()?);
+    let entries = compiler.source_map().entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name, "f");
+    assert_eq!(module.function(entries[0].func_index).params, 1);
+    Ok(())
+}
+
+#[test]
+fn defined_args_cannot_exceed_args() {
+    let err = FunctionSectionHeader::builder()
+        .with_args(1, 2)
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("exceeds the argument count"));
+}
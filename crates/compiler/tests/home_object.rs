@@ -0,0 +1,80 @@
+use anyhow::Result;
+use common::compile_function;
+use jac_testutil::{inspect_wasm, translation_from_ops};
+use jac_translate::quickpars::{Opcode, SpecialObjectKind};
+use jacc::Compiler;
+
+mod common;
+
+#[test]
+fn super_call_reads_the_home_object() -> Result<()> {
+    let (wasm, index) = compile_function(
+        "class A { foo() { return 1; } }\n\
+         class B extends A { bar() { return super.foo() + 1; } }\n\
+         new B().bar();",
+        "bar",
+    )?;
+    let module = inspect_wasm(&wasm);
+    let calls = &module.function(index).calls;
+    let position = |name: &str| calls.iter().position(|c| c == name);
+
+    // `super` is resolved from the home object read by `bar`.
+    let home_object = position("jacrt.home_object").expect("a home object read");
+    let get_super = position("jacrt.get_super").expect("a `super` lookup");
+    assert!(home_object < get_super, "{calls:?}");
+    // The home object of the methods is set when the classes are defined.
+    assert!(module
+        .functions
+        .iter()
+        .any(|f| f.calls.iter().any(|c| c == "jacrt.set_home_object")));
+    Ok(())
+}
+
+#[test]
+fn home_object_flows_into_super_access() -> Result<()> {
+    // `return super[""]`, with `true` as `this`, followed by
+    // `super[""] = false` and the definition of a method, `false`, on the
+    // home object `-1`, which is returned.
+    let special = SpecialObjectKind::HomeObject as i32;
+    let ops = [
+        Opcode::PushTrue,
+        Opcode::SpecialObject { argument: special },
+        Opcode::GetSuper,
+        Opcode::PushEmptyString,
+        Opcode::GetSuperValue,
+        Opcode::Drop,
+        Opcode::PushTrue,
+        Opcode::SpecialObject { argument: special },
+        Opcode::GetSuper,
+        Opcode::PushEmptyString,
+        Opcode::PushFalse,
+        Opcode::PutSuperValue,
+        Opcode::PushMinus1,
+        Opcode::PushFalse,
+        Opcode::SetHomeObject,
+        Opcode::Drop,
+        Opcode::Return,
+    ];
+    let mut translation = translation_from_ops(&ops);
+    translation.header.atoms = vec!["method".to_string()];
+    translation.module.functions[0].check_stack_balance()?;
+    let module = inspect_wasm(&Compiler::new(translation).compile()?);
+
+    // Each `super` access resolves the home object first, and every
+    // operator finds its operands on the shadow stack.
+    assert_eq!(
+        module.functions[0].calls,
+        [
+            "jacrt.home_object",
+            "jacrt.get_super",
+            "jacrt.empty_string",
+            "jacrt.get_super_value",
+            "jacrt.home_object",
+            "jacrt.get_super",
+            "jacrt.empty_string",
+            "jacrt.put_super_value",
+            "jacrt.set_home_object",
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, inspect_wasm, read_leb128, FIXTURE_FILENAME};
+use jac_translate::TranslationBuilder;
+use jacc::{Compiler, BYTECODE_LINES_SECTION};
+use wasmparser::{Parser, Payload};
+
+/// Reads a length prefixed string.
+fn read_str<'a>(data: &mut &'a [u8]) -> &'a str {
+    let len = read_leb128(data) as usize;
+    let (s, rest) = data.split_at(len);
+    *data = rest;
+    std::str::from_utf8(s).unwrap()
+}
+
+#[test]
+fn line_table_section_references_defined_functions() -> Result<()> {
+    let bytecode = compile_fixture("function add(a, b) {\n  return a + b;\n}\n");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation);
+    let mut wasm = compiler.compile()?;
+    wasm.extend(compiler.emit_bytecode_lines_section());
+    let module = inspect_wasm(&wasm);
+
+    let mut section = None;
+    for payload in Parser::new(0).parse_all(&wasm) {
+        if let Payload::CustomSection(reader) = payload? {
+            if reader.name() == BYTECODE_LINES_SECTION {
+                section = Some(reader.data().to_vec());
+            }
+        }
+    }
+    let section = section.expect("a bytecode lines section");
+
+    let mut data = section.as_slice();
+    let count = read_leb128(&mut data);
+    assert!(count > 0);
+    for _ in 0..count {
+        let func_index = read_leb128(&mut data);
+        // Every entry describes a function defined by the module.
+        assert!(module.functions.iter().any(|f| f.index == func_index));
+        assert_eq!(read_str(&mut data), FIXTURE_FILENAME);
+        let rows = read_leb128(&mut data);
+        for _ in 0..rows * 3 {
+            read_leb128(&mut data);
+        }
+    }
+    assert!(data.is_empty());
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use common::compile_function;
+use jac_testutil::inspect_wasm;
+
+mod common;
+
+#[test]
+fn length_reads_use_get_length() -> Result<()> {
+    let (wasm, index) = compile_function("function f(a) { return a.length; }", "f")?;
+    let module = inspect_wasm(&wasm);
+    assert_eq!(module.function(index).calls, ["jacrt.get_length"]);
+    Ok(())
+}
+
+#[test]
+fn null_is_a_boxed_constant() -> Result<()> {
+    let (wasm, index) = compile_function("function f() { return null; }", "f")?;
+    let module = inspect_wasm(&wasm);
+    let function = module.function(index);
+    // `null` is materialized without calling into the runtime.
+    assert!(function.calls.is_empty());
+    let null = format!("I64Const {{ value: {} }}", 2i64 << 32);
+    assert!(function.operators.contains(&null));
+    Ok(())
+}
+
+#[test]
+fn element_reads_pass_the_array_and_the_index() -> Result<()> {
+    let (wasm, index) = compile_function("function f(a, i) { return a[i]; }", "f")?;
+    let module = inspect_wasm(&wasm);
+    let function = module.function(index);
+    assert_eq!(function.calls, ["jacrt.get_array_el"]);
+    // The array and the index are the first and second params.
+    let import = module
+        .imports
+        .iter()
+        .position(|i| i == "jacrt.get_array_el")
+        .unwrap();
+    let call = format!("Call {{ function_index: {import} }}");
+    let position = function
+        .operators
+        .iter()
+        .position(|op| *op == call)
+        .unwrap();
+    assert_eq!(
+        function.operators[position - 2..position],
+        ["LocalGet { local_index: 0 }", "LocalGet { local_index: 1 }"]
+    );
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, inspect_wasm};
+use jac_translate::TranslationBuilder;
+use jacc::Compiler;
+
+#[test]
+fn two_arg_function_takes_two_boxed_values() -> Result<()> {
+    let bytecode = compile_fixture("function first(a, b) {\n  return a;\n}\n");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation);
+    let module = inspect_wasm(&compiler.compile()?);
+
+    let entry = compiler
+        .source_map()
+        .entries()
+        .iter()
+        .find(|e| e.name == "first")
+        .unwrap();
+    let function = module.function(entry.func_index);
+    // One NaN-boxed `i64` param per argument, and the NaN-boxed result.
+    assert_eq!(function.params, 2);
+    assert_eq!(function.results, 1);
+    Ok(())
+}
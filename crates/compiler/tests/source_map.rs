@@ -0,0 +1,49 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, inspect_wasm};
+use jac_translate::TranslationBuilder;
+use jacc::Compiler;
+
+#[test]
+fn source_map_entries_refer_to_emitted_functions() -> Result<()> {
+    let bytecode = compile_fixture("function add(a, b) {\n  return a + b;\n}\nadd(1, 2);\n");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let function_count = translation.module.functions.len();
+    let mut compiler = Compiler::new(translation);
+    let wasm = compiler.compile()?;
+    let module = inspect_wasm(&wasm);
+
+    let entries = compiler.source_map().entries();
+    assert_eq!(entries.len(), function_count);
+    assert_eq!(module.functions.len(), function_count);
+    for (entry, function) in entries.iter().zip(&module.functions) {
+        assert_eq!(entry.func_index, function.index);
+    }
+
+    let add = entries.iter().find(|e| e.name == "add").unwrap();
+    assert_eq!(add.line, Some(1));
+    // `add` returns the sum of its two arguments.
+    let function = module.function(add.func_index);
+    assert_eq!((function.params, function.results), (2, 1));
+    assert_eq!(function.calls, ["jacrt.add"]);
+    Ok(())
+}
+
+#[test]
+fn source_map_names_match_the_symbol_map() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function add(a, b) { return a + b; }\n[1, 2].map(function (x) { return add(x, 1); });",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation);
+    compiler.compile()?;
+
+    let symbols = compiler
+        .source_map()
+        .entries()
+        .iter()
+        .map(|e| format!("{}:{}\n", e.func_index, e.name))
+        .collect::<String>();
+    assert_eq!(symbols, compiler.emit_symbol_map());
+    assert!(symbols.contains(":lambda_fn_0\n"));
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, inspect_wasm};
+use jac_translate::TranslationBuilder;
+use jacc::Compiler;
+
+#[test]
+fn symbol_map_has_one_line_per_function() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function add(a, b) { return a + b; }\n[1, 2].map(function (x) { return add(x, 1); });",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let mut compiler = Compiler::new(translation);
+    let module = inspect_wasm(&compiler.compile()?);
+    let map = compiler.emit_symbol_map();
+
+    let entries = map
+        .lines()
+        .map(|line| {
+            let (index, name) = line.split_once(':').unwrap();
+            (index.parse::<u32>().unwrap(), name.to_string())
+        })
+        .collect::<Vec<_>>();
+    // One line per function defined by the compiled module.
+    assert_eq!(entries.len(), module.functions.len());
+    for ((index, _), function) in entries.iter().zip(&module.functions) {
+        assert_eq!(*index, function.index);
+    }
+    assert!(entries.iter().any(|(_, name)| name == "add"));
+    assert!(entries.iter().any(|(_, name)| name == "lambda_fn_0"));
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use jac_testutil::translation_from_ops;
+use jac_translate::quickpars::{AtomIndex, Opcode};
+use jacc::Compiler;
+
+#[test]
+fn with_statements_are_rejected() {
+    // The operators of `with (obj) { x }`, which can't be compiled from
+    // source, since modules are always strict.
+    let mut translation = translation_from_ops(&[
+        Opcode::GetArg0,
+        Opcode::ToObject,
+        Opcode::WithGetVar {
+            atom: AtomIndex::from_u32(1),
+            diff: 5,
+            is_with: 1,
+        },
+        Opcode::GetVar {
+            atom: AtomIndex::from_u32(1),
+        },
+        Opcode::Drop,
+        Opcode::ReturnUndef,
+    ]);
+    translation.header.atoms.push("x".to_string());
+    let err = Compiler::new(translation).compile().unwrap_err();
+    assert_eq!(err.to_string(), "`with` statements are not supported");
+}
@@ -0,0 +1,50 @@
+//! Chrome trace export.
+//!
+//! Traces are rendered in the [Trace Event Format], which can be loaded in
+//! `chrome://tracing` or Perfetto to visualize the function invocations as a
+//! timeline.
+//!
+//! [Trace Event Format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+
+use anyhow::{ensure, Result};
+use serde_json::{json, Value};
+
+/// A completed function invocation in the timeline.
+#[derive(Debug, Clone)]
+pub(crate) struct Span {
+    /// The function name.
+    pub name: String,
+    /// The fuel consumed by the trace before the invocation.
+    pub start_fuel: u64,
+    /// The fuel consumed by the invocation, including its callees.
+    pub fuel: u64,
+}
+
+/// Renders the invocations as Chrome trace complete events.
+///
+/// Fuel is converted to microseconds by dividing it by `fuel_per_us`; the
+/// scale only affects the visualization of the timeline, not the fuel
+/// accounting of the trace.
+pub(crate) fn to_chrome_trace(spans: &[Span], fuel_per_us: f64) -> Result<String> {
+    ensure!(
+        fuel_per_us.is_finite() && fuel_per_us > 0.0,
+        "Expected a positive fuel per microsecond, found {}",
+        fuel_per_us
+    );
+    let events = spans
+        .iter()
+        .map(|span| {
+            json!({
+                "name": span.name,
+                "ph": "X",
+                "ts": span.start_fuel as f64 / fuel_per_us,
+                "dur": span.fuel as f64 / fuel_per_us,
+                "pid": 1,
+                "tid": 1,
+                "args": { "fuel": span.fuel },
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    Ok(json!({ "traceEvents": events }).to_string())
+}
@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// Errors produced while processing an execution trace.
+///
+/// Errors returned by this crate can be downcast to [TraceError] to tell
+/// problems with the trace apart from problems with the bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceError {
+    /// A line of the trace that doesn't follow the trace format.
+    MalformedEvent {
+        /// The line number in the trace, starting at `1` for the header.
+        line: usize,
+        /// The contents of the line.
+        content: String,
+    },
+    /// The native calls of an opcode are not properly nested, e.g., a wasm
+    /// function ends without having started.
+    UnbalancedNativeCalls,
+    /// A matched function executed an opcode that doesn't exist in the
+    /// bytecode, which indicates that the trace was produced from different
+    /// bytecode.
+    BytecodeMismatch,
+    /// Traces being merged were parsed against different bytecode.
+    DifferentBytecode,
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::MalformedEvent { line, content } => {
+                write!(f, "Malformed trace event at line {}: {}", line, content)
+            }
+            TraceError::UnbalancedNativeCalls => write!(f, "Unbalanced native calls in trace"),
+            TraceError::BytecodeMismatch => {
+                write!(f, "The trace doesn't match the bytecode")
+            }
+            TraceError::DifferentBytecode => {
+                write!(f, "The traces were parsed against different bytecode")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
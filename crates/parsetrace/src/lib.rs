@@ -1,23 +1,114 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrome::{to_chrome_trace, Span};
+pub use error::TraceError;
 use jac_translate::{
     quickpars::{ClosureVarIndex, FuncIndex, LocalIndex},
     Translation, TranslationBuilder,
 };
-use quickpars::Opcode;
+use quickpars::{debug_escape, Opcode};
+use serde_json::json;
+pub use stats::{opcode_stats_to_markdown, OpStat};
+pub use summary::{to_csv, to_markdown, FunctionSummary};
 use trace::BytecodeTraceEvent;
+pub use trace::WasmCallFrame;
 use utils::{generate_trace, match_all_functions, recover_bytecodes};
+mod chrome;
+mod error;
+mod stats;
+mod summary;
 mod trace;
 mod utils;
 
 /// Produces a human readable report from QuickJS bytecode and a raw execution
 /// trace.
 pub fn trace(bytecode: &[u8], raw_trace: &str) -> Result<Vec<String>> {
-    let builder = TranslationBuilder::new();
-    let translation = builder.translate(bytecode)?;
-    let trace_parser = ProfileTraceParser::new(raw_trace, &translation)?;
-    Ok(trace_parser.report_trace().unwrap_or_default())
+    trace_with(bytecode, raw_trace, &ReportOptions::default())
+}
+
+/// Same as [trace], configured through [ReportOptions].
+pub fn trace_with(
+    bytecode: &[u8],
+    raw_trace: &str,
+    options: &ReportOptions,
+) -> Result<Vec<String>> {
+    let mut trace_parser = ProfileTraceParser::from_bytecode(raw_trace, bytecode)?;
+    if options.merge_intrinsics {
+        trace_parser.merge_intrinsics();
+    }
+    trace_parser.report_trace_with(options)
+}
+
+/// Produces the trace as JSON lines, one JSON object per trace event, see
+/// [ProfileTraceParser::json_lines].
+pub fn trace_json_lines(bytecode: &[u8], raw_trace: &str) -> Result<Vec<String>> {
+    let trace_parser = ProfileTraceParser::from_bytecode(raw_trace, bytecode)?;
+    Ok(trace_parser.json_lines().collect())
+}
+
+/// Options of the trace report.
+#[derive(Debug, Default, Clone)]
+pub struct ReportOptions {
+    /// Prefix each line with the total fuel consumed up to, and including,
+    /// the event.
+    pub cumulative: bool,
+    /// Prefix the lines of opcodes with their offset in the trace.
+    pub offsets: bool,
+    /// Name intrinsic functions with identical recovered opcodes alike, see
+    /// [ProfileTraceParser::merge_intrinsics].
+    pub merge_intrinsics: bool,
+    /// The order of the report lines.
+    pub sort_by: ReportOrder,
+    /// Suffix the fuel of each opcode line with its percentage of the fuel
+    /// consumed by all the opcodes of the same function in the trace.
+    pub pct_per_line: bool,
+}
+
+/// The order of the lines of the trace report.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReportOrder {
+    /// Every event, in execution order, indented by call depth.
+    #[default]
+    Time,
+    /// Only the opcode executions, without indentation, sorted by fuel in
+    /// descending order. Opcodes with the same fuel keep their execution
+    /// order.
+    Fuel,
+}
+
+/// Produces the per function summary from QuickJS bytecode and a raw
+/// execution trace.
+pub fn summary(bytecode: &[u8], raw_trace: &str) -> Result<Vec<FunctionSummary>> {
+    summary_with(bytecode, raw_trace, &ReportOptions::default())
+}
+
+/// Same as [summary], configured through [ReportOptions].
+pub fn summary_with(
+    bytecode: &[u8],
+    raw_trace: &str,
+    options: &ReportOptions,
+) -> Result<Vec<FunctionSummary>> {
+    let mut trace_parser = ProfileTraceParser::from_bytecode(raw_trace, bytecode)?;
+    if options.merge_intrinsics {
+        trace_parser.merge_intrinsics();
+    }
+    Ok(trace_parser.function_summary())
+}
+
+/// Produces a Chrome trace of the function invocations from QuickJS bytecode
+/// and a raw execution trace, scaling fuel to microseconds by `fuel_per_us`.
+pub fn chrome_trace(bytecode: &[u8], raw_trace: &str, fuel_per_us: f64) -> Result<String> {
+    let trace_parser = ProfileTraceParser::from_bytecode(raw_trace, bytecode)?;
+    trace_parser.chrome_trace(fuel_per_us)
+}
+
+/// Produces the per opcode fuel statistics from QuickJS bytecode and a raw
+/// execution trace.
+pub fn opcode_stats(bytecode: &[u8], raw_trace: &str) -> Result<BTreeMap<String, OpStat>> {
+    let trace_parser = ProfileTraceParser::from_bytecode(raw_trace, bytecode)?;
+    Ok(trace_parser.opcode_fuel_stats())
 }
 
 /// Represents all the profiled opcode bytes for a single function, ordered by their offset.
@@ -28,6 +119,37 @@ type ProfiledOpcodeList = Vec<(u32, u8)>;
 /// value contains (idx of matched function in js module, opcode_offset to [OpcodeList] idx pairs)
 type MatchedFuncInfo = (u32, HashMap<u32, u32>);
 
+/// The minimum fraction of recovered opcodes expected to match the bytecode.
+///
+/// Recovered opcodes belonging to intrinsic functions never match, so some
+/// amount of unmatched opcodes is expected; lower match rates usually indicate
+/// that the trace was produced from different bytecode or by a different
+/// QuickJS build.
+pub const MIN_MATCH_RATE: f64 = 0.5;
+
+/// The result of validating a trace against the bytecode.
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    /// The number of distinct opcodes recovered from the trace.
+    pub recovered_opcodes: usize,
+    /// The number of recovered opcodes matched to an opcode with the same
+    /// offset and byte in the bytecode.
+    pub matched_opcodes: usize,
+}
+
+impl ValidationReport {
+    /// The fraction of recovered opcodes that matched the bytecode.
+    pub fn match_rate(&self) -> f64 {
+        self.matched_opcodes as f64 / self.recovered_opcodes as f64
+    }
+
+    /// Whether the match rate is below [MIN_MATCH_RATE], which indicates
+    /// a mismatch between the trace and the bytecode.
+    pub fn is_suspicious(&self) -> bool {
+        self.match_rate() < MIN_MATCH_RATE
+    }
+}
+
 /// Parser for the wasm-based quickjs bytecode profile trace.
 ///
 /// This parser takes the raw execution trace and the parsed JS bytecode module, then
@@ -37,22 +159,44 @@ type MatchedFuncInfo = (u32, HashMap<u32, u32>);
 pub struct ProfileTraceParser<'a> {
     /// Fully parsed execution trace events.
     trace: Vec<BytecodeTraceEvent>,
-    /// In-memory representation of QuickJS bytecode.
-    translation: &'a Translation<'a>,
+    /// In-memory representation of QuickJS bytecode, either borrowed from
+    /// the caller or translated by the parser.
+    translation: Cow<'a, Translation<'a>>,
     /// Maps recovered functions to the user defined functions in the JS module.
     matched_functions: HashMap<u32, MatchedFuncInfo>,
     /// Maps recovered intrinsic function ids to their default names (intrinsic_fn_#id).
     intrinsic_fn_names: HashMap<u32, String>,
+    /// Maps merged intrinsic function ids to the id of the first intrinsic
+    /// function with the same recovered opcodes.
+    merged_intrinsics: HashMap<u32, u32>,
     /// Per function operator metadata.
     operator_metadata: HashMap<u32, Vec<(u32, Opcode)>>,
+    /// The profiled opcodes of each recovered function.
+    recovered_opcodes: HashMap<u32, ProfiledOpcodeList>,
 }
 
 impl<'a> ProfileTraceParser<'a> {
+    /// Creates a parser from a raw execution trace and the translation of the
+    /// traced bytecode.
     pub fn new(raw_execution_trace: &str, translation: &'a Translation) -> Result<Self> {
+        Self::with_translation(raw_execution_trace, Cow::Borrowed(translation))
+    }
+
+    /// Creates a parser from a raw execution trace and the traced bytecode,
+    /// translating the bytecode.
+    pub fn from_bytecode(raw_execution_trace: &str, bytecode: &'a [u8]) -> Result<Self> {
+        let translation = TranslationBuilder::new().translate(bytecode)?;
+        Self::with_translation(raw_execution_trace, Cow::Owned(translation))
+    }
+
+    fn with_translation(
+        raw_execution_trace: &str,
+        translation: Cow<'a, Translation<'a>>,
+    ) -> Result<Self> {
         let mut operator_metadata = HashMap::new();
         for func in &translation.module.functions {
             let mut operators = vec![];
-            let mut reader = func.operators.clone();
+            let mut reader = func.operators_reader();
             while !reader.done() {
                 if let Ok(op) = Opcode::from_reader(&mut reader) {
                     operators.push(op);
@@ -61,29 +205,484 @@ impl<'a> ProfileTraceParser<'a> {
             operator_metadata.insert(func.index.as_u32(), operators);
         }
 
-        let trace = generate_trace(raw_execution_trace);
-        let recovered_opcodes = recover_bytecodes(&trace);
-        let matched_functions = match_all_functions(&operator_metadata, &recovered_opcodes);
-        let intrinsic_fn_names = recovered_opcodes
+        let mut parser = Self {
+            trace: generate_trace(raw_execution_trace)?,
+            translation,
+            matched_functions: Default::default(),
+            intrinsic_fn_names: Default::default(),
+            merged_intrinsics: Default::default(),
+            operator_metadata,
+            recovered_opcodes: Default::default(),
+        };
+        parser.match_functions();
+        Ok(parser)
+    }
+
+    /// Appends the events of another trace, parsed against the same
+    /// bytecode, so that reports and summaries aggregate both traces.
+    ///
+    /// Recovered function ids are assumed to be stable across traces, i.e.,
+    /// the traces were produced by the same QuickJS build. Functions are
+    /// matched again, since each trace may execute different opcodes.
+    pub fn merge(&mut self, other: &ProfileTraceParser) -> Result<()> {
+        if self.operator_metadata != other.operator_metadata
+            || self.translation.header.atoms != other.translation.header.atoms
+        {
+            bail!(TraceError::DifferentBytecode);
+        }
+        self.trace.extend(other.trace.iter().cloned());
+        let merge_intrinsics = !self.merged_intrinsics.is_empty();
+        self.match_functions();
+        if merge_intrinsics {
+            self.merge_intrinsics();
+        }
+        Ok(())
+    }
+
+    /// Matches the recovered functions of the trace to the functions in the
+    /// bytecode, naming the unmatched ones as intrinsic functions.
+    fn match_functions(&mut self) {
+        self.recovered_opcodes = recover_bytecodes(&self.trace);
+        self.matched_functions =
+            match_all_functions(&self.operator_metadata, &self.recovered_opcodes);
+        // Sort the unmatched functions so that intrinsic names are stable
+        // across runs.
+        let mut unmatched = self
+            .recovered_opcodes
             .keys()
-            .filter(|k| !matched_functions.contains_key(k))
+            .filter(|k| !self.matched_functions.contains_key(k))
+            .collect::<Vec<_>>();
+        unmatched.sort_unstable();
+        self.intrinsic_fn_names = unmatched
+            .into_iter()
             .enumerate()
             .map(|(idx, k)| (*k, format!("intrinsic_fn_{}", idx)))
             .collect::<HashMap<_, _>>();
+        self.merged_intrinsics = Default::default();
+    }
 
-        Ok(Self {
-            trace,
-            translation,
-            matched_functions,
-            intrinsic_fn_names,
-            operator_metadata,
+    /// Gives intrinsic functions with identical recovered opcodes the same
+    /// name, and aggregates them as a single function in the summary.
+    ///
+    /// The same builtin often shows up under multiple recovered function
+    /// ids; this is a heuristic, since different builtins could execute
+    /// the same opcodes.
+    pub fn merge_intrinsics(&mut self) {
+        let mut unmatched = self.intrinsic_fn_names.keys().copied().collect::<Vec<_>>();
+        unmatched.sort_unstable();
+        let mut groups: Vec<(&ProfiledOpcodeList, u32)> = vec![];
+        let mut names = HashMap::new();
+        let mut merged = HashMap::new();
+        for id in unmatched {
+            let opcodes = &self.recovered_opcodes[&id];
+            match groups.iter().position(|(o, _)| *o == opcodes) {
+                Some(group) => {
+                    merged.insert(id, groups[group].1);
+                    names.insert(id, format!("intrinsic_fn_{}", group));
+                }
+                None => {
+                    names.insert(id, format!("intrinsic_fn_{}", groups.len()));
+                    groups.push((opcodes, id));
+                }
+            }
+        }
+        self.intrinsic_fn_names = names;
+        self.merged_intrinsics = merged;
+    }
+
+    /// Validates that the trace is consistent with the bytecode, by
+    /// computing how many of the recovered opcodes matched an opcode in the
+    /// bytecode.
+    pub fn validate(&self) -> Result<ValidationReport> {
+        let recovered_opcodes = self.recovered_opcodes.values().map(Vec::len).sum();
+        if recovered_opcodes == 0 {
+            bail!("The trace doesn't contain any opcode events");
+        }
+        let matched_opcodes = self
+            .matched_functions
+            .values()
+            .map(|(_, opcode_idx_map)| opcode_idx_map.len())
+            .sum();
+
+        Ok(ValidationReport {
+            recovered_opcodes,
+            matched_opcodes,
+        })
+    }
+
+    /// Resolves the name of a recovered function: the name of the matched
+    /// JS function, or its `intrinsic_fn_N` name otherwise.
+    ///
+    /// Returns `None` if the function didn't execute any opcode in the trace.
+    pub fn resolve_recovered(&self, recovered_func_id: u32) -> Option<String> {
+        match self.matched_functions.get(&recovered_func_id) {
+            Some((js_func_idx, _)) => self
+                .translation
+                .try_resolve_func_name(FuncIndex::from_u32(*js_func_idx), None)
+                .ok()
+                .map(str::to_string),
+            None => self.intrinsic_fn_names.get(&recovered_func_id).cloned(),
+        }
+    }
+
+    /// Aggregates the fuel consumption and calls of each function in the
+    /// trace, sorted by self fuel in descending order.
+    ///
+    /// Fuel that can't be attributed to a function is not accounted for.
+    pub fn function_summary(&self) -> Vec<FunctionSummary> {
+        let mut summaries: HashMap<u32, FunctionSummary> = HashMap::new();
+        let mut stack: Vec<u32> = vec![];
+        for event in &self.trace {
+            let (recovered_func_id, fuel) = match event {
+                BytecodeTraceEvent::FunctionStart(recovered_func_id) => {
+                    stack.push(*recovered_func_id);
+                    self.summary_entry(&mut summaries, *recovered_func_id).calls += 1;
+                    continue;
+                }
+                BytecodeTraceEvent::FunctionEnd(_) => {
+                    stack.pop();
+                    continue;
+                }
+                BytecodeTraceEvent::OpcodeRun {
+                    recovered_func_id,
+                    fuel_consumption,
+                    ..
+                }
+                | BytecodeTraceEvent::FunctionSetup {
+                    recovered_func_id,
+                    fuel_consumption,
+                } => (*recovered_func_id, *fuel_consumption as u64),
+                BytecodeTraceEvent::SystemSetup(_) => continue,
+            };
+
+            self.summary_entry(&mut summaries, recovered_func_id)
+                .self_fuel += fuel;
+            // Account the fuel once per active function, so that recursive
+            // calls are not counted multiple times.
+            let mut active = stack
+                .iter()
+                .map(|id| self.representative(*id))
+                .collect::<Vec<_>>();
+            active.push(self.representative(recovered_func_id));
+            active.sort_unstable();
+            active.dedup();
+            for id in active {
+                self.summary_entry(&mut summaries, id).total_fuel += fuel;
+            }
+        }
+
+        let mut summary = summaries.into_values().collect::<Vec<_>>();
+        summary.sort_by(|a, b| {
+            b.self_fuel
+                .cmp(&a.self_fuel)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        summary
+    }
+
+    /// Aggregates the fuel consumption of each opcode kind in the trace,
+    /// keyed by mnemonic.
+    ///
+    /// Unlike the report, opcodes of unmatched functions are accounted for,
+    /// since the mnemonic only depends on the opcode byte.
+    pub fn opcode_fuel_stats(&self) -> BTreeMap<String, OpStat> {
+        let mut stats: BTreeMap<String, OpStat> = BTreeMap::new();
+        for event in &self.trace {
+            if let BytecodeTraceEvent::OpcodeRun {
+                opcode_byte,
+                fuel_consumption,
+                ..
+            } = event
+            {
+                stats
+                    .entry(Opcode::name_from_byte(*opcode_byte))
+                    .or_default()
+                    .record(*fuel_consumption as u64);
+            }
+        }
+        stats
+    }
+
+    /// Renders the function invocations in the trace as a Chrome trace,
+    /// using the fuel consumed so far as the clock.
+    ///
+    /// Fuel is scaled to microseconds by `fuel_per_us`, which only affects
+    /// the visualization. Invocations that don't end in the trace are not
+    /// rendered.
+    pub fn chrome_trace(&self, fuel_per_us: f64) -> Result<String> {
+        let mut spans = vec![];
+        let mut open: Vec<(u32, u64)> = vec![];
+        let mut fuel = 0u64;
+        for event in &self.trace {
+            match event {
+                BytecodeTraceEvent::FunctionStart(recovered_func_id) => {
+                    open.push((*recovered_func_id, fuel));
+                }
+                BytecodeTraceEvent::FunctionEnd(_) => {
+                    if let Some((recovered_func_id, start_fuel)) = open.pop() {
+                        spans.push(Span {
+                            name: self.recovered_name(recovered_func_id),
+                            start_fuel,
+                            fuel: fuel - start_fuel,
+                        });
+                    }
+                }
+                BytecodeTraceEvent::OpcodeRun {
+                    fuel_consumption, ..
+                }
+                | BytecodeTraceEvent::FunctionSetup {
+                    fuel_consumption, ..
+                }
+                | BytecodeTraceEvent::SystemSetup(fuel_consumption) => {
+                    fuel += *fuel_consumption as u64;
+                }
+            }
+        }
+
+        to_chrome_trace(&spans, fuel_per_us)
+    }
+
+    /// Renders each trace event as a single line JSON object with the
+    /// `type`, `function`, `offset`, `opcode`, `fuel` and `depth` of the
+    /// event, lazily, in trace order.
+    ///
+    /// Fields that don't apply to an event, e.g., the opcode of a function
+    /// start, are `null`.
+    pub fn json_lines(&self) -> impl Iterator<Item = String> + use<'_, 'a> {
+        self.trace.iter().scan(0usize, |depth, event| {
+            let value = match event {
+                BytecodeTraceEvent::OpcodeRun {
+                    recovered_func_id,
+                    opcode_offset,
+                    opcode_byte,
+                    fuel_consumption,
+                    ..
+                } => json!({
+                    "type": "opcode",
+                    "function": self.recovered_name(*recovered_func_id),
+                    "offset": opcode_offset,
+                    "opcode": Opcode::name_from_byte(*opcode_byte),
+                    "fuel": fuel_consumption,
+                    "depth": *depth,
+                }),
+                BytecodeTraceEvent::FunctionStart(recovered_func_id) => {
+                    *depth += 1;
+                    json!({
+                        "type": "function_start",
+                        "function": self.recovered_name(*recovered_func_id),
+                        "offset": null,
+                        "opcode": null,
+                        "fuel": 0,
+                        "depth": *depth - 1,
+                    })
+                }
+                BytecodeTraceEvent::FunctionEnd(recovered_func_id) => {
+                    *depth = depth.saturating_sub(1);
+                    json!({
+                        "type": "function_end",
+                        "function": self.recovered_name(*recovered_func_id),
+                        "offset": null,
+                        "opcode": null,
+                        "fuel": 0,
+                        "depth": *depth,
+                    })
+                }
+                BytecodeTraceEvent::FunctionSetup {
+                    recovered_func_id,
+                    fuel_consumption,
+                } => json!({
+                    "type": "function_setup",
+                    "function": self.recovered_name(*recovered_func_id),
+                    "offset": null,
+                    "opcode": null,
+                    "fuel": fuel_consumption,
+                    "depth": *depth,
+                }),
+                BytecodeTraceEvent::SystemSetup(fuel_consumption) => json!({
+                    "type": "system_setup",
+                    "function": null,
+                    "offset": null,
+                    "opcode": null,
+                    "fuel": fuel_consumption,
+                    "depth": *depth,
+                }),
+            };
+            Some(value.to_string())
+        })
+    }
+
+    /// Aggregates the self fuel of each native wasm function across the
+    /// native calls of every opcode in the trace, as
+    /// `(wasm_func_id, self_fuel)` pairs sorted by fuel in descending order.
+    ///
+    /// The native calls are parsed into call frames one opcode at a time, so
+    /// only the frames of a single opcode are alive at any point.
+    pub fn native_hotspots(&self) -> Result<Vec<(u32, u32)>> {
+        fn visit(frame: &WasmCallFrame, fuel: &mut HashMap<u32, u32>) {
+            *fuel.entry(frame.wasm_func_id).or_default() += frame.self_fuel();
+            for call in &frame.calls {
+                visit(call, fuel);
+            }
+        }
+
+        let mut fuel = HashMap::new();
+        for native_calls in self.raw_native_calls() {
+            for frame in WasmCallFrame::parse(native_calls)? {
+                visit(&frame, &mut fuel);
+            }
+        }
+
+        let mut hotspots = fuel.into_iter().collect::<Vec<_>>();
+        hotspots.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(hotspots)
+    }
+
+    /// Returns the raw native calls segment of each opcode execution, in
+    /// trace order, to be parsed on demand with [WasmCallFrame::parse].
+    ///
+    /// Opcodes without native calls yield an empty segment.
+    pub fn raw_native_calls(&self) -> impl Iterator<Item = &str> + '_ {
+        self.trace.iter().filter_map(|event| match event {
+            BytecodeTraceEvent::OpcodeRun { native_calls, .. } => Some(&**native_calls),
+            _ => None,
         })
     }
 
-    pub fn report_trace(&self) -> Option<Vec<String>> {
+    /// Sums the fuel of the opcodes executed by each recovered function.
+    fn opcode_fuel_per_function(&self) -> HashMap<u32, u64> {
+        let mut fuel = HashMap::new();
+        for event in &self.trace {
+            if let BytecodeTraceEvent::OpcodeRun {
+                recovered_func_id,
+                fuel_consumption,
+                ..
+            } = event
+            {
+                *fuel.entry(*recovered_func_id).or_default() += *fuel_consumption as u64;
+            }
+        }
+        fuel
+    }
+
+    /// Counts the tail calls executed by each function, as
+    /// `(func_name, count)` pairs sorted by count in descending order.
+    pub fn tail_calls(&self) -> Vec<(String, u32)> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for event in &self.trace {
+            if let BytecodeTraceEvent::OpcodeRun {
+                recovered_func_id,
+                opcode_byte,
+                ..
+            } = event
+            {
+                if is_tail_call(*opcode_byte) {
+                    *counts
+                        .entry(self.recovered_name(*recovered_func_id))
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        let mut tail_calls = counts.into_iter().collect::<Vec<_>>();
+        tail_calls.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tail_calls
+    }
+
+    /// Returns the opcodes of the matched functions that never executed in
+    /// the trace, as `(func_name, offset)` pairs sorted by function index
+    /// and offset.
+    ///
+    /// Functions that didn't match any recovered function, e.g., because
+    /// they never ran, are not reported.
+    pub fn uncovered_opcodes(&self) -> Vec<(String, u32)> {
+        let mut matched = self.matched_functions.values().collect::<Vec<_>>();
+        matched.sort_unstable_by_key(|(js_func_idx, _)| *js_func_idx);
+        let mut uncovered = vec![];
+        for (js_func_idx, opcode_idx_map) in matched {
+            let Some(operators) = self.operator_metadata.get(js_func_idx) else {
+                continue;
+            };
+            let name = self
+                .translation
+                .try_resolve_func_name(FuncIndex::from_u32(*js_func_idx), None)
+                .unwrap_or("<invalid function>");
+            let covered = opcode_idx_map.values().collect::<HashSet<_>>();
+            uncovered.extend(
+                operators
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !covered.contains(&(*idx as u32)))
+                    .map(|(_, (offset, _))| (name.to_string(), *offset)),
+            );
+        }
+        uncovered
+    }
+
+    /// Same as [ProfileTraceParser::resolve_recovered], falling back to
+    /// `unknown_fn_N` for functions that didn't execute any opcode.
+    fn recovered_name(&self, recovered_func_id: u32) -> String {
+        self.resolve_recovered(recovered_func_id)
+            .unwrap_or_else(|| format!("unknown_fn_{}", recovered_func_id))
+    }
+
+    /// Returns the id under which a recovered function is aggregated, which
+    /// differs from its own id only for merged intrinsic functions.
+    fn representative(&self, recovered_func_id: u32) -> u32 {
+        self.merged_intrinsics
+            .get(&recovered_func_id)
+            .copied()
+            .unwrap_or(recovered_func_id)
+    }
+
+    /// Returns the summary of a recovered function, creating it if needed.
+    fn summary_entry<'s>(
+        &self,
+        summaries: &'s mut HashMap<u32, FunctionSummary>,
+        recovered_func_id: u32,
+    ) -> &'s mut FunctionSummary {
+        let recovered_func_id = self.representative(recovered_func_id);
+        summaries
+            .entry(recovered_func_id)
+            .or_insert_with(|| FunctionSummary::new(self.recovered_name(recovered_func_id)))
+    }
+
+    pub fn report_trace(&self) -> Result<Vec<String>> {
+        self.report_trace_with(&ReportOptions::default())
+    }
+
+    /// Produces the report, configured through [ReportOptions].
+    ///
+    /// Functions entered through a tail call are reported at the depth of
+    /// their caller, since they replace the caller's frame, so tail
+    /// recursion doesn't deepen the report.
+    ///
+    /// Errors with [TraceError::BytecodeMismatch] if a matched function
+    /// executed an opcode that can't be found in the bytecode.
+    pub fn report_trace_with(&self, options: &ReportOptions) -> Result<Vec<String>> {
+        let flatten = options.sort_by == ReportOrder::Fuel;
         let mut call_depth = 0;
+        // Whether each active function was entered through a tail call,
+        // which replaces the caller's frame instead of nesting in it.
+        let mut tail_frames: Vec<bool> = vec![];
+        let mut after_tail_call = false;
+        let function_fuel = if options.pct_per_line {
+            self.opcode_fuel_per_function()
+        } else {
+            HashMap::new()
+        };
+        let mut cumulative_fuel = 0u64;
         let mut output = vec![];
         for event in &self.trace {
+            cumulative_fuel += match event {
+                BytecodeTraceEvent::OpcodeRun {
+                    fuel_consumption, ..
+                }
+                | BytecodeTraceEvent::FunctionSetup {
+                    fuel_consumption, ..
+                }
+                | BytecodeTraceEvent::SystemSetup(fuel_consumption) => *fuel_consumption as u64,
+                _ => 0,
+            };
             let entry_report = match event {
                 BytecodeTraceEvent::OpcodeRun {
                     recovered_func_id,
@@ -92,11 +691,23 @@ impl<'a> ProfileTraceParser<'a> {
                     opcode_byte,
                     ..
                 } => {
-                    if let Some((js_func_idx, opcode_idx_map)) =
+                    let pct = match function_fuel.get(recovered_func_id) {
+                        Some(total) if *total > 0 => {
+                            format!(
+                                " ({:.1}%)",
+                                *fuel_consumption as f64 * 100.0 / *total as f64
+                            )
+                        }
+                        Some(_) => " (0.0%)".to_string(),
+                        None => String::new(),
+                    };
+                    let opcode_str = if let Some((js_func_idx, opcode_idx_map)) =
                         self.matched_functions.get(recovered_func_id)
                     {
-                        let opcode_idx = opcode_idx_map.get(opcode_offset)?;
-                        let (offset, opcode) = self
+                        let opcode_idx = opcode_idx_map
+                            .get(opcode_offset)
+                            .ok_or(TraceError::BytecodeMismatch)?;
+                        let (_, opcode) = self
                             .translation
                             .module
                             .functions
@@ -106,69 +717,54 @@ impl<'a> ProfileTraceParser<'a> {
                                     .get(&f.index.as_u32())
                                     .map(|f| f.get(*opcode_idx as usize))
                                     .unwrap()
-                            })?;
-                        let opcode_str = report(
-                            *offset,
-                            FuncIndex::from_u32(*js_func_idx),
-                            &self.translation,
-                            opcode,
-                        );
-                        Some(format!(
-                            "{:indent$}{} fuel_cost: {}",
-                            "",
-                            opcode_str,
-                            fuel_consumption,
-                            indent = call_depth * 2
-                        ))
+                            })
+                            .ok_or(TraceError::BytecodeMismatch)?;
+                        report(FuncIndex::from_u32(*js_func_idx), &self.translation, opcode)
                     } else {
-                        Some(format!(
-                            "{:indent$}{}: {} fuel_cost: {}",
-                            "",
-                            opcode_offset,
-                            Opcode::name_from_byte(*opcode_byte),
-                            fuel_consumption,
-                            indent = call_depth * 2
-                        ))
-                    }
+                        Opcode::name_from_byte(*opcode_byte).to_string()
+                    };
+                    let prefix = if options.offsets {
+                        format!("{}: ", opcode_offset)
+                    } else {
+                        String::new()
+                    };
+                    Some(format!(
+                        "{:indent$}{}{} fuel_cost: {}{}",
+                        "",
+                        prefix,
+                        opcode_str,
+                        fuel_consumption,
+                        pct,
+                        indent = if flatten { 0 } else { call_depth * 2 }
+                    ))
                 }
                 BytecodeTraceEvent::FunctionStart(recovered_func_id) => {
-                    let js_func_name = self
-                        .matched_functions
-                        .get(recovered_func_id)
-                        .map(|x| {
-                            Some(
-                                self.translation
-                                    .resolve_func_name(FuncIndex::from_u32(x.0), None)
-                                    .to_string(),
-                            )
-                        })
-                        .unwrap_or(self.intrinsic_fn_names.get(recovered_func_id).cloned())?;
-                    call_depth += 1;
+                    let js_func_name = debug_escape(&self.recovered_name(*recovered_func_id));
+                    let tail = after_tail_call && call_depth > 0;
+                    tail_frames.push(tail);
+                    if !tail {
+                        call_depth += 1;
+                    }
                     Some(format!(
-                        "{:indent$}FUNCTION START {}:",
+                        "{:indent$}FUNCTION START {}{}:",
                         "",
                         js_func_name,
+                        if tail { " (tail call)" } else { "" },
                         indent = (call_depth - 1) * 2
                     ))
                 }
                 BytecodeTraceEvent::FunctionEnd(recovered_func_id) => {
-                    let js_func_name = self
-                        .matched_functions
-                        .get(recovered_func_id)
-                        .map(|x| {
-                            Some(
-                                self.translation
-                                    .resolve_func_name(FuncIndex::from_u32(x.0), None)
-                                    .to_string(),
-                            )
-                        })
-                        .unwrap_or(self.intrinsic_fn_names.get(recovered_func_id).cloned())?;
-                    call_depth -= 1;
+                    let js_func_name = debug_escape(&self.recovered_name(*recovered_func_id));
+                    let tail = tail_frames.pop().unwrap_or(false);
+                    if !tail {
+                        call_depth -= 1;
+                    }
                     Some(format!(
-                        "{:indent$}FUNCTION END {}",
+                        "{:indent$}FUNCTION END {}{}",
                         "",
                         js_func_name,
-                        indent = call_depth * 2
+                        if tail { " (tail call)" } else { "" },
+                        indent = if tail { call_depth - 1 } else { call_depth } * 2
                     ))
                 }
                 BytecodeTraceEvent::FunctionSetup {
@@ -186,117 +782,151 @@ impl<'a> ProfileTraceParser<'a> {
                     indent = call_depth * 2
                 )),
             };
+            after_tail_call = matches!(
+                event,
+                BytecodeTraceEvent::OpcodeRun { opcode_byte, .. } if is_tail_call(*opcode_byte)
+            );
+            // Only opcode executions are reported when flattening.
+            let entry_report = entry_report
+                .filter(|_| !flatten || matches!(event, BytecodeTraceEvent::OpcodeRun { .. }));
             if let Some(entry) = entry_report {
-                output.push(entry);
+                let fuel = match event {
+                    BytecodeTraceEvent::OpcodeRun {
+                        fuel_consumption, ..
+                    } => *fuel_consumption,
+                    _ => 0,
+                };
+                if options.cumulative {
+                    output.push((fuel, format!("{} {}", cumulative_fuel, entry)));
+                } else {
+                    output.push((fuel, entry));
+                }
             }
         }
-        Some(output)
+        if flatten {
+            output.sort_by_key(|b| std::cmp::Reverse(b.0));
+        }
+        Ok(output.into_iter().map(|(_, line)| line).collect())
     }
 }
 
-pub fn report(pc: u32, func_index: FuncIndex, translation: &Translation, op: &Opcode) -> String {
+/// Whether the opcode byte is a tail call, which QuickJS emits for calls
+/// in return position.
+///
+/// The function entered through a tail call starts right after the tail
+/// call opcode is traced, and is reported in place of its caller's frame.
+fn is_tail_call(opcode_byte: u8) -> bool {
+    opcode_byte == Opcode::TailCall { argc: 0 }.discriminant()
+        || opcode_byte == Opcode::TailCallMethod { argc: 0 }.discriminant()
+}
+
+/// Describes an operator of the given function by the name it refers to,
+/// escaped through [debug_escape], or by its mnemonic otherwise.
+pub fn report(func_index: FuncIndex, translation: &Translation, op: &Opcode) -> String {
     use Opcode::*;
-    format!(
-        "{}: {}",
-        pc,
-        match *op {
-            FClosure { index } => translation.resolve_func_name(func_index, Some(index)),
-            PushAtomValue { atom } => translation.resolve_atom_name(atom),
-            PrivateSymbol { atom } => translation.resolve_atom_name(atom),
-            ThrowError { atom, .. } => translation.resolve_atom_name(atom),
-            CheckVar { atom } => translation.resolve_atom_name(atom),
-            GetVarUndef { atom } => translation.resolve_atom_name(atom),
-            GetVar { atom } => translation.resolve_atom_name(atom),
-            PutVar { atom } => translation.resolve_atom_name(atom),
-            PutVarInit { atom } => translation.resolve_atom_name(atom),
-            PutVarStrict { atom } => translation.resolve_atom_name(atom),
-            DefineVar { atom, .. } => translation.resolve_atom_name(atom),
-            CheckDefineVar { atom, .. } => translation.resolve_atom_name(atom),
-            DefineFunc { atom, .. } => translation.resolve_atom_name(atom),
-            GetField { atom } => translation.resolve_atom_name(atom),
-            GetField2 { atom } => translation.resolve_atom_name(atom),
-            PutField { atom } => translation.resolve_atom_name(atom),
-            DefineField { atom } => translation.resolve_atom_name(atom),
-            SetName { atom } => translation.resolve_atom_name(atom),
-            DefineMethod { atom, .. } => translation.resolve_atom_name(atom),
-            DefineMethodComputed { .. } => "DefineComputedMethod",
-            DefineClass { atom, .. } => translation.resolve_atom_name(atom),
-            DefineClassComputed { atom, .. } => translation.resolve_atom_name(atom),
-            GetLoc { index } => translation.resolve_func_local_name(func_index, index),
-            PutLoc { index } => translation.resolve_func_local_name(func_index, index),
-            SetLoc { index } => translation.resolve_func_local_name(func_index, index),
-            GetArg { index } => translation.resolve_func_arg_name(func_index, index),
-            PutArg { index } => translation.resolve_func_arg_name(func_index, index),
-            SetArg { index } => translation.resolve_func_arg_name(func_index, index),
-            GetVarRef { index } => translation.resolve_closure_var_name(func_index, index),
-            PutVarRef { index } => translation.resolve_closure_var_name(func_index, index),
-            SetVarRef { index } => translation.resolve_closure_var_name(func_index, index),
-            SetLocUninit { index } => translation.resolve_func_local_name(func_index, index),
-            GetLocCheck { index } => translation.resolve_func_local_name(func_index, index),
-            PutLocCheck { index } => translation.resolve_func_local_name(func_index, index),
-            PutLocCheckInit { index } => translation.resolve_func_local_name(func_index, index),
-            GetLocCheckThis { index } => translation.resolve_func_local_name(func_index, index),
-            GetVarRefCheck { index } => translation.resolve_closure_var_name(func_index, index),
-            PutVarRefCheck { index } => translation.resolve_closure_var_name(func_index, index),
-            PutVarRefCheckInit { index } => translation.resolve_closure_var_name(func_index, index),
-            WithGetVar { atom, .. } => translation.resolve_atom_name(atom),
-            WithPutVar { atom, .. } => translation.resolve_atom_name(atom),
-            WithDeleteVar { atom, .. } => translation.resolve_atom_name(atom),
-            WithMakeRef { atom, .. } => translation.resolve_atom_name(atom),
-            WithGetRef { atom, .. } => translation.resolve_atom_name(atom),
-            WithGetRefUndef { atom, .. } => translation.resolve_atom_name(atom),
-            MakeLocRef { atom, .. } => translation.resolve_atom_name(atom),
-            MakeArgRef { atom, .. } => translation.resolve_atom_name(atom),
-            MakeVarRefRef { atom, .. } => translation.resolve_atom_name(atom),
-            MakeVarRef { atom } => translation.resolve_atom_name(atom),
-            DecLoc { index } => translation.resolve_func_local_name(func_index, index),
-            IncLoc { index } => translation.resolve_func_local_name(func_index, index),
-            AddLoc { index } => translation.resolve_func_local_name(func_index, index),
-            FClosure8 { index } => translation.resolve_func_name(func_index, Some(index)),
-            GetLoc8 { index } => translation.resolve_func_local_name(func_index, index),
-            PutLoc8 { index } => translation.resolve_func_local_name(func_index, index),
-            SetLoc8 { index } => translation.resolve_func_local_name(func_index, index),
-            GetLoc0 | GetLoc1 | GetLoc2 | GetLoc3 => {
-                let index = op.discriminant() - 199 as u8;
-                translation.resolve_func_local_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            PutLoc0 | PutLoc1 | PutLoc2 | PutLoc3 => {
-                let index = op.discriminant() - 203 as u8;
-                translation.resolve_func_local_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            SetLoc0 | SetLoc1 | SetLoc2 | SetLoc3 => {
-                let index = op.discriminant() - 207 as u8;
-                translation.resolve_func_local_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            GetArg0 | GetArg1 | GetArg2 | GetArg3 => {
-                let index = op.discriminant() - 211 as u8;
-                translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            PutArg0 | PutArg1 | PutArg2 | PutArg3 => {
-                let index = op.discriminant() - 215 as u8;
-                translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            SetArg0 | SetArg1 | SetArg2 | SetArg3 => {
-                let index = op.discriminant() - 219 as u8;
-                translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            GetVarRef0 | GetVarRef1 | GetVarRef2 | GetVarRef3 => {
-                let index = op.discriminant() - 223 as u8;
-                translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
-            }
-            PutVarRef0 | PutVarRef1 | PutVarRef2 | PutVarRef3 => {
-                let index = op.discriminant() - 227 as u8;
-                translation
-                    .resolve_closure_var_name(func_index, ClosureVarIndex::from_u32(index as _))
-            }
-            SetVarRef0 | SetVarRef1 | SetVarRef2 | SetVarRef3 => {
-                let index = op.discriminant() - 231 as u8;
-                translation
-                    .resolve_closure_var_name(func_index, ClosureVarIndex::from_u32(index as _))
-            }
-            _ => unreachable!(), // _ => format!("{:?}", op),
+    let name = match *op {
+        FClosure { index } => match translation.try_resolve_func_name(func_index, Some(index)) {
+            Ok(name) => name,
+            Err(_) => return "<invalid closure>".to_string(),
+        },
+        PushAtomValue { atom } => translation.resolve_atom_name(atom),
+        PrivateSymbol { atom } => translation.resolve_atom_name(atom),
+        ThrowError { atom, .. } => translation.resolve_atom_name(atom),
+        CheckVar { atom } => translation.resolve_atom_name(atom),
+        GetVarUndef { atom } => translation.resolve_atom_name(atom),
+        GetVar { atom } => translation.resolve_atom_name(atom),
+        PutVar { atom } => translation.resolve_atom_name(atom),
+        PutVarInit { atom } => translation.resolve_atom_name(atom),
+        PutVarStrict { atom } => translation.resolve_atom_name(atom),
+        DefineVar { atom, .. } => translation.resolve_atom_name(atom),
+        CheckDefineVar { atom, .. } => translation.resolve_atom_name(atom),
+        DefineFunc { atom, .. } => translation.resolve_atom_name(atom),
+        GetField { atom } => translation.resolve_atom_name(atom),
+        GetField2 { atom } => translation.resolve_atom_name(atom),
+        PutField { atom } => translation.resolve_atom_name(atom),
+        DefineField { atom } => translation.resolve_atom_name(atom),
+        SetName { atom } => translation.resolve_atom_name(atom),
+        DefineMethod { atom, .. } => translation.resolve_atom_name(atom),
+        DefineMethodComputed { .. } => return "DefineComputedMethod".to_string(),
+        DefineClass { atom, .. } => translation.resolve_atom_name(atom),
+        DefineClassComputed { atom, .. } => translation.resolve_atom_name(atom),
+        GetLoc { index } => translation.resolve_func_local_name(func_index, index),
+        PutLoc { index } => translation.resolve_func_local_name(func_index, index),
+        SetLoc { index } => translation.resolve_func_local_name(func_index, index),
+        GetArg { index } => translation.resolve_func_arg_name(func_index, index),
+        PutArg { index } => translation.resolve_func_arg_name(func_index, index),
+        SetArg { index } => translation.resolve_func_arg_name(func_index, index),
+        GetVarRef { index } => translation.resolve_closure_var_name(func_index, index),
+        PutVarRef { index } => translation.resolve_closure_var_name(func_index, index),
+        SetVarRef { index } => translation.resolve_closure_var_name(func_index, index),
+        SetLocUninit { index } => translation.resolve_func_local_name(func_index, index),
+        GetLocCheck { index } => translation.resolve_func_local_name(func_index, index),
+        PutLocCheck { index } => translation.resolve_func_local_name(func_index, index),
+        PutLocCheckInit { index } => translation.resolve_func_local_name(func_index, index),
+        GetLocCheckThis { index } => translation.resolve_func_local_name(func_index, index),
+        GetVarRefCheck { index } => translation.resolve_closure_var_name(func_index, index),
+        PutVarRefCheck { index } => translation.resolve_closure_var_name(func_index, index),
+        PutVarRefCheckInit { index } => translation.resolve_closure_var_name(func_index, index),
+        WithGetVar { atom, .. } => translation.resolve_atom_name(atom),
+        WithPutVar { atom, .. } => translation.resolve_atom_name(atom),
+        WithDeleteVar { atom, .. } => translation.resolve_atom_name(atom),
+        WithMakeRef { atom, .. } => translation.resolve_atom_name(atom),
+        WithGetRef { atom, .. } => translation.resolve_atom_name(atom),
+        WithGetRefUndef { atom, .. } => translation.resolve_atom_name(atom),
+        MakeLocRef { atom, .. } => translation.resolve_atom_name(atom),
+        MakeArgRef { atom, .. } => translation.resolve_atom_name(atom),
+        MakeVarRefRef { atom, .. } => translation.resolve_atom_name(atom),
+        MakeVarRef { atom } => translation.resolve_atom_name(atom),
+        DecLoc { index } => translation.resolve_func_local_name(func_index, index),
+        IncLoc { index } => translation.resolve_func_local_name(func_index, index),
+        AddLoc { index } => translation.resolve_func_local_name(func_index, index),
+        FClosure8 { index } => match translation.try_resolve_func_name(func_index, Some(index)) {
+            Ok(name) => name,
+            Err(_) => return "<invalid closure>".to_string(),
+        },
+        GetLoc8 { index } => translation.resolve_func_local_name(func_index, index),
+        PutLoc8 { index } => translation.resolve_func_local_name(func_index, index),
+        SetLoc8 { index } => translation.resolve_func_local_name(func_index, index),
+        GetLoc0 | GetLoc1 | GetLoc2 | GetLoc3 => {
+            let index = op.discriminant() - 199 as u8;
+            translation.resolve_func_local_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        PutLoc0 | PutLoc1 | PutLoc2 | PutLoc3 => {
+            let index = op.discriminant() - 203 as u8;
+            translation.resolve_func_local_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        SetLoc0 | SetLoc1 | SetLoc2 | SetLoc3 => {
+            let index = op.discriminant() - 207 as u8;
+            translation.resolve_func_local_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        GetArg0 | GetArg1 | GetArg2 | GetArg3 => {
+            let index = op.discriminant() - 211 as u8;
+            translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        PutArg0 | PutArg1 | PutArg2 | PutArg3 => {
+            let index = op.discriminant() - 215 as u8;
+            translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        SetArg0 | SetArg1 | SetArg2 | SetArg3 => {
+            let index = op.discriminant() - 219 as u8;
+            translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        GetVarRef0 | GetVarRef1 | GetVarRef2 | GetVarRef3 => {
+            let index = op.discriminant() - 223 as u8;
+            translation.resolve_func_arg_name(func_index, LocalIndex::from_u32(index as _))
+        }
+        PutVarRef0 | PutVarRef1 | PutVarRef2 | PutVarRef3 => {
+            let index = op.discriminant() - 227 as u8;
+            translation.resolve_closure_var_name(func_index, ClosureVarIndex::from_u32(index as _))
+        }
+        SetVarRef0 | SetVarRef1 | SetVarRef2 | SetVarRef3 => {
+            let index = op.discriminant() - 231 as u8;
+            translation.resolve_closure_var_name(func_index, ClosureVarIndex::from_u32(index as _))
         }
-    )
+        Import => return "dynamic import()".to_string(),
+        _ => return op.to_string(),
+    };
+    debug_escape(name)
 }
 
 // impl fmt::Debug for ProfileTraceParser<'_> {
@@ -0,0 +1,48 @@
+//! Per opcode statistics of a trace.
+
+use std::collections::BTreeMap;
+
+/// Aggregated fuel consumption of an opcode kind in a trace.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpStat {
+    /// The number of times opcodes of this kind ran.
+    pub count: u64,
+    /// The fuel consumed by all the runs.
+    pub total_fuel: u64,
+    /// The average fuel consumed per run.
+    pub avg_fuel: f64,
+    /// The maximum fuel consumed by a single run.
+    pub max_fuel: u64,
+}
+
+impl OpStat {
+    /// Accounts a single run of the opcode.
+    pub(crate) fn record(&mut self, fuel: u64) {
+        self.count += 1;
+        self.total_fuel += fuel;
+        self.max_fuel = self.max_fuel.max(fuel);
+        self.avg_fuel = self.total_fuel as f64 / self.count as f64;
+    }
+}
+
+/// Renders per opcode statistics as a Markdown table, sorted by average fuel
+/// in descending order.
+pub fn opcode_stats_to_markdown(stats: &BTreeMap<String, OpStat>) -> String {
+    let mut rows = stats.iter().collect::<Vec<_>>();
+    rows.sort_by(|(a_name, a), (b_name, b)| {
+        b.avg_fuel
+            .total_cmp(&a.avg_fuel)
+            .then_with(|| a_name.cmp(b_name))
+    });
+
+    let mut out = String::new();
+    out.push_str("| Opcode | Count | Total Fuel | Avg Fuel | Max Fuel |\n");
+    out.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+    for (name, s) in rows {
+        out.push_str(&format!(
+            "| {} | {} | {} | {:.2} | {} |\n",
+            name, s.count, s.total_fuel, s.avg_fuel, s.max_fuel
+        ));
+    }
+    out
+}
@@ -0,0 +1,97 @@
+//! Per function summary of a trace.
+
+/// Aggregated fuel consumption of a function in a trace.
+#[derive(Debug, Clone)]
+pub struct FunctionSummary {
+    /// The function name.
+    pub name: String,
+    /// Fuel consumed by the function itself, excluding its callees.
+    pub self_fuel: u64,
+    /// Fuel consumed by the function, including its callees.
+    pub total_fuel: u64,
+    /// The number of times the function was called.
+    pub calls: u64,
+}
+
+impl FunctionSummary {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            self_fuel: 0,
+            total_fuel: 0,
+            calls: 0,
+        }
+    }
+}
+
+/// Returns the percentage of `fuel` relative to `total`.
+fn percentage(fuel: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        fuel as f64 * 100.0 / total as f64
+    }
+}
+
+/// Renders a function summary as a Markdown table, followed by a totals row.
+///
+/// The percentage column is the share of each function's self fuel in the
+/// fuel of the whole trace.
+pub fn to_markdown(summary: &[FunctionSummary]) -> String {
+    let total_fuel = summary.iter().map(|s| s.self_fuel).sum::<u64>();
+    let total_calls = summary.iter().map(|s| s.calls).sum::<u64>();
+
+    let mut out = String::new();
+    out.push_str("| Function | Self Fuel | Total Fuel | Calls | % |\n");
+    out.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+    for s in summary {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {:.2} |\n",
+            s.name.replace('|', "\\|"),
+            s.self_fuel,
+            s.total_fuel,
+            s.calls,
+            percentage(s.self_fuel, total_fuel)
+        ));
+    }
+    out.push_str(&format!(
+        "| **Total** | {} | {} | {} | {:.2} |\n",
+        total_fuel,
+        total_fuel,
+        total_calls,
+        percentage(total_fuel, total_fuel)
+    ));
+    out
+}
+
+/// Renders a function summary as CSV, with a header row.
+///
+/// The percentage column is the share of each function's self fuel in the
+/// fuel of the whole trace.
+pub fn to_csv(summary: &[FunctionSummary]) -> String {
+    let total_fuel = summary.iter().map(|s| s.self_fuel).sum::<u64>();
+
+    let mut out = String::new();
+    out.push_str("function,self_fuel,total_fuel,calls,pct\n");
+    for s in summary {
+        out.push_str(&format!(
+            "{},{},{},{},{:.2}\n",
+            csv_field(&s.name),
+            s.self_fuel,
+            s.total_fuel,
+            s.calls,
+            percentage(s.self_fuel, total_fuel)
+        ));
+    }
+    out
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break,
+/// doubling any quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
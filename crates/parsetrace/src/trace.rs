@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
-use anyhow::{bail, Error, Result};
+use anyhow::{anyhow, bail, Error, Result};
+
+use crate::TraceError;
 
 /// Represents a single trace event profiled during the execution of the quickjs engine as a wasm module.
 /// The profiler captures source level bytecode execution events and fuel consumption.
@@ -18,13 +20,16 @@ pub enum BytecodeTraceEvent {
     /// `wasm_func_id` is the wasm function id, `S` indicates function start, `E` indicates function end,
     /// and `fuel_watermark` is the (fuel level at the event - fuel level at opcode start). The events
     /// represented by `native_calls` should be well formed and properly nested.
+    ///
+    /// Opcodes can make thousands of native calls, so only the raw
+    /// `native_calls` segment is kept, which is validated but not parsed
+    /// into call frames until needed, see [WasmCallFrame::parse].
     OpcodeRun {
         recovered_func_id: u32,
         opcode_offset: u32,
         opcode_byte: u8,
         fuel_consumption: u32,
-        #[allow(dead_code)]
-        native_calls: Vec<WasmCallFrame>,
+        native_calls: Box<str>,
     },
     /// Source function start event, trace format is:
     ///
@@ -39,7 +44,6 @@ pub enum BytecodeTraceEvent {
     ///
     /// `recovered_func_id,0,00,fuel_consumption,`
     FunctionSetup {
-        #[allow(dead_code)]
         recovered_func_id: u32,
         fuel_consumption: u32,
     },
@@ -73,6 +77,36 @@ impl Default for WasmCallFrame {
     }
 }
 
+impl WasmCallFrame {
+    /// Parses the `native_calls` segment of an opcode trace event into the
+    /// call frames of the top level native calls.
+    pub fn parse(native_calls: &str) -> Result<Vec<WasmCallFrame>> {
+        if native_calls.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut wasm_call_list = WasmCallFrame::default();
+        let events: Vec<&str> = native_calls.split("|").collect();
+        build_call_frame(&mut wasm_call_list, &events, 0)?;
+        Ok(wasm_call_list.calls)
+    }
+
+    /// The fuel consumed by the function, including its callees.
+    pub fn total_fuel(&self) -> u32 {
+        self.end_fuel_watermark
+            .saturating_sub(self.start_fuel_watermark)
+    }
+
+    /// The fuel consumed by the function itself, excluding its callees.
+    pub fn self_fuel(&self) -> u32 {
+        let children = self
+            .calls
+            .iter()
+            .map(WasmCallFrame::total_fuel)
+            .sum::<u32>();
+        self.total_fuel().saturating_sub(children)
+    }
+}
+
 impl FromStr for BytecodeTraceEvent {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -101,13 +135,13 @@ impl FromStr for BytecodeTraceEvent {
                     }
                 } else {
                     let wasm_calls_trace = parts.get(4).unwrap_or(&"");
-                    let native_calls = native_calls_from_str(wasm_calls_trace)?;
+                    validate_native_calls(wasm_calls_trace)?;
                     Ok(OpcodeRun {
                         recovered_func_id,
                         opcode_offset,
                         opcode_byte,
                         fuel_consumption,
-                        native_calls,
+                        native_calls: (*wasm_calls_trace).into(),
                     })
                 }
             }
@@ -115,15 +149,40 @@ impl FromStr for BytecodeTraceEvent {
     }
 }
 
-/// Parse the native calls from trace string.
-fn native_calls_from_str(trace: &str) -> Result<Vec<WasmCallFrame>> {
+/// Checks that the native calls from trace string are well formed and
+/// properly nested, following the same rules as [build_call_frame], without
+/// building the call frames.
+fn validate_native_calls(trace: &str) -> Result<()> {
     if trace.is_empty() {
-        return Ok(vec![]);
+        return Ok(());
+    }
+    // The ids of the functions that started but didn't end yet, starting
+    // with the root frame.
+    let mut open = vec![WasmCallFrame::default().wasm_func_id];
+    for event in trace.split("|") {
+        let (wasm_func_id, is_start, _) = parse_native_event(event)?;
+        if is_start {
+            open.push(wasm_func_id);
+        } else if open.last() == Some(&wasm_func_id) {
+            open.pop();
+            if open.is_empty() {
+                break;
+            }
+        } else {
+            return Err(anyhow!(TraceError::UnbalancedNativeCalls));
+        }
     }
-    let mut wasm_call_list = WasmCallFrame::default();
-    let events: Vec<&str> = trace.split("|").collect();
-    build_call_frame(&mut wasm_call_list, &events, 0)?;
-    Ok(wasm_call_list.calls)
+    Ok(())
+}
+
+/// Parses a single native call event into its wasm function id, whether
+/// it's a start event and its fuel watermark.
+fn parse_native_event(event: &str) -> Result<(u32, bool, u32)> {
+    let parts: Vec<&str> = event.split(":").collect();
+    let [wasm_func_id, kind, fuel_watermark] = parts[..] else {
+        bail!("Invalid native call event: {}", event);
+    };
+    Ok((wasm_func_id.parse()?, kind == "S", fuel_watermark.parse()?))
 }
 
 /// helper function to build the call frame from the trace string.
@@ -135,10 +194,7 @@ fn build_call_frame(
     let mut i = cur_pos;
     let list_len = trace_list.len();
     while i < list_len {
-        let parts: Vec<&str> = trace_list[i].split(":").collect();
-        let wasm_func_id: u32 = parts[0].parse().unwrap();
-        let is_start = parts[1] == "S";
-        let fuel_watermark: u32 = parts[2].parse().unwrap();
+        let (wasm_func_id, is_start, fuel_watermark) = parse_native_event(trace_list[i])?;
         if wasm_func_id == call_frame.wasm_func_id && !is_start {
             call_frame.end_fuel_watermark = fuel_watermark;
             return Ok(i + 1);
@@ -152,7 +208,7 @@ fn build_call_frame(
             }
             call_frame.calls.push(new_frame);
         } else {
-            return Err(Error::msg("Improperly formatted trace"));
+            return Err(anyhow!(TraceError::UnbalancedNativeCalls));
         }
     }
     Ok(i)
@@ -1,17 +1,31 @@
 use std::collections::{HashMap, HashSet};
 
+use anyhow::{Error, Result};
 use quickpars::Opcode;
 
-use crate::{trace::BytecodeTraceEvent, MatchedFuncInfo, ProfiledOpcodeList};
+use crate::{trace::BytecodeTraceEvent, MatchedFuncInfo, ProfiledOpcodeList, TraceError};
 
 /// Turns the raw execution trace into a vector of trace events.
-pub(crate) fn generate_trace(raw_execution_trace: &str) -> Vec<BytecodeTraceEvent> {
-    let result = raw_execution_trace
+pub(crate) fn generate_trace(raw_execution_trace: &str) -> Result<Vec<BytecodeTraceEvent>> {
+    raw_execution_trace
         .lines()
+        .enumerate()
         .skip(1) // skip header
-        .map(|line| line.parse().unwrap())
-        .collect();
-    result
+        .map(|(index, line)| {
+            line.parse().map_err(|err: Error| {
+                let line_number = index + 1;
+                if err.is::<TraceError>() {
+                    err.context(format!("At trace line {}", line_number))
+                } else {
+                    TraceError::MalformedEvent {
+                        line: line_number,
+                        content: line.to_string(),
+                    }
+                    .into()
+                }
+            })
+        })
+        .collect()
 }
 
 /// builds a mapping of recovered function ids to their corresponding profiled opcodes.
@@ -72,6 +86,10 @@ pub(crate) fn match_single_function(
     meta: &HashMap<u32, Vec<(u32, Opcode)>>,
     matched_js_func_idx: &mut HashSet<u32>,
 ) -> Option<MatchedFuncInfo> {
+    // A short function's bytecode can be a prefix of a longer one's, so
+    // among all the candidates, prefer the one whose opcode count is
+    // closest to the recovered opcode count, then the lowest index.
+    let mut best: Option<(usize, MatchedFuncInfo)> = None;
     for i in meta.keys() {
         if matched_js_func_idx.contains(&(*i as u32)) {
             continue;
@@ -79,11 +97,22 @@ pub(crate) fn match_single_function(
 
         let operators = meta.get(i).unwrap();
         if let Some(matched_opcode_pairs) = match_pair(recovered_bytes, &operators) {
-            matched_js_func_idx.insert(*i as u32);
-            return Some((*i as u32, matched_opcode_pairs));
+            let distance = operators.len().abs_diff(recovered_bytes.len());
+            let is_better = match &best {
+                Some((best_distance, (best_idx, _))) => {
+                    (distance, *i) < (*best_distance, *best_idx)
+                }
+                None => true,
+            };
+            if is_better {
+                best = Some((distance, (*i, matched_opcode_pairs)));
+            }
         }
     }
-    None
+
+    let (_, matched) = best?;
+    matched_js_func_idx.insert(matched.0);
+    Some(matched)
 }
 
 pub(crate) fn match_pair(
@@ -116,3 +145,49 @@ pub(crate) fn match_pair(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the opcodes of a function with the given operators, one
+    /// byte each.
+    fn function(ops: &[Opcode]) -> Vec<(u32, Opcode)> {
+        ops.iter()
+            .enumerate()
+            .map(|(pc, op)| (pc as u32, op.clone()))
+            .collect()
+    }
+
+    /// Returns the trace of the given opcodes.
+    fn recovered(opcodes: &[(u32, Opcode)]) -> Vec<(u32, u8)> {
+        opcodes
+            .iter()
+            .map(|(pc, op)| (*pc, op.discriminant()))
+            .collect()
+    }
+
+    #[test]
+    fn closest_length_wins_among_shared_prefixes() {
+        let short = function(&[Opcode::Undefined, Opcode::Return]);
+        let long = function(&[
+            Opcode::Undefined,
+            Opcode::Return,
+            Opcode::Undefined,
+            Opcode::Return,
+        ]);
+        // The long function has the lowest index, so only the length sets
+        // the two apart.
+        let meta = HashMap::from([(0, long.clone()), (1, short.clone())]);
+
+        let mut matched = HashSet::new();
+        let (index, pairs) =
+            match_single_function(&recovered(&short), &meta, &mut matched).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(pairs, HashMap::from([(0, 0), (1, 1)]));
+
+        let (index, _) = match_single_function(&recovered(&long), &meta, &mut matched).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(matched, HashSet::from([0, 1]));
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use common::NESTED_TRACE;
+use jac_translate::Translation;
+use parsetrace::ProfileTraceParser;
+use serde_json::Value;
+
+mod common;
+
+/// Returns the `ts` and `dur` of each event of a Chrome trace.
+fn timings(chrome_trace: &str) -> Result<Vec<(f64, f64)>> {
+    let trace: Value = serde_json::from_str(chrome_trace)?;
+    Ok(trace["traceEvents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| {
+            (
+                event["ts"].as_f64().unwrap(),
+                event["dur"].as_f64().unwrap(),
+            )
+        })
+        .collect())
+}
+
+#[test]
+fn doubling_fuel_per_us_halves_the_durations() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(NESTED_TRACE, &translation)?;
+
+    let single = timings(&parser.chrome_trace(1.0)?)?;
+    let double = timings(&parser.chrome_trace(2.0)?)?;
+    // The inner invocation ends first.
+    assert_eq!(single, vec![(24.0, 5.0), (7.0, 35.0)]);
+    assert_eq!(double.len(), single.len());
+    for ((ts, dur), (half_ts, half_dur)) in single.iter().zip(&double) {
+        assert_eq!(ts / 2.0, *half_ts);
+        assert_eq!(dur / 2.0, *half_dur);
+    }
+    Ok(())
+}
+
+#[test]
+fn non_positive_fuel_per_us_is_rejected() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new("header\n1,0,START,0,\n1,0,END,0,\n", &translation)?;
+    assert!(parser.chrome_trace(0.0).is_err());
+    assert!(parser.chrome_trace(-1.0).is_err());
+    Ok(())
+}
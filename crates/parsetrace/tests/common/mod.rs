@@ -0,0 +1,8 @@
+//! Helpers shared by the integration tests.
+
+/// A raw trace where function 1 calls function 2 once, after a 7 fuel
+/// prologue outside of any function.
+///
+/// Function 1 runs `0c` twice, with 10 and 13 fuel, and `0e` once with 7
+/// fuel, and function 2 runs `0c` once with 5 fuel, for a total of 42.
+pub const NESTED_TRACE: &str = "header\n0,0,00,7,\n1,0,START,0,\n1,4,0c,10,\n1,5,0e,7,\n2,0,START,0,\n2,4,0c,5,\n2,0,END,0,\n1,6,0c,13,\n1,0,END,0,\n";
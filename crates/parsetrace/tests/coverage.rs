@@ -0,0 +1,53 @@
+use anyhow::Result;
+use jac_translate::{quickpars::Opcode, TranslationBuilder};
+use javy::{Config, Runtime};
+use parsetrace::ProfileTraceParser;
+
+#[test]
+fn untaken_branch_is_uncovered() -> Result<()> {
+    let runtime = Runtime::new(Config::default())?;
+    let bytecode = runtime.compile_to_bytecode(
+        "index.js",
+        "function f(x) { if (x) { x = x * 2; } return x; }\nf(0);",
+    )?;
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = translation.function_by_name("f").unwrap();
+    let mut operators = vec![];
+    let mut reader = func.operators_reader();
+    while !reader.done() {
+        operators.push(Opcode::from_reader(&mut reader)?);
+    }
+
+    // `f(0)` jumps over the body of the `if` statement.
+    let (branch_pc, target) = operators
+        .iter()
+        .find_map(|(pc, op)| match op {
+            Opcode::IfFalse { offset } => Some((*pc, (*pc as i64 + 1 + *offset as i64) as u32)),
+            Opcode::IfFalse8 { offset } => Some((*pc, (*pc as i64 + 1 + *offset as i64) as u32)),
+            _ => None,
+        })
+        .unwrap();
+    let skipped = |pc: u32| pc > branch_pc && pc < target;
+    // Events at offset 0 are function setup events, so the first opcode
+    // can't be traced.
+    let mut raw_trace = String::from("header\n1,0,START,0,\n");
+    for (pc, op) in &operators {
+        if *pc != 0 && !skipped(*pc) {
+            raw_trace.push_str(&format!("1,{},{:02x},1,\n", pc, op.discriminant()));
+        }
+    }
+    raw_trace.push_str("1,0,END,0,\n");
+
+    let parser = ProfileTraceParser::new(&raw_trace, &translation)?;
+    let uncovered = parser.uncovered_opcodes();
+
+    let expected = operators
+        .iter()
+        .map(|(pc, _)| *pc)
+        .filter(|pc| *pc == 0 || skipped(*pc))
+        .map(|pc| ("f".to_string(), pc))
+        .collect::<Vec<_>>();
+    assert!(expected.len() > 1);
+    assert_eq!(uncovered, expected);
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use jac_translate::Translation;
+use parsetrace::{ProfileTraceParser, TraceError};
+
+#[test]
+fn malformed_event_is_an_error() {
+    let translation = Translation::default();
+    let raw_trace = "header\n0,0,00,10,\n1,not,an,event\n";
+    let err = ProfileTraceParser::new(raw_trace, &translation)
+        .err()
+        .expect("malformed trace to be rejected");
+
+    assert_eq!(
+        err.downcast_ref::<TraceError>(),
+        Some(&TraceError::MalformedEvent {
+            line: 3,
+            content: "1,not,an,event".to_string(),
+        })
+    );
+}
+
+#[test]
+fn unbalanced_native_calls_are_an_error() {
+    let translation = Translation::default();
+    let raw_trace = "header\n1,4,0c,10,5:E:3\n";
+    let err = ProfileTraceParser::new(raw_trace, &translation)
+        .err()
+        .expect("unbalanced native calls to be rejected");
+
+    assert_eq!(
+        err.downcast_ref::<TraceError>(),
+        Some(&TraceError::UnbalancedNativeCalls)
+    );
+}
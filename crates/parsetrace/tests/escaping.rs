@@ -0,0 +1,21 @@
+use jac_testutil::translation_from_ops;
+use jac_translate::quickpars::{AtomIndex, FuncIndex, Opcode};
+use parsetrace::report;
+
+#[test]
+fn keys_with_newlines_are_escaped() {
+    let mut translation = translation_from_ops(&[Opcode::Undefined, Opcode::Return]);
+    translation.header.atoms.push("a\nb".to_string());
+    let func = FuncIndex::from_u32(0);
+    let atom = AtomIndex::from_u32(1);
+
+    assert_eq!(
+        report(func, &translation, &Opcode::GetField { atom }),
+        r#""a\nb""#
+    );
+    // Operators that don't refer to a name aren't quoted.
+    assert_eq!(
+        report(func, &translation, &Opcode::Import),
+        "dynamic import()"
+    );
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use jac_translate::Translation;
+use parsetrace::ProfileTraceParser;
+
+#[test]
+fn one_json_object_per_event() -> Result<()> {
+    let translation = Translation::default();
+    let raw_trace = "header\n0,0,00,7,\n1,0,START,0,\n1,0,00,3,\n1,1,0c,10,5:S:1|5:E:4\n1,2,28,2,\n1,0,END,0,\n";
+    let parser = ProfileTraceParser::new(raw_trace, &translation)?;
+    let lines = parser.json_lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), raw_trace.lines().count() - 1);
+    for line in &lines {
+        let value: serde_json::Value = serde_json::from_str(line)?;
+        for field in ["type", "function", "offset", "opcode", "fuel", "depth"] {
+            assert!(value.get(field).is_some(), "missing `{field}` in {line}");
+        }
+    }
+    let opcode: serde_json::Value = serde_json::from_str(&lines[3])?;
+    assert_eq!(opcode["type"], "opcode");
+    assert_eq!(opcode["offset"], 1);
+    assert_eq!(opcode["fuel"], 10);
+    assert_eq!(opcode["depth"], 1);
+    Ok(())
+}
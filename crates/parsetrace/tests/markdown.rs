@@ -0,0 +1,56 @@
+use anyhow::Result;
+use common::NESTED_TRACE;
+use jac_translate::Translation;
+use parsetrace::{to_markdown, ProfileTraceParser};
+
+mod common;
+
+/// Splits a Markdown table row into its cells.
+fn cells(row: &str) -> Vec<&str> {
+    row.trim()
+        .strip_prefix('|')
+        .and_then(|row| row.strip_suffix('|'))
+        .expect("a table row")
+        .split('|')
+        .map(str::trim)
+        .collect()
+}
+
+#[test]
+fn summary_renders_as_a_markdown_table() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(NESTED_TRACE, &translation)?;
+    let summary = parser.function_summary();
+    assert_eq!(summary.len(), 2);
+
+    let markdown = to_markdown(&summary);
+    let lines = markdown.lines().collect::<Vec<_>>();
+    let header = cells(lines[0]);
+    assert_eq!(
+        header,
+        ["Function", "Self Fuel", "Total Fuel", "Calls", "%"]
+    );
+
+    // Every column has a separator cell made of dashes, optionally
+    // aligned through colons.
+    let separator = cells(lines[1]);
+    assert_eq!(separator.len(), header.len());
+    for cell in separator {
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+        assert!(dashes.len() >= 3, "invalid separator cell `{cell}`");
+        assert!(
+            dashes.chars().all(|c| c == '-'),
+            "invalid separator cell `{cell}`"
+        );
+    }
+
+    // One row per function, sorted by self fuel, followed by the totals.
+    let rows = lines[2..].iter().map(|row| cells(row)).collect::<Vec<_>>();
+    assert_eq!(rows.len(), summary.len() + 1);
+    assert!(rows.iter().all(|row| row.len() == header.len()));
+    let self_fuel = |row: &[&str]| row[1].parse::<u64>().unwrap();
+    assert!(self_fuel(&rows[0]) >= self_fuel(&rows[1]));
+    assert_eq!(rows[2][0], "**Total**");
+    assert_eq!(self_fuel(&rows[2]), 35);
+    Ok(())
+}
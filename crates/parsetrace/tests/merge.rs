@@ -0,0 +1,39 @@
+use anyhow::Result;
+use jac_translate::{quickpars::FunctionSectionHeader, Translation};
+use parsetrace::{ProfileTraceParser, TraceError};
+
+#[test]
+fn merged_fuel_adds_up() -> Result<()> {
+    let translation = Translation::default();
+    let first = "header\n0,0,00,7,\n1,0,START,0,\n1,0,00,3,\n1,4,0c,10,\n1,0,END,0,\n";
+    let second = "header\n1,0,START,0,\n1,0,00,2,\n1,4,0c,5,\n1,0,END,0,\n";
+    let mut parser = ProfileTraceParser::new(first, &translation)?;
+    let other = ProfileTraceParser::new(second, &translation)?;
+    parser.merge(&other)?;
+
+    let summary = parser.function_summary();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].calls, 2);
+    assert_eq!(summary[0].self_fuel, 3 + 10 + 2 + 5);
+    assert_eq!(summary[0].total_fuel, summary[0].self_fuel);
+    Ok(())
+}
+
+#[test]
+fn merging_different_bytecode_is_an_error() -> Result<()> {
+    let raw_trace = "header\n1,0,START,0,\n1,4,0c,10,\n1,0,END,0,\n";
+    let empty = Translation::default();
+    let mut translation = Translation::default();
+    translation
+        .module
+        .push_func(FunctionSectionHeader::default());
+    let mut parser = ProfileTraceParser::new(raw_trace, &empty)?;
+    let other = ProfileTraceParser::new(raw_trace, &translation)?;
+
+    let err = parser.merge(&other).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<TraceError>(),
+        Some(&TraceError::DifferentBytecode)
+    );
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use jac_translate::Translation;
+use parsetrace::{ProfileTraceParser, WasmCallFrame};
+
+const NATIVE_CALLS: &str = "5:S:1|6:S:2|6:E:4|5:E:9";
+
+#[test]
+fn native_calls_are_kept_raw() -> Result<()> {
+    let translation = Translation::default();
+    let raw_trace =
+        format!("header\n1,0,START,0,\n1,4,0c,10,{NATIVE_CALLS}\n1,5,0e,2,\n1,0,END,0,\n");
+    let parser = ProfileTraceParser::new(&raw_trace, &translation)?;
+    parser.report_trace()?;
+
+    // The report doesn't need the call frames, so the raw segments are
+    // retained as they appear in the trace.
+    let raw = parser.raw_native_calls().collect::<Vec<_>>();
+    assert_eq!(raw, vec![NATIVE_CALLS, ""]);
+
+    let frames = WasmCallFrame::parse(raw[0])?;
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].wasm_func_id, 5);
+    assert_eq!(frames[0].total_fuel(), 8);
+    assert_eq!(frames[0].self_fuel(), 6);
+    assert_eq!(frames[0].calls[0].wasm_func_id, 6);
+
+    assert_eq!(parser.native_hotspots()?, vec![(5, 6), (6, 2)]);
+    Ok(())
+}
+
+#[test]
+fn self_fuel_excludes_every_child() -> Result<()> {
+    let frames = WasmCallFrame::parse("5:S:0|6:S:1|6:E:3|7:S:4|7:E:8|5:E:10")?;
+    assert_eq!(frames.len(), 1);
+    let frame = &frames[0];
+    assert_eq!(frame.calls.len(), 2);
+    assert_eq!(frame.calls[0].total_fuel(), 2);
+    assert_eq!(frame.calls[1].total_fuel(), 4);
+    assert_eq!(frame.total_fuel(), 10);
+    assert_eq!(frame.self_fuel(), 4);
+    Ok(())
+}
+
+#[test]
+fn repeated_native_calls_aggregate_their_fuel() -> Result<()> {
+    let translation = Translation::default();
+    // Function 6 is called twice from function 5, and once more directly
+    // from another opcode.
+    let raw_trace = "header\n1,0,START,0,\n\
+        1,4,0c,10,5:S:0|6:S:1|6:E:3|6:S:4|6:E:8|5:E:10\n\
+        1,5,0c,5,6:S:0|6:E:5\n\
+        1,0,END,0,\n";
+    let parser = ProfileTraceParser::new(raw_trace, &translation)?;
+
+    assert_eq!(parser.native_hotspots()?, vec![(6, 11), (5, 4)]);
+    Ok(())
+}
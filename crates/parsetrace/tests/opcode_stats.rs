@@ -0,0 +1,25 @@
+use anyhow::Result;
+use common::NESTED_TRACE;
+use jac_translate::{quickpars::Opcode, Translation};
+use parsetrace::ProfileTraceParser;
+
+mod common;
+
+#[test]
+fn average_fuel_is_total_over_count() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(NESTED_TRACE, &translation)?;
+    let stats = parser.opcode_fuel_stats();
+    assert_eq!(stats.len(), 2);
+
+    let stat = &stats[&Opcode::name_from_byte(0x0c)];
+    assert_eq!(stat.count, 3);
+    assert_eq!(stat.total_fuel, 28);
+    assert_eq!(stat.max_fuel, 13);
+    assert_eq!(stat.avg_fuel, stat.total_fuel as f64 / stat.count as f64);
+
+    let stat = &stats[&Opcode::name_from_byte(0x0e)];
+    assert_eq!((stat.count, stat.total_fuel), (1, 7));
+    assert_eq!(stat.avg_fuel, 7.0);
+    Ok(())
+}
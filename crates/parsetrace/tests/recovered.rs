@@ -0,0 +1,142 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FunctionLocal, FunctionSectionHeader, Opcode},
+    TranslationBuilder,
+};
+use parsetrace::{summary_with, ProfileTraceParser, ReportOptions};
+
+/// The operators of `add`.
+const ADD: [Opcode; 4] = [
+    Opcode::GetArg0,
+    Opcode::GetArg1,
+    Opcode::Add,
+    Opcode::Return,
+];
+
+/// Builds the bytecode of a function named `add`, adding its two arguments.
+fn add() -> Result<Vec<u8>> {
+    let mut operators = vec![];
+    for op in &ADD {
+        op.encode(&mut operators);
+    }
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_args(2, 2)
+        .with_stack_size(2)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let args = [1, 2].map(|atom| FunctionLocal {
+        name_index: interned_atom(atom),
+        ..Default::default()
+    });
+    Ok(encode_bytecode(
+        &["add", "a", "b"],
+        &encode_function(&header, &args, &[], &operators),
+    ))
+}
+
+/// Returns a trace of a call to `add` from an intrinsic function, whose
+/// opcodes can't be found in the bytecode.
+fn trace() -> String {
+    let mut raw_trace = String::from("header\n1,0,START,0,\n1,7,ff,3,\n2,0,START,0,\n");
+    for (pc, op) in ADD.iter().enumerate().skip(1) {
+        raw_trace.push_str(&format!("2,{},{:02x},1,\n", pc, op.discriminant()));
+    }
+    raw_trace.push_str("2,0,END,0,\n1,0,END,0,\n");
+    raw_trace
+}
+
+#[test]
+fn both_constructors_report_the_same() -> Result<()> {
+    let bytecode = add()?;
+    let raw_trace = trace();
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+
+    let borrowed = ProfileTraceParser::new(&raw_trace, &translation)?;
+    let owned = ProfileTraceParser::from_bytecode(&raw_trace, &bytecode)?;
+    let report = borrowed.report_trace()?;
+    assert!(report
+        .iter()
+        .any(|line| line.contains("FUNCTION START add")));
+    assert_eq!(report, owned.report_trace()?);
+    Ok(())
+}
+
+#[test]
+fn recovered_ids_resolve_to_names() -> Result<()> {
+    let bytecode = add()?;
+    let parser = ProfileTraceParser::from_bytecode(&trace(), &bytecode)?;
+    // The second recovered function matches `add`, while the first one, whose
+    // opcodes are not in the bytecode, is an intrinsic.
+    assert_eq!(parser.resolve_recovered(2).as_deref(), Some("add"));
+    assert_eq!(
+        parser.resolve_recovered(1).as_deref(),
+        Some("intrinsic_fn_0")
+    );
+    assert_eq!(parser.resolve_recovered(3), None);
+    Ok(())
+}
+
+#[test]
+fn intrinsics_are_named_deterministically() -> Result<()> {
+    let bytecode = add()?;
+    // Several intrinsic functions, none of which can be matched.
+    let mut raw_trace = String::from("header\n");
+    for id in [9, 4, 7, 1] {
+        raw_trace.push_str(&format!("{id},0,START,0,\n{id},7,ff,1,\n{id},0,END,0,\n"));
+    }
+    let names = || -> Result<Vec<Option<String>>> {
+        let parser = ProfileTraceParser::from_bytecode(&raw_trace, &bytecode)?;
+        Ok([1, 4, 7, 9]
+            .into_iter()
+            .map(|id| parser.resolve_recovered(id))
+            .collect())
+    };
+
+    let first = names()?;
+    for _ in 0..8 {
+        assert_eq!(names()?, first);
+    }
+    // Intrinsics are numbered in recovered id order.
+    let expected = (0..4)
+        .map(|i| Some(format!("intrinsic_fn_{i}")))
+        .collect::<Vec<_>>();
+    assert_eq!(first, expected);
+    Ok(())
+}
+
+#[test]
+fn intrinsics_with_identical_opcodes_are_merged() -> Result<()> {
+    let bytecode = add()?;
+    // Intrinsics 4 and 7 execute the same opcodes, unlike intrinsic 9.
+    let mut raw_trace = String::from("header\n");
+    for (id, pc, op, fuel) in [(4, 7, "ff", 1), (7, 7, "ff", 2), (9, 3, "fe", 5)] {
+        raw_trace.push_str(&format!(
+            "{id},0,START,0,\n{id},{pc},{op},{fuel},\n{id},0,END,0,\n"
+        ));
+    }
+    let options = ReportOptions {
+        merge_intrinsics: true,
+        ..Default::default()
+    };
+
+    let mut parser = ProfileTraceParser::from_bytecode(&raw_trace, &bytecode)?;
+    parser.merge_intrinsics();
+    assert_eq!(parser.resolve_recovered(4), parser.resolve_recovered(7));
+    assert_ne!(parser.resolve_recovered(4), parser.resolve_recovered(9));
+
+    let mut summary = summary_with(&bytecode, &raw_trace, &options)?
+        .into_iter()
+        .map(|s| (s.name, s.calls, s.self_fuel))
+        .collect::<Vec<_>>();
+    summary.sort();
+    assert_eq!(
+        summary,
+        [
+            ("intrinsic_fn_0".to_string(), 2, 3),
+            ("intrinsic_fn_1".to_string(), 1, 5),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use common::NESTED_TRACE;
+use jac_testutil::compile_fixture;
+use jac_translate::{quickpars::Opcode, Translation, TranslationBuilder};
+use parsetrace::{ProfileTraceParser, ReportOptions, ReportOrder};
+
+mod common;
+
+/// A raw trace of a single function, whose opcodes consumed 10, 42 and 3
+/// fuel.
+const FLAT_TRACE: &str =
+    "header\n0,0,00,7,\n1,0,START,0,\n1,4,0c,10,\n1,5,0e,42,\n1,6,0c,3,\n1,0,END,0,\n";
+
+/// Returns the fuel of an opcode report line.
+fn fuel(line: &str) -> u32 {
+    line.rsplit("fuel_cost: ").next().unwrap().parse().unwrap()
+}
+
+/// Returns the fuel consumed by the event of a report line, if any.
+fn delta(line: &str) -> u64 {
+    ["fuel_cost: ", "SYSTEM COST: "]
+        .iter()
+        .find_map(|label| line.split_once(label))
+        .map_or(0, |(_, fuel)| fuel.trim().parse().unwrap())
+}
+
+#[test]
+fn fuel_order_starts_with_the_most_expensive_opcode() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(FLAT_TRACE, &translation)?;
+    let report = parser.report_trace_with(&ReportOptions {
+        sort_by: ReportOrder::Fuel,
+        ..Default::default()
+    })?;
+
+    // Only the opcode executions are reported, flattened.
+    assert_eq!(report.len(), 3);
+    assert!(report.iter().all(|line| !line.starts_with(' ')));
+    let fuels = report.iter().map(|line| fuel(line)).collect::<Vec<_>>();
+    assert_eq!(fuels, vec![42, 10, 3]);
+    Ok(())
+}
+
+#[test]
+fn time_order_is_the_default() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(FLAT_TRACE, &translation)?;
+    let report = parser.report_trace()?;
+
+    assert_eq!(report.len(), 6);
+    assert!(report[1].starts_with("FUNCTION START"));
+    assert_eq!(fuel(&report[2]), 10);
+    Ok(())
+}
+
+#[test]
+fn cumulative_fuel_is_the_sum_of_prior_deltas() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(NESTED_TRACE, &translation)?;
+    let report = parser.report_trace_with(&ReportOptions {
+        cumulative: true,
+        ..Default::default()
+    })?;
+
+    let mut total = 0;
+    let mut previous = 0;
+    for line in &report {
+        let (cumulative, entry) = line.split_once(' ').unwrap();
+        let cumulative = cumulative.parse::<u64>()?;
+        total += delta(entry);
+        assert_eq!(cumulative, total, "{line}");
+        assert!(cumulative >= previous, "{line}");
+        previous = cumulative;
+    }
+    assert_eq!(total, 42);
+    Ok(())
+}
+
+#[test]
+fn percentages_add_up_per_function() -> Result<()> {
+    let translation = Translation::default();
+    let parser = ProfileTraceParser::new(NESTED_TRACE, &translation)?;
+    let report = parser.report_trace_with(&ReportOptions {
+        pct_per_line: true,
+        ..Default::default()
+    })?;
+
+    let pct = |line: &String| -> f64 {
+        let start = line.rfind('(').unwrap() + 1;
+        line[start..line.len() - 2].parse().unwrap()
+    };
+    let opcode_lines = report
+        .iter()
+        .filter(|line| line.contains("fuel_cost"))
+        .collect::<Vec<_>>();
+    assert_eq!(opcode_lines.len(), 4);
+    // The opcodes of the outer function consumed 10, 7 and 13 fuel.
+    let outer = opcode_lines
+        .iter()
+        .filter(|line| !line.starts_with("    "))
+        .map(|line| pct(line))
+        .sum::<f64>();
+    assert!((outer - 100.0).abs() < 0.2, "unexpected total {outer}");
+    let inner = opcode_lines
+        .iter()
+        .find(|line| line.starts_with("    "))
+        .unwrap();
+    assert_eq!(pct(inner), 100.0);
+    Ok(())
+}
+
+#[test]
+fn matched_lines_are_prefixed_with_a_single_offset() -> Result<()> {
+    let bytecode = compile_fixture("function add(a, b) { return a + b; }\nadd(1, 2);");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = translation.function_by_name("add").unwrap();
+    let mut pcs = vec![];
+    let mut raw_trace = String::from("header\n1,0,START,0,\n");
+    let mut reader = func.operators_reader();
+    while !reader.done() {
+        let (pc, op) = Opcode::from_reader(&mut reader)?;
+        if pc != 0 {
+            raw_trace.push_str(&format!("1,{},{:02x},1,\n", pc, op.discriminant()));
+            pcs.push(pc);
+        }
+    }
+    raw_trace.push_str("1,0,END,0,\n");
+
+    let parser = ProfileTraceParser::new(&raw_trace, &translation)?;
+    let opcode_lines = |offsets| -> Result<Vec<String>> {
+        Ok(parser
+            .report_trace_with(&ReportOptions {
+                offsets,
+                ..Default::default()
+            })?
+            .into_iter()
+            .filter(|line| line.contains("fuel_cost"))
+            .map(|line| line.trim_start().to_string())
+            .collect())
+    };
+    assert!(parser
+        .report_trace()?
+        .iter()
+        .any(|line| line.contains("FUNCTION START add")));
+
+    let with_offsets = opcode_lines(true)?;
+    let without_offsets = opcode_lines(false)?;
+    assert_eq!(with_offsets.len(), pcs.len());
+    for ((line, bare), pc) in with_offsets.iter().zip(&without_offsets).zip(&pcs) {
+        assert_eq!(*line, format!("{pc}: {bare}"));
+        assert!(
+            !bare.starts_with(char::is_numeric),
+            "unexpected offset: {bare}"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use parsetrace::{to_csv, FunctionSummary};
+
+/// Returns the summary of a function with the given name and fuel.
+fn summary(name: &str, self_fuel: u64, total_fuel: u64, calls: u64) -> FunctionSummary {
+    FunctionSummary {
+        name: name.to_string(),
+        self_fuel,
+        total_fuel,
+        calls,
+    }
+}
+
+#[test]
+fn summary_round_trips_through_a_csv_reader() -> Result<()> {
+    let summaries = [
+        summary("[\"a,b\"]", 30, 40, 2),
+        summary("say \"hi\"", 10, 10, 1),
+        summary("plain", 0, 10, 1),
+    ];
+    let csv = to_csv(&summaries);
+    // Names with a separator or quotes are quoted, doubling the quotes.
+    assert!(csv.contains("\"[\"\"a,b\"\"]\",30,40,2,75.00\n"), "{csv}");
+    assert!(csv.contains("plain,0,10,1,0.00\n"), "{csv}");
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(
+        reader.headers()?,
+        vec!["function", "self_fuel", "total_fuel", "calls", "pct"]
+    );
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records.len(), summaries.len());
+    for (record, summary) in records.iter().zip(&summaries) {
+        assert_eq!(&record[0], summary.name);
+        assert_eq!(record[1].parse::<u64>()?, summary.self_fuel);
+        assert_eq!(record[2].parse::<u64>()?, summary.total_fuel);
+        assert_eq!(record[3].parse::<u64>()?, summary.calls);
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use jac_translate::{quickpars::Opcode, TranslationBuilder};
+use javy::{Config, Runtime};
+use parsetrace::ProfileTraceParser;
+
+const DEPTH: usize = 8;
+
+#[test]
+fn tail_calls_do_not_deepen_the_report() -> Result<()> {
+    let runtime = Runtime::new(Config::default())?;
+    let bytecode = runtime.compile_to_bytecode(
+        "index.js",
+        "function count(n) { if (n === 0) return 0; return count(n - 1); }\ncount(8);",
+    )?;
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = translation.function_by_name("count").unwrap();
+    let mut operators = vec![];
+    let mut reader = func.operators_reader();
+    while !reader.done() {
+        operators.push(Opcode::from_reader(&mut reader)?);
+    }
+    let tail_call_pc = operators
+        .iter()
+        .find(|(_, op)| matches!(op, Opcode::TailCall { .. }))
+        .map(|(pc, _)| *pc)
+        .expect("`return count(n - 1)` to be a tail call");
+
+    // Each invocation runs up to the tail call, except for the last one.
+    let mut raw_trace = String::from("header\n");
+    for _ in 0..DEPTH {
+        raw_trace.push_str("1,0,START,0,\n");
+        for (pc, op) in operators.iter().filter(|(pc, _)| *pc != 0) {
+            if *pc <= tail_call_pc {
+                raw_trace.push_str(&format!("1,{},{:02x},1,\n", pc, op.discriminant()));
+            }
+        }
+    }
+    raw_trace.push_str(&"1,0,END,0,\n".repeat(DEPTH));
+
+    let parser = ProfileTraceParser::new(&raw_trace, &translation)?;
+    assert_eq!(
+        parser.tail_calls(),
+        vec![("count".to_string(), DEPTH as u32)]
+    );
+
+    let report = parser.report_trace()?;
+    let indent = |line: &String| line.len() - line.trim_start().len();
+    assert!(report.iter().all(|line| indent(line) <= 2));
+    let starts = report
+        .iter()
+        .filter(|line| line.starts_with("FUNCTION START count"))
+        .collect::<Vec<_>>();
+    assert_eq!(starts.len(), DEPTH);
+    assert!(starts[1..]
+        .iter()
+        .all(|line| line.ends_with("(tail call):")));
+    assert_eq!(report.last().unwrap(), "FUNCTION END count");
+    Ok(())
+}
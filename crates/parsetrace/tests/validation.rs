@@ -0,0 +1,52 @@
+use anyhow::Result;
+use jac_testutil::translation_from_ops;
+use jac_translate::quickpars::Opcode;
+use parsetrace::{ProfileTraceParser, MIN_MATCH_RATE};
+
+/// Returns a trace executing the given operators once, one byte each,
+/// after the first one.
+fn trace(ops: &[Opcode]) -> String {
+    let mut raw_trace = String::from("header\n1,0,START,0,\n");
+    for (pc, op) in ops.iter().enumerate().skip(1) {
+        raw_trace.push_str(&format!("1,{},{:02x},1,\n", pc, op.discriminant()));
+    }
+    raw_trace.push_str("1,0,END,0,\n");
+    raw_trace
+}
+
+#[test]
+fn matching_trace_is_not_suspicious() -> Result<()> {
+    let ops = [
+        Opcode::Undefined,
+        Opcode::Null,
+        Opcode::Drop,
+        Opcode::Return,
+    ];
+    let translation = translation_from_ops(&ops);
+    let parser = ProfileTraceParser::new(&trace(&ops), &translation)?;
+
+    let report = parser.validate()?;
+    assert_eq!(report.recovered_opcodes, 3);
+    assert_eq!(report.matched_opcodes, 3);
+    assert!(!report.is_suspicious());
+    Ok(())
+}
+
+#[test]
+fn trace_of_different_bytecode_is_suspicious() -> Result<()> {
+    let translation = translation_from_ops(&[
+        Opcode::Undefined,
+        Opcode::Null,
+        Opcode::Drop,
+        Opcode::Return,
+    ]);
+    let other = [Opcode::Null, Opcode::PushTrue, Opcode::Not, Opcode::Return];
+    let parser = ProfileTraceParser::new(&trace(&other), &translation)?;
+
+    let report = parser.validate()?;
+    assert_eq!(report.recovered_opcodes, 3);
+    assert_eq!(report.matched_opcodes, 0);
+    assert!(report.match_rate() < MIN_MATCH_RATE);
+    assert!(report.is_suspicious());
+    Ok(())
+}
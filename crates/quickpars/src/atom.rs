@@ -231,3 +231,48 @@ pub const ATOM_NAMES: [&str; 228] = [
     "Symbol.asyncIterator",
     "Symbol.operatorSet",
 ];
+
+/// A table of built-in atoms, which precede the interned atoms of the
+/// bytecode.
+///
+/// Built-in atoms differ across QuickJS builds; parsing with a table that
+/// doesn't match the engine that produced the bytecode shifts the indices
+/// of every interned atom.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum AtomTable {
+    /// QuickJS 2021-03-27 with BIGNUM support, as patched by `rquickjs`
+    /// to add the `columnNumber` atom, i.e., [ATOM_NAMES]. Used by Javy.
+    #[default]
+    Rquickjs,
+    /// Upstream QuickJS 2021-03-27 with BIGNUM support.
+    Upstream,
+}
+
+impl AtomTable {
+    /// Returns the built-in atom names, in index order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        let table = *self;
+        ATOM_NAMES
+            .into_iter()
+            .filter(move |name| table == AtomTable::Rquickjs || *name != "columnNumber")
+    }
+}
+
+/// Escapes an atom name for display.
+///
+/// Names which are valid identifiers are returned as is; any other name is
+/// wrapped in quotes, with quotes and non-printable characters escaped, so
+/// that names derived from string literals can't corrupt line-based output.
+pub fn debug_escape(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+
+    if is_ident {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
@@ -7,6 +7,35 @@ use anyhow::{bail, ensure, Result};
 // means that the engine is compiled with BIGNUM support.
 pub const VERSION: u8 = 67;
 
+/// Bit set in the bytecode version when the engine is compiled with BIGNUM
+/// support.
+pub const BIGNUM_VERSION_FLAG: u8 = 0x40;
+
+/// Information inferred from a bytecode version.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// The bytecode version.
+    pub version: u8,
+    /// Whether the engine that produced the bytecode was compiled with BIGNUM
+    /// support.
+    pub has_bignum: bool,
+    /// The QuickJS base bytecode version, without feature flags.
+    pub quickjs_base: u8,
+}
+
+/// Describes the given bytecode version.
+///
+/// QuickJS derives the bytecode version from a base version, setting
+/// [BIGNUM_VERSION_FLAG] when the engine is compiled with BIGNUM support;
+/// e.g., `67` is base version `3` with BIGNUM.
+pub fn describe_version(version: u8) -> VersionInfo {
+    VersionInfo {
+        version,
+        has_bignum: version & BIGNUM_VERSION_FLAG != 0,
+        quickjs_base: version & !BIGNUM_VERSION_FLAG,
+    }
+}
+
 /// Bytecode tags.
 ///
 /// Each tag represents a value or a section in the bytecode.
@@ -39,8 +68,20 @@ impl Tag {
     /// Maps an arbitrary byte to a [Tag].
     pub fn map_byte(byte: u8) -> Result<Tag> {
         Ok(match byte {
+            1 => Tag::Null,
+            2 => Tag::Undefined,
+            3 => Tag::False,
+            4 => Tag::True,
+            5 => Tag::I32,
+            6 => Tag::F64,
+            7 => Tag::String,
+            8 => Tag::Object,
+            9 => Tag::Array,
+            11 => Tag::TemplateObject,
             12 => Tag::FunctionBytecode,
             13 => Tag::Module,
+            18 => Tag::ObjectValue,
+            19 => Tag::ObjectRef,
             _ => bail!("Unknown tag: {byte}"),
         })
     }
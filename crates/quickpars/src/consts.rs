@@ -1,2 +1,7 @@
 pub const JS_EXPORT_TYPE_LOCAL: u8 = 0;
 pub const JS_EXPORT_TYPE_INDIRECT: u8 = 1;
+
+// Encoding of the pc to line and pc to column debug streams.
+pub const PC2LINE_BASE: i32 = -1;
+pub const PC2LINE_RANGE: u8 = 5;
+pub const PC2LINE_OP_FIRST: u8 = 1;
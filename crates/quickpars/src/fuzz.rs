@@ -0,0 +1,57 @@
+//! [Arbitrary] implementations of the bytecode structures, to generate
+//! random but valid modules when fuzzing.
+
+use crate::{AtomIndex, BinaryReader, FunctionLocal, FunctionSectionHeader, Opcode};
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+/// The maximum length in bytes of the immediates of an opcode, e.g., the
+/// atom, diff and flag of `WithGetVar`.
+const MAX_IMMEDIATES_LEN: usize = 9;
+
+impl<'a> Arbitrary<'a> for Opcode {
+    /// Generates opcodes that can be decoded, by decoding an arbitrary
+    /// opcode byte followed by arbitrary immediates. Bytes that don't
+    /// decode, e.g., unsupported opcodes, are rejected.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut bytes = [0; MAX_IMMEDIATES_LEN + 1];
+        u.fill_buffer(&mut bytes)?;
+        Opcode::from_reader(&mut BinaryReader::new(&bytes))
+            .map(|(_, op)| op)
+            .map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+impl<'a> Arbitrary<'a> for FunctionSectionHeader {
+    /// Generates headers whose counts are consistent, i.e., the defined
+    /// arguments are a subset of the arguments, which are a subset of the
+    /// locals.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let local_count = u.int_in_range(0..=u16::MAX as u32)?;
+        let arg_count = u.int_in_range(0..=local_count)?;
+        let defined_arg_count = u.int_in_range(0..=arg_count)?;
+        Ok(Self {
+            flags: u.arbitrary()?,
+            name_index: AtomIndex::from_u32(u.arbitrary()?),
+            arg_count,
+            var_count: local_count - arg_count,
+            defined_arg_count,
+            stack_size: u.arbitrary()?,
+            closure_var_count: u.arbitrary()?,
+            constant_pool_size: u.arbitrary()?,
+            bytecode_len: u.arbitrary()?,
+            local_count,
+            is_strict: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for FunctionLocal {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            name_index: AtomIndex::from_u32(u.arbitrary()?),
+            scope_level: u.arbitrary()?,
+            scope_next: u.arbitrary()?,
+            flags: u.arbitrary()?,
+        })
+    }
+}
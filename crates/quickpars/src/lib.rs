@@ -2,7 +2,7 @@
 
 use core::str;
 
-use anyhow::{anyhow, ensure, Context, Result};
+use anyhow::{bail, ensure, Result};
 
 pub mod atom;
 pub use atom::*;
@@ -10,16 +10,22 @@ pub mod bc;
 pub use bc::*;
 pub mod consts;
 pub use consts::*;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
 pub mod op;
 pub use op::*;
 pub mod readers;
 pub use readers::*;
 pub mod sections;
 pub use sections::*;
+pub mod strip;
+pub use strip::*;
+pub mod value;
+pub use value::*;
 
 macro_rules! entity {
     ($name:ident) => {
-        #[derive(Copy, Clone, Debug)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
         pub struct $name(u32);
 
         impl Default for $name {
@@ -49,6 +55,9 @@ entity!(ClosureVarIndex);
 entity!(FuncIndex);
 entity!(ConstantPoolIndex);
 
+/// The function flag signaling that the function encodes debug information.
+pub const DEBUG_FLAG: u16 = 1 << 9;
+
 /// Known payload in the bytecode.
 #[derive(Debug, Clone)]
 pub enum Payload<'a> {
@@ -60,6 +69,9 @@ pub enum Payload<'a> {
     FunctionClosureVars(Vec<FunctionClosureVar>),
     FunctionDebugInfo(DebugInfo<'a>),
     FunctionOperators(BinaryReader<'a>),
+    /// A constant value, either in a function's constant pool or at the
+    /// top-level.
+    Value(ConstValue),
     End,
 }
 
@@ -100,6 +112,66 @@ struct FuncMeta {
     debug: bool,
 }
 
+/// A saved [Parser] position, taken at a function boundary.
+///
+/// At a function boundary the parser holds no function metadata and expects
+/// a tag next, so the offset, along with the number of objects read so far,
+/// which later object references refer to, is enough to resume parsing.
+/// Both can be persisted through [ParserSnapshot::offset] and
+/// [ParserSnapshot::objects], and restored through
+/// [ParserSnapshot::from_parts].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParserSnapshot {
+    /// The offset of the next tag.
+    offset: usize,
+    /// The number of objects read before the offset.
+    objects: u32,
+}
+
+impl ParserSnapshot {
+    /// Constructs a snapshot from a previously saved offset, before which
+    /// no objects were read, e.g., the one of the first function.
+    pub fn from_offset(offset: usize) -> Self {
+        Self::from_parts(offset, 0)
+    }
+
+    /// Constructs a snapshot from a previously saved offset and object
+    /// count.
+    pub fn from_parts(offset: usize, objects: u32) -> Self {
+        Self { offset, objects }
+    }
+
+    /// Returns the offset of the next tag in the bytecode.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of objects read before the offset, see
+    /// [ConstValue::ObjectRef].
+    pub fn objects(&self) -> u32 {
+        self.objects
+    }
+}
+
+/// An entry of the table of contents of the bytecode, see
+/// [Parser::table_of_contents].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionEntry {
+    /// The index of the function, in the order in which functions appear
+    /// in the bytecode.
+    pub index: FuncIndex,
+    /// The function name.
+    pub name: String,
+    /// The argument count.
+    pub arg_count: u32,
+    /// The number of locals.
+    pub local_count: u32,
+    /// The function bytecode length.
+    pub bytecode_len: u32,
+    /// The offset of the function tag in the bytecode.
+    pub byte_offset: usize,
+}
+
 /// A QuickJS bytecode parser.
 #[derive(Debug, Copy, Clone)]
 pub struct Parser {
@@ -111,8 +183,24 @@ pub struct Parser {
     done: bool,
     /// Metadata about the current function.
     meta: Option<FuncMeta>,
+    /// Whether atom strings are skipped when parsing the header.
+    skip_atoms: bool,
+    /// Whether parsing resumes at the next function after an error in a
+    /// function.
+    recovery: bool,
+    /// The built-in atoms preceding the interned atoms.
+    builtin_atoms: AtomTable,
+    /// The maximum nesting depth of constant arrays and objects.
+    max_depth: usize,
+    /// The number of objects read so far in the current bytecode object,
+    /// which [Tag::ObjectRef] ids refer to.
+    objects: u32,
 }
 
+/// The default maximum nesting depth of constant values, see
+/// [Parser::with_max_depth].
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
 impl Parser {
     /// Create a new [Parser].
     pub fn new() -> Self {
@@ -121,27 +209,162 @@ impl Parser {
             offset: 0,
             done: false,
             meta: None,
+            skip_atoms: false,
+            recovery: false,
+            builtin_atoms: AtomTable::default(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            objects: 0,
         }
     }
+
+    /// Configures the parser to skip to the function section.
+    ///
+    /// The header is still parsed, to account for its bytes, but the
+    /// interned atom strings are skipped without being decoded: the emitted
+    /// [Payload::Header] reports the interned atom count, but only holds
+    /// the built-in atoms. This is useful at compile time, where the runtime
+    /// re-interns the atoms when reading the bytecode.
+    ///
+    /// Since the names of interned atoms are unknown, they must be resolved
+    /// through [HeaderSection::atom], which returns `None` for them.
+    pub fn skip_to_functions(mut self) -> Self {
+        self.skip_atoms = true;
+        self
+    }
+
+    /// Configures the built-in atoms that precede the interned atoms in
+    /// [Payload::Header], which must match the QuickJS build that produced
+    /// the bytecode. Defaults to [AtomTable::Rquickjs].
+    pub fn with_builtin_atoms(mut self, table: AtomTable) -> Self {
+        self.builtin_atoms = table;
+        self
+    }
+
+    /// Configures the maximum nesting depth of constant arrays and objects,
+    /// which are parsed recursively. Values nested deeper produce an error
+    /// instead of overflowing the stack. Defaults to [DEFAULT_MAX_DEPTH].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Configures the parser to recover from errors in functions.
+    ///
+    /// When a function fails to parse, the error is still emitted, but
+    /// instead of stopping, the parser skips to the next function that
+    /// parses successfully and continues from there. Since the operators
+    /// of a function are `bytecode_len` bytes long, errors before the
+    /// operators skip at least that many bytes. This is useful to inspect
+    /// the functions of partially corrupt bytecode; the skipped functions
+    /// may leave their constant pools incomplete.
+    pub fn with_recovery(mut self) -> Self {
+        self.recovery = true;
+        self
+    }
+
+    /// Resumes parsing from a snapshot taken through
+    /// [Parser::state_snapshot].
+    ///
+    /// The bytes before the snapshot offset are expected to be identical to
+    /// the ones the snapshot was taken from; the payloads produced before
+    /// the snapshot, in particular the atoms in the header, are not parsed
+    /// again and must be kept by the caller.
+    ///
+    /// The resumed parser keeps the configuration of this one, e.g., its
+    /// maximum depth and recovery.
+    pub fn resume(&self, snapshot: ParserSnapshot, data: &[u8]) -> Result<Self> {
+        ensure!(
+            snapshot.offset <= data.len(),
+            "Snapshot offset {} is out of bounds, bytecode length is {}",
+            snapshot.offset,
+            data.len()
+        );
+        Ok(Self {
+            state: ParserState::Tags,
+            offset: snapshot.offset,
+            done: snapshot.offset == data.len(),
+            meta: None,
+            objects: snapshot.objects,
+            ..*self
+        })
+    }
 }
 
 impl Parser {
     /// Parse the entire bytecode buffer.
     pub fn parse_buffer(self, data: &[u8]) -> impl Iterator<Item = Result<Payload<'_>>> {
         let mut parser = self;
-        std::iter::from_fn(move || {
-            if parser.done {
-                return None;
+        std::iter::from_fn(move || parser.parse_next(data))
+    }
+
+    /// Parse the next payload in the bytecode buffer, if any.
+    pub fn parse_next<'a>(&mut self, data: &'a [u8]) -> Option<Result<Payload<'a>>> {
+        if self.done {
+            return None;
+        }
+        Some(self.parse(data))
+    }
+
+    /// Lists the functions in the bytecode, from their headers.
+    ///
+    /// Operators and debug information are skipped without being decoded,
+    /// which makes this cheaper than a full translation when only the
+    /// functions' metadata is needed.
+    pub fn table_of_contents(data: &[u8]) -> Result<Vec<FunctionEntry>> {
+        let mut parser = Parser::new();
+        let mut atoms = vec![];
+        let mut entries = vec![];
+        loop {
+            let byte_offset = parser.offset;
+            let Some(payload) = parser.parse_next(data) else {
+                break;
+            };
+            match payload? {
+                Payload::Header(header) => atoms = header.atoms,
+                Payload::FunctionHeader(header) => entries.push(FunctionEntry {
+                    index: FuncIndex::from_u32(entries.len() as u32),
+                    name: atoms
+                        .get(header.name_index.as_u32() as usize)
+                        .cloned()
+                        .unwrap_or_default(),
+                    arg_count: header.arg_count,
+                    local_count: header.local_count,
+                    bytecode_len: header.bytecode_len,
+                    byte_offset,
+                }),
+                _ => {}
             }
-            Some(parser.parse(data))
+        }
+
+        Ok(entries)
+    }
+
+    /// Takes a snapshot of the parser position.
+    ///
+    /// Snapshots can only be taken at function boundaries, i.e., when the
+    /// next payload is a tag: right after the bytecode header, a module
+    /// header or the last section of a function. Resuming in the middle of a
+    /// function is not supported, since the function's sections depend on
+    /// its header.
+    pub fn state_snapshot(&self) -> Result<ParserSnapshot> {
+        ensure!(
+            self.state == ParserState::Tags,
+            "Snapshots can only be taken at function boundaries, current state: {:?}",
+            self.state
+        );
+        Ok(ParserSnapshot {
+            offset: self.offset,
+            objects: self.objects,
         })
     }
 
     /// Intermeidate parsing helper.
     fn parse<'a>(&mut self, data: &'a [u8]) -> Result<Payload<'a>> {
         // Every time `parse` is called, make sure to update the view of data
-        // that we're parsing via `&data[self.offset...]`
-        let mut reader = BinaryReader::new(&data[self.offset..]);
+        // that we're parsing via `&data[self.offset...]`. The reader keeps
+        // track of `self.offset` as its origin so that errors, including
+        // those raised by sub-readers, are reported at absolute offsets.
+        let mut reader = BinaryReader::with_origin(&data[self.offset..], self.offset);
         match self.parse_with(&mut reader) {
             Ok(payload) => {
                 self.offset += reader.offset;
@@ -151,16 +374,66 @@ impl Parser {
                 Ok(payload)
             }
             Err(err) => {
+                let failed_at = reader.absolute_offset();
+                let err = err.context(format!(
+                    "Failed to parse bytecode at offset: {} and state: {:?}",
+                    failed_at, self.state,
+                ));
+                if self.recovery {
+                    if let Some(offset) = self.resync(data, failed_at) {
+                        self.offset = offset;
+                        self.state = ParserState::Tags;
+                        self.meta = None;
+                        return Err(err);
+                    }
+                }
                 self.done = true;
-                Err(err).with_context(|| {
-                    format!(
-                        "Failed to parse bytecode at offset: {} and state: {:?}",
-                        self.offset + reader.offset,
-                        self.state,
-                    )
-                })
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the offset of the next function that parses successfully,
+    /// after a failure at `failed_at` in the current function.
+    fn resync(&self, data: &[u8], failed_at: usize) -> Option<usize> {
+        let from = match (self.state, self.meta) {
+            (ParserState::Version | ParserState::Header | ParserState::End, _) => return None,
+            // The operators are yet to be read, so they can be skipped.
+            (
+                ParserState::FunctionLocals
+                | ParserState::FunctionClosureVars
+                | ParserState::FunctionOperators,
+                Some(meta),
+            ) => failed_at + meta.bytecode_len as usize,
+            _ => failed_at + 1,
+        };
+
+        (from..data.len()).find(|offset| {
+            data[*offset] == Tag::FunctionBytecode as u8 && self.parses_function_at(data, *offset)
+        })
+    }
+
+    /// Whether a whole function, from its tag to the end of its debug
+    /// information, parses successfully at the given offset, with the
+    /// configuration of this parser.
+    fn parses_function_at(&self, data: &[u8], offset: usize) -> bool {
+        let mut parser = Parser {
+            state: ParserState::Tags,
+            offset,
+            done: false,
+            meta: None,
+            recovery: false,
+            ..*self
+        };
+        while let Some(payload) = parser.parse_next(data) {
+            if payload.is_err() {
+                return false;
+            }
+            if parser.state == ParserState::Tags {
+                return true;
             }
         }
+        false
     }
 
     /// Performs binary parsing with the provided binary reader.
@@ -178,10 +451,18 @@ impl Parser {
                 }),
 
             ParserState::Header => self.parse_header(reader),
-            ParserState::Tags => reader
-                .read_u8()
-                .and_then(Tag::map_byte)
-                .and_then(|tag| self.parse_tag(tag, reader)),
+            ParserState::Tags => {
+                let byte = reader.read_u8()?;
+                // The version byte doesn't overlap with any tag, so when
+                // found in place of a tag it marks the start of another
+                // bytecode object concatenated to the current one.
+                if byte == VERSION {
+                    self.state = ParserState::Header;
+                    self.objects = 0;
+                    return Ok(Version(byte));
+                }
+                Tag::map_byte(byte).and_then(|tag| self.parse_tag(tag, reader))
+            }
             ParserState::FunctionLocals => self.parse_local_section(reader),
             ParserState::FunctionClosureVars => self.parse_closure_var_section(reader),
             ParserState::FunctionOperators => self.parse_operators_section(reader),
@@ -211,9 +492,8 @@ impl Parser {
             Tag::Module => self.parse_module_header(reader),
             Tag::FunctionBytecode => {
                 let flags = reader.read_u16()?;
-                // JS mode.
-                // Are we in `strict` mode?
-                reader.read_u8()?;
+                // JS mode, where the lowest bit indicates strict mode.
+                let js_mode = reader.read_u8()?;
                 // Function name.
                 let name_index = reader.read_atom()?;
                 // Arg count.
@@ -248,9 +528,16 @@ impl Parser {
                     constant_pool_size,
                     bytecode_len,
                     local_count,
+                    is_strict: js_mode & 1 != 0,
                 }));
             }
-            x => Err(anyhow!("Unsupported {x:?}")),
+            tag => {
+                // Only account the objects of values that parse successfully.
+                let mut objects = self.objects;
+                let value = read_value(tag, reader, 0, self.max_depth, &mut objects)?;
+                self.objects = objects;
+                Ok(Payload::Value(value))
+            }
         };
         if reader.done() {
             self.state = ParserState::End;
@@ -383,17 +670,26 @@ impl Parser {
             )
         );
         let atom_count = reader.read_leb128()?;
-        let mut atoms = ATOM_NAMES
-            .into_iter()
+        let mut atoms = self
+            .builtin_atoms
+            .names()
             .map(str::to_string)
             .collect::<Vec<_>>();
+        let builtin_atom_count = atoms.len();
         for _ in 0..atom_count {
-            atoms.push(str::from_utf8(read_str_bytes(reader)?)?.to_string());
+            let bytes = read_str_bytes(reader)?;
+            if !self.skip_atoms {
+                atoms.push(str::from_utf8(bytes)?.to_string());
+            }
         }
 
         self.state = ParserState::Tags;
 
-        Ok(Payload::Header(HeaderSection::new(atom_count, atoms)))
+        Ok(Payload::Header(HeaderSection::new(
+            atom_count,
+            atoms,
+            builtin_atom_count,
+        )))
     }
 
     // Parse the module header.
@@ -476,6 +772,13 @@ impl Parser {
             })
             // has_tla
             .and_then(|_| reader.read_u8())?;
+        // Any other value usually means that the parsing of the preceding
+        // entries drifted.
+        ensure!(
+            has_tla <= 1,
+            "Invalid module has_tla value {}, expected 0 or 1; the module section is likely out of sync",
+            has_tla
+        );
         Ok(Payload::ModuleHeader(ModuleSectionHeader::new(
             name_entity,
             req_modules,
@@ -486,3 +789,119 @@ impl Parser {
         )))
     }
 }
+
+/// Reads a value with the given tag, recursing through nested arrays and
+/// objects.
+///
+/// `depth` is the number of arrays and objects enclosing the value, which
+/// can't exceed `max_depth`.
+///
+/// `objects` is the number of objects read so far in the bytecode object,
+/// which [Tag::ObjectRef] ids refer to.
+fn read_value(
+    tag: Tag,
+    reader: &mut BinaryReader<'_>,
+    depth: usize,
+    max_depth: usize,
+    objects: &mut u32,
+) -> Result<ConstValue> {
+    if matches!(tag, Tag::Array | Tag::Object | Tag::ObjectValue) {
+        ensure!(
+            depth < max_depth,
+            "Constant value nesting exceeds the maximum depth of {}",
+            max_depth
+        );
+    }
+    // Objects are numbered before reading their contents, so that they
+    // can refer to themselves.
+    match tag {
+        Tag::Array | Tag::Object | Tag::ObjectValue => *objects += 1,
+        // The cooked and raw strings arrays.
+        Tag::TemplateObject => *objects += 2,
+        _ => {}
+    }
+    Ok(match tag {
+        Tag::Null => ConstValue::Null,
+        Tag::Undefined => ConstValue::Undefined,
+        Tag::False => ConstValue::Bool(false),
+        Tag::True => ConstValue::Bool(true),
+        Tag::I32 => ConstValue::I32(reader.read_sleb128()?),
+        Tag::F64 => ConstValue::F64(reader.read_f64()?),
+        Tag::String => ConstValue::String(read_string(reader)?),
+        Tag::TemplateObject => read_template_object(reader)?,
+        Tag::Array => {
+            let len = reader.read_leb128()?;
+            let mut elements = vec![];
+            for _ in 0..len {
+                let tag = Tag::map_byte(reader.read_u8()?)?;
+                elements.push(read_value(tag, reader, depth + 1, max_depth, objects)?);
+            }
+            ConstValue::Array(elements)
+        }
+        Tag::Object => {
+            let len = reader.read_leb128()?;
+            let mut props = vec![];
+            for _ in 0..len {
+                let key = AtomIndex::from_u32(reader.read_atom()?);
+                let tag = Tag::map_byte(reader.read_u8()?)?;
+                props.push((key, read_value(tag, reader, depth + 1, max_depth, objects)?));
+            }
+            ConstValue::Object(props)
+        }
+        Tag::ObjectValue => {
+            let tag = Tag::map_byte(reader.read_u8()?)?;
+            ConstValue::ObjectValue(Box::new(read_value(
+                tag,
+                reader,
+                depth + 1,
+                max_depth,
+                objects,
+            )?))
+        }
+        Tag::ObjectRef => {
+            let id = reader.read_leb128()?;
+            // Only objects read so far can be referenced, which rules out
+            // cycles through references that were never defined.
+            ensure!(
+                id < *objects,
+                "Invalid object reference {} >= {}",
+                id,
+                objects
+            );
+            ConstValue::ObjectRef(id)
+        }
+        x => bail!("Unsupported {x:?}"),
+    })
+}
+
+/// Reads a template object: an array of cooked strings, or `undefined` for
+/// cooked strings with invalid escapes, followed by the array of raw
+/// strings.
+fn read_template_object(reader: &mut BinaryReader<'_>) -> Result<ConstValue> {
+    let len = reader.read_leb128()?;
+    let mut cooked = vec![];
+    for _ in 0..len {
+        cooked.push(match Tag::map_byte(reader.read_u8()?)? {
+            Tag::String => Some(read_string(reader)?),
+            Tag::Undefined => None,
+            tag => bail!("Unexpected {tag:?} in template object"),
+        });
+    }
+
+    let tag = reader.read_u8()?;
+    ensure!(
+        tag == Tag::Array as u8,
+        "Expected the raw strings array of the template object, found tag {}",
+        tag
+    );
+    let len = reader.read_leb128()?;
+    let mut raw = vec![];
+    for _ in 0..len {
+        match Tag::map_byte(reader.read_u8()?)? {
+            Tag::String => raw.push(read_string(reader)?),
+            tag => bail!("Unexpected {tag:?} in template object raw strings"),
+        }
+    }
+
+    Ok(ConstValue::TemplateObject { cooked, raw })
+}
@@ -1,10 +1,11 @@
 use crate::readers::BinaryReader;
 use crate::{AtomIndex, ClosureVarIndex, ConstantPoolIndex, LocalIndex};
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
+use std::fmt;
 
 /// A QuickJS operator code.
 #[repr(u8)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Opcode {
     /// A marker, never emitted.
     Invalid = 0,
@@ -492,6 +493,112 @@ pub enum Opcode {
     TypeOfIsFunction,
 }
 
+/// The kind of object pushed by [Opcode::SpecialObject].
+///
+/// Mirrors QuickJS' `OP_special_object` arguments.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpecialObjectKind {
+    /// The `arguments` object.
+    Arguments = 0,
+    /// The `arguments` object, mapped to the function parameters.
+    MappedArguments,
+    /// The active function object.
+    ThisFunc,
+    /// `new.target`.
+    NewTarget,
+    /// The home object of the active function.
+    HomeObject,
+    /// The variable environment object.
+    VarObject,
+    /// `import.meta`.
+    ImportMeta,
+}
+
+impl SpecialObjectKind {
+    /// Decodes the kind from an `OP_special_object` argument.
+    pub fn from_argument(argument: i32) -> Option<Self> {
+        use SpecialObjectKind::*;
+        Some(match argument {
+            0 => Arguments,
+            1 => MappedArguments,
+            2 => ThisFunc,
+            3 => NewTarget,
+            4 => HomeObject,
+            5 => VarObject,
+            6 => ImportMeta,
+            _ => return None,
+        })
+    }
+}
+
+/// The kind of error thrown by [Opcode::ThrowError].
+///
+/// Mirrors QuickJS' `JS_THROW_*` values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ThrowErrorKind {
+    /// Assignment to a read-only variable.
+    VarReadOnly = 0,
+    /// Redeclaration of a variable.
+    VarRedeclaration,
+    /// Access to a variable before its initialization.
+    VarUninitialized,
+    /// Deletion of a `super` reference.
+    DeleteSuper,
+    /// Iterator without a `throw` method.
+    IteratorThrow,
+}
+
+impl ThrowErrorKind {
+    /// Decodes the kind from an `OP_throw_error` type.
+    pub fn from_type(ty: u8) -> Option<Self> {
+        use ThrowErrorKind::*;
+        Some(match ty {
+            0 => VarReadOnly,
+            1 => VarRedeclaration,
+            2 => VarUninitialized,
+            3 => DeleteSuper,
+            4 => IteratorThrow,
+            _ => return None,
+        })
+    }
+
+    /// The name of the JavaScript error constructor used for the error.
+    pub fn error_name(&self) -> &'static str {
+        use ThrowErrorKind::*;
+        match self {
+            VarReadOnly | IteratorThrow => "TypeError",
+            VarRedeclaration => "SyntaxError",
+            VarUninitialized | DeleteSuper => "ReferenceError",
+        }
+    }
+}
+
+/// The operands of [Opcode::CopyDataProperties], decoded from its mask.
+///
+/// Each operand is identified by its position from the top of the stack,
+/// where `0` is the top, i.e., `sp[-1]`. The operands are left on the
+/// stack.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CopyDataPropertiesMask {
+    /// The object the properties are copied to.
+    pub target: u8,
+    /// The object the properties are copied from.
+    pub source: u8,
+    /// The object whose keys are excluded from the copy, or `null`.
+    pub excluded: u8,
+}
+
+impl CopyDataPropertiesMask {
+    /// Decodes the operands from an `OP_copy_data_properties` mask.
+    pub fn from_mask(mask: u8) -> Self {
+        Self {
+            target: mask & 3,
+            source: (mask >> 2) & 7,
+            excluded: (mask >> 5) & 7,
+        }
+    }
+}
+
 impl Opcode {
     /// reads an opcode, with immediates from a buffer, and returns the parsed opcode object.
     pub fn from_reader(reader: &mut BinaryReader<'_>) -> Result<(u32, Opcode)> {
@@ -501,7 +608,7 @@ impl Opcode {
         let op = match byte {
             0 => Invalid,
             1 => PushI32 {
-                value: i32::try_from(reader.read_u32()?)?,
+                value: reader.read_u32()? as i32,
             },
             2 => PushConst {
                 index: ConstantPoolIndex::from_u32(reader.read_u32()?),
@@ -582,11 +689,11 @@ impl Opcode {
             }
             49 => {
                 let argc = reader.read_u16()?;
-                let scope = reader.read_u16()? - 1;
+                let scope = read_eval_scope(reader)?;
                 Eval { scope, argc }
             }
             50 => ApplyEval {
-                scope: reader.read_u16()? - 1,
+                scope: read_eval_scope(reader)?,
             },
             51 => Regexp,
             52 => GetSuper,
@@ -1220,7 +1327,603 @@ impl Opcode {
         .to_string()
     }
 
+    /// Returns the atom immediate of the operator, if any.
+    pub fn atom(&self) -> Option<AtomIndex> {
+        use Opcode::*;
+        match *self {
+            PushAtomValue { atom }
+            | PrivateSymbol { atom }
+            | ThrowError { atom, .. }
+            | CheckVar { atom }
+            | GetVarUndef { atom }
+            | GetVar { atom }
+            | PutVar { atom }
+            | PutVarInit { atom }
+            | PutVarStrict { atom }
+            | DefineVar { atom, .. }
+            | CheckDefineVar { atom, .. }
+            | DefineFunc { atom, .. }
+            | GetField { atom }
+            | GetField2 { atom }
+            | PutField { atom }
+            | DefineField { atom }
+            | SetName { atom }
+            | DefineMethod { atom, .. }
+            | DefineClass { atom, .. }
+            | DefineClassComputed { atom, .. }
+            | WithGetVar { atom, .. }
+            | WithPutVar { atom, .. }
+            | WithDeleteVar { atom, .. }
+            | WithMakeRef { atom, .. }
+            | WithGetRef { atom, .. }
+            | WithGetRefUndef { atom, .. }
+            | MakeLocRef { atom, .. }
+            | MakeArgRef { atom, .. }
+            | MakeVarRefRef { atom, .. }
+            | MakeVarRef { atom }
+            | DeleteVar { atom } => Some(atom),
+            _ => None,
+        }
+    }
+
+    /// Returns the JavaScript construct that typically emits the operator,
+    /// if it's specific to one, e.g., `for...of loop` for `ForOfStart`.
+    ///
+    /// Hints are meant for reading disassembly; the same operator may be
+    /// emitted for other constructs, e.g., `Append` for spread arguments.
+    pub fn source_hint(&self) -> Option<&'static str> {
+        use Opcode::*;
+        Some(match self {
+            FClosure { .. } | FClosure8 { .. } => "function expression or declaration",
+            Object => "object literal",
+            ArrayFrom { .. } => "array literal",
+            Regexp => "regular expression literal",
+            Rest { .. } => "rest parameter",
+            CallConstructor { .. } => "new expression",
+            TailCall { .. } | TailCallMethod { .. } => "call in return position",
+            Apply { .. } | Append => "spread syntax",
+            Eval { .. } | ApplyEval { .. } => "direct eval",
+            Import => "dynamic import()",
+            Throw => "throw statement",
+            GetSuper | GetSuperValue | PutSuperValue => "super reference",
+            SetHomeObject => "method with super access",
+            DefineClass { .. } | DefineClassComputed { .. } => "class declaration",
+            DefineMethod { .. } | DefineMethodComputed { .. } => "method definition",
+            DefineField { .. } => "object literal property",
+            GetPrivateField | PutPrivateField | DefinePrivateField | PrivateIn => {
+                "private class member"
+            }
+            CopyDataProperties { .. } => "object spread or destructuring rest",
+            Catch { .. } | NipCatch => "try statement",
+            GoSub { .. } | Ret => "finally block",
+            WithGetVar { .. }
+            | WithPutVar { .. }
+            | WithDeleteVar { .. }
+            | WithMakeRef { .. }
+            | WithGetRef { .. }
+            | WithGetRefUndef { .. } => "with statement",
+            ForInStart | ForInNext => "for...in loop",
+            ForOfStart | ForOfNext { .. } => "for...of loop",
+            ForAwaitOfStart => "for await...of loop",
+            InitialYield | Yield => "generator function",
+            YieldStar | AsyncYieldStar => "yield* expression",
+            Await => "await expression",
+            TypeOf | TypeOfIsUndefined | TypeOfIsFunction => "typeof operator",
+            InstanceOf => "instanceof operator",
+            In => "in operator",
+            Delete | DeleteVar { .. } => "delete operator",
+            SpecialObject { argument } => match SpecialObjectKind::from_argument(*argument)? {
+                SpecialObjectKind::Arguments | SpecialObjectKind::MappedArguments => {
+                    "arguments object"
+                }
+                SpecialObjectKind::NewTarget => "new.target",
+                SpecialObjectKind::HomeObject => "method with super access",
+                SpecialObjectKind::ImportMeta => "import.meta",
+                SpecialObjectKind::ThisFunc | SpecialObjectKind::VarObject => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Returns the number of values popped and pushed by the operator, as
+    /// described by QuickJS' opcode definitions.
+    ///
+    /// Operators that operate on values below the top of the stack, e.g.,
+    /// `ForOfNext`, are described as popping and pushing those values back.
+    pub fn stack_effect(&self) -> (u32, u32) {
+        use Opcode::*;
+        match *self {
+            CallConstructor { argc } => (argc as u32 + 2, 1),
+            Call { argc } => (argc as u32 + 1, 1),
+            TailCall { argc } => (argc as u32 + 1, 0),
+            CallMethod { argc } => (argc as u32 + 2, 1),
+            TailCallMethod { argc } => (argc as u32 + 2, 0),
+            ArrayFrom { argc } => (argc as u32, 1),
+            Eval { argc, .. } => (argc as u32 + 1, 1),
+            Call0 => (1, 1),
+            Call1 => (2, 1),
+            Call2 => (3, 1),
+            Call3 => (4, 1),
+            Invalid
+            | ReturnUndef
+            | CheckCtor
+            | ThrowError { .. }
+            | DefineVar { .. }
+            | CheckDefineVar { .. }
+            | SetLocUninit { .. }
+            | CloseLoc { .. }
+            | GoTo { .. }
+            | GoSub { .. }
+            | InitialYield
+            | DecLoc { .. }
+            | IncLoc { .. }
+            | Nop
+            | GoTo8 { .. }
+            | GoTo16 { .. } => (0, 0),
+            PushI32 { .. }
+            | PushConst { .. }
+            | FClosure { .. }
+            | PushAtomValue { .. }
+            | PrivateSymbol { .. }
+            | Undefined
+            | Null
+            | PushThis
+            | PushFalse
+            | PushTrue
+            | Object
+            | SpecialObject { .. }
+            | Rest { .. }
+            | CheckVar { .. }
+            | GetVarUndef { .. }
+            | GetVar { .. }
+            | GetLoc { .. }
+            | GetArg { .. }
+            | GetVarRef { .. }
+            | GetLocCheck { .. }
+            | GetLocCheckThis { .. }
+            | GetVarRefCheck { .. }
+            | Catch { .. }
+            | DeleteVar { .. }
+            | PushMinus1
+            | Push0
+            | Push1
+            | Push2
+            | Push3
+            | Push4
+            | Push5
+            | Push6
+            | Push7
+            | PushI8 { .. }
+            | PushI16 { .. }
+            | PushConst8 { .. }
+            | FClosure8 { .. }
+            | PushEmptyString
+            | GetLoc8 { .. }
+            | GetLoc0
+            | GetLoc1
+            | GetLoc2
+            | GetLoc3
+            | GetArg0
+            | GetArg1
+            | GetArg2
+            | GetArg3
+            | GetVarRef0
+            | GetVarRef1
+            | GetVarRef2
+            | GetVarRef3 => (0, 1),
+            Drop
+            | Return
+            | ReturnAsync
+            | Throw
+            | PutVar { .. }
+            | PutVarInit { .. }
+            | DefineFunc { .. }
+            | PutLoc { .. }
+            | PutArg { .. }
+            | PutVarRef { .. }
+            | PutLocCheck { .. }
+            | PutLocCheckInit { .. }
+            | PutVarRefCheck { .. }
+            | PutVarRefCheckInit { .. }
+            | IfFalse { .. }
+            | IfTrue { .. }
+            | Ret
+            | WithGetVar { .. }
+            | WithDeleteVar { .. }
+            | WithMakeRef { .. }
+            | WithGetRef { .. }
+            | WithGetRefUndef { .. }
+            | AddLoc { .. }
+            | PutLoc8 { .. }
+            | PutLoc0
+            | PutLoc1
+            | PutLoc2
+            | PutLoc3
+            | PutArg0
+            | PutArg1
+            | PutArg2
+            | PutArg3
+            | PutVarRef0
+            | PutVarRef1
+            | PutVarRef2
+            | PutVarRef3
+            | IfFalse8 { .. }
+            | IfTrue8 { .. } => (1, 0),
+            Nip
+            | ApplyEval { .. }
+            | Regexp
+            | GetPrivateField
+            | GetArrayEl
+            | DefineField { .. }
+            | SetProto
+            | DefineMethod { .. }
+            | NipCatch
+            | WithPutVar { .. }
+            | Delete
+            | Mul
+            | Div
+            | Mod
+            | Add
+            | Sub
+            | Pow
+            | Shl
+            | Sar
+            | Shr
+            | Lt
+            | Lte
+            | Gt
+            | Gte
+            | InstanceOf
+            | In
+            | Eq
+            | Neq
+            | StrictEq
+            | StrictNeq
+            | And
+            | Xor
+            | Or
+            | PrivateIn
+            | MulPow10
+            | MathMod => (2, 1),
+            Nip1 | DefineArrayEl | Append => (3, 2),
+            Dup
+            | CheckCtorReturn
+            | GetField2 { .. }
+            | IteratorGetValueDone
+            | Yield
+            | YieldStar
+            | AsyncYieldStar
+            | PostDec
+            | PostInc => (1, 2),
+            Dup1 | Insert2 | GetRefValue => (2, 3),
+            Dup2 => (2, 4),
+            Dup3 => (3, 6),
+            Insert3 => (3, 4),
+            Insert4 | IteratorCall { .. } => (4, 5),
+            Perm3 | Rot3L | Rot3R | CopyDataProperties { .. } | DefineClassComputed { .. } => {
+                (3, 3)
+            }
+            Perm4 | Swap2 | Rot4L | IteratorNext => (4, 4),
+            Perm5 | Rot5L => (5, 5),
+            Swap
+            | CheckBrand
+            | GetArrayEl2
+            | SetNameComputed
+            | SetHomeObject
+            | DefineClass { .. }
+            | ToPropKey2 => (2, 2),
+            Apply { .. } | DefinePrivateField | GetSuperValue | DefineMethodComputed { .. } => {
+                (3, 1)
+            }
+            AddBrand | PutVarStrict { .. } | PutField { .. } => (2, 0),
+            GetSuper
+            | Import
+            | GetField { .. }
+            | SetName { .. }
+            | SetLoc { .. }
+            | SetArg { .. }
+            | SetVarRef { .. }
+            | ToObject
+            | ToPropKey
+            | ForInStart
+            | IteratorCheckObject
+            | Await
+            | Neg
+            | Plus
+            | Dec
+            | Inc
+            | Not
+            | LNot
+            | TypeOf
+            | UndefOrNull
+            | SetLoc8 { .. }
+            | SetLoc0
+            | SetLoc1
+            | SetLoc2
+            | SetLoc3
+            | SetArg0
+            | SetArg1
+            | SetArg2
+            | SetArg3
+            | SetVarRef0
+            | SetVarRef1
+            | SetVarRef2
+            | SetVarRef3
+            | GetLength
+            | IsUndefined
+            | IsNull
+            | TypeOfIsUndefined
+            | TypeOfIsFunction => (1, 1),
+            PutRefValue | PutPrivateField | PutArrayEl | IteratorClose => (3, 0),
+            PutSuperValue => (4, 0),
+            MakeLocRef { .. } | MakeArgRef { .. } | MakeVarRefRef { .. } | MakeVarRef { .. } => {
+                (0, 2)
+            }
+            ForOfStart | ForAwaitOfStart | ForInNext => (1, 3),
+            ForOfNext { .. } => (3, 5),
+        }
+    }
+
+    /// Writes the opcode and its immediates to `out`, in the format read by
+    /// [Opcode::from_reader].
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        use Opcode::*;
+        out.push(self.discriminant());
+        match *self {
+            PushI32 { value } => out.extend(value.to_le_bytes()),
+            PushConst { index } | FClosure { index } => out.extend(index.as_u32().to_le_bytes()),
+            PushAtomValue { atom }
+            | PrivateSymbol { atom }
+            | CheckVar { atom }
+            | GetVarUndef { atom }
+            | GetVar { atom }
+            | PutVar { atom }
+            | PutVarInit { atom }
+            | PutVarStrict { atom }
+            | GetField { atom }
+            | GetField2 { atom }
+            | PutField { atom }
+            | DefineField { atom }
+            | SetName { atom }
+            | MakeVarRef { atom }
+            | DeleteVar { atom } => out.extend(atom.as_u32().to_le_bytes()),
+            SpecialObject { argument } => out.push(argument as u8),
+            Rest { first: value }
+            | CallConstructor { argc: value }
+            | Call { argc: value }
+            | TailCall { argc: value }
+            | CallMethod { argc: value }
+            | TailCallMethod { argc: value }
+            | ArrayFrom { argc: value }
+            | Apply { magic: value }
+            | CloseLoc { index: value } => out.extend(value.to_le_bytes()),
+            ThrowError { atom, ty } => {
+                out.extend(atom.as_u32().to_le_bytes());
+                out.push(ty);
+            }
+            Eval { scope, argc } => {
+                out.extend(argc.to_le_bytes());
+                out.extend((scope + 1).to_le_bytes());
+            }
+            ApplyEval { scope } => out.extend((scope + 1).to_le_bytes()),
+            DefineVar { flags, atom }
+            | CheckDefineVar { flags, atom }
+            | DefineFunc { flags, atom }
+            | DefineMethod { atom, flags }
+            | DefineClass { atom, flags }
+            | DefineClassComputed { atom, flags } => {
+                out.extend(atom.as_u32().to_le_bytes());
+                out.push(flags);
+            }
+            CopyDataProperties { mask: value }
+            | DefineMethodComputed { flags: value }
+            | IteratorCall { flags: value }
+            | PushConst8 { index: value } => out.push(value),
+            GetLoc { index }
+            | PutLoc { index }
+            | SetLoc { index }
+            | GetArg { index }
+            | PutArg { index }
+            | SetArg { index }
+            | SetLocUninit { index }
+            | GetLocCheck { index }
+            | PutLocCheck { index }
+            | PutLocCheckInit { index }
+            | GetLocCheckThis { index } => out.extend((index.as_u32() as u16).to_le_bytes()),
+            GetVarRef { index }
+            | PutVarRef { index }
+            | SetVarRef { index }
+            | GetVarRefCheck { index }
+            | PutVarRefCheck { index }
+            | PutVarRefCheckInit { index } => out.extend((index.as_u32() as u16).to_le_bytes()),
+            IfFalse { offset } | IfTrue { offset } | GoTo { offset } => {
+                out.extend(offset.to_le_bytes())
+            }
+            Catch { diff } | GoSub { diff } => out.extend(diff.to_le_bytes()),
+            WithGetVar {
+                atom,
+                diff,
+                is_with,
+            }
+            | WithPutVar {
+                atom,
+                diff,
+                is_with,
+            }
+            | WithDeleteVar {
+                atom,
+                diff,
+                is_with,
+            }
+            | WithMakeRef {
+                atom,
+                diff,
+                is_with,
+            }
+            | WithGetRef {
+                atom,
+                diff,
+                is_with,
+            }
+            | WithGetRefUndef {
+                atom,
+                diff,
+                is_with,
+            } => {
+                out.extend(atom.as_u32().to_le_bytes());
+                out.extend(diff.to_le_bytes());
+                out.push(is_with);
+            }
+            MakeLocRef { atom, idx } | MakeArgRef { atom, idx } | MakeVarRefRef { atom, idx } => {
+                out.extend(atom.as_u32().to_le_bytes());
+                out.extend(idx.to_le_bytes());
+            }
+            DecLoc { index }
+            | IncLoc { index }
+            | AddLoc { index }
+            | GetLoc8 { index }
+            | PutLoc8 { index }
+            | SetLoc8 { index } => out.push(index.as_u32() as u8),
+            FClosure8 { index } => out.push(index.as_u32() as u8),
+            ForOfNext { offset } | PushI8 { val: offset } => out.push(offset as u8),
+            IfFalse8 { offset } | IfTrue8 { offset } | GoTo8 { offset } => out.push(offset as u8),
+            PushI16 { val: offset } | GoTo16 { offset } => out.extend(offset.to_le_bytes()),
+            _ => {}
+        }
+    }
+
     pub fn discriminant(&self) -> u8 {
         unsafe { *<*const _>::from(self).cast::<u8>() }
     }
 }
+
+/// Reads the scope immediate of `Eval` and `ApplyEval`, which is encoded
+/// as the scope index plus one.
+fn read_eval_scope(reader: &mut BinaryReader<'_>) -> Result<u16> {
+    reader
+        .read_u16()?
+        .checked_sub(1)
+        .ok_or_else(|| anyhow!("Invalid eval scope: 0"))
+}
+
+impl fmt::Display for Opcode {
+    /// Renders the operator mnemonic followed by its raw immediates; atoms,
+    /// locals, closure variables and constants are rendered as indices.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Opcode::*;
+        f.write_str(&Opcode::name_from_byte(self.discriminant()))?;
+        match *self {
+            PushI32 { value } => write!(f, " {}", value),
+            PushConst { index } => write!(f, " {}", index.as_u32()),
+            FClosure { index } => write!(f, " {}", index.as_u32()),
+            PushAtomValue { atom } => write!(f, " {}", atom.as_u32()),
+            PrivateSymbol { atom } => write!(f, " {}", atom.as_u32()),
+            SpecialObject { argument } => write!(f, " {}", argument),
+            Rest { first } => write!(f, " {}", first),
+            CallConstructor { argc } => write!(f, " {}", argc),
+            Call { argc } => write!(f, " {}", argc),
+            TailCall { argc } => write!(f, " {}", argc),
+            CallMethod { argc } => write!(f, " {}", argc),
+            TailCallMethod { argc } => write!(f, " {}", argc),
+            ArrayFrom { argc } => write!(f, " {}", argc),
+            Apply { magic } => write!(f, " {}", magic),
+            ThrowError { ty, atom } => write!(f, " {} {}", ty, atom.as_u32()),
+            Eval { scope, argc } => write!(f, " {} {}", scope, argc),
+            ApplyEval { scope } => write!(f, " {}", scope),
+            CheckVar { atom } => write!(f, " {}", atom.as_u32()),
+            GetVarUndef { atom } => write!(f, " {}", atom.as_u32()),
+            GetVar { atom } => write!(f, " {}", atom.as_u32()),
+            PutVar { atom } => write!(f, " {}", atom.as_u32()),
+            PutVarInit { atom } => write!(f, " {}", atom.as_u32()),
+            PutVarStrict { atom } => write!(f, " {}", atom.as_u32()),
+            DefineVar { flags, atom } => write!(f, " {} {}", flags, atom.as_u32()),
+            CheckDefineVar { flags, atom } => write!(f, " {} {}", flags, atom.as_u32()),
+            DefineFunc { flags, atom } => write!(f, " {} {}", flags, atom.as_u32()),
+            GetField { atom } => write!(f, " {}", atom.as_u32()),
+            GetField2 { atom } => write!(f, " {}", atom.as_u32()),
+            PutField { atom } => write!(f, " {}", atom.as_u32()),
+            DefineField { atom } => write!(f, " {}", atom.as_u32()),
+            SetName { atom } => write!(f, " {}", atom.as_u32()),
+            CopyDataProperties { mask } => write!(f, " {}", mask),
+            DefineMethod { atom, flags } => write!(f, " {} {}", atom.as_u32(), flags),
+            DefineMethodComputed { flags } => write!(f, " {}", flags),
+            DefineClass { flags, atom } => write!(f, " {} {}", flags, atom.as_u32()),
+            DefineClassComputed { flags, atom } => write!(f, " {} {}", flags, atom.as_u32()),
+            GetLoc { index } => write!(f, " {}", index.as_u32()),
+            PutLoc { index } => write!(f, " {}", index.as_u32()),
+            SetLoc { index } => write!(f, " {}", index.as_u32()),
+            GetArg { index } => write!(f, " {}", index.as_u32()),
+            PutArg { index } => write!(f, " {}", index.as_u32()),
+            SetArg { index } => write!(f, " {}", index.as_u32()),
+            GetVarRef { index } => write!(f, " {}", index.as_u32()),
+            PutVarRef { index } => write!(f, " {}", index.as_u32()),
+            SetVarRef { index } => write!(f, " {}", index.as_u32()),
+            SetLocUninit { index } => write!(f, " {}", index.as_u32()),
+            GetLocCheck { index } => write!(f, " {}", index.as_u32()),
+            PutLocCheck { index } => write!(f, " {}", index.as_u32()),
+            PutLocCheckInit { index } => write!(f, " {}", index.as_u32()),
+            GetLocCheckThis { index } => write!(f, " {}", index.as_u32()),
+            GetVarRefCheck { index } => write!(f, " {}", index.as_u32()),
+            PutVarRefCheck { index } => write!(f, " {}", index.as_u32()),
+            PutVarRefCheckInit { index } => write!(f, " {}", index.as_u32()),
+            CloseLoc { index } => write!(f, " {}", index),
+            IfFalse { offset } => write!(f, " {}", offset),
+            IfTrue { offset } => write!(f, " {}", offset),
+            GoTo { offset } => write!(f, " {}", offset),
+            Catch { diff } => write!(f, " {}", diff),
+            GoSub { diff } => write!(f, " {}", diff),
+            WithGetVar {
+                atom,
+                diff,
+                is_with,
+            } => write!(f, " {} {} {}", atom.as_u32(), diff, is_with),
+            WithPutVar {
+                atom,
+                diff,
+                is_with,
+            } => write!(f, " {} {} {}", atom.as_u32(), diff, is_with),
+            WithDeleteVar {
+                atom,
+                diff,
+                is_with,
+            } => write!(f, " {} {} {}", atom.as_u32(), diff, is_with),
+            WithMakeRef {
+                atom,
+                diff,
+                is_with,
+            } => write!(f, " {} {} {}", atom.as_u32(), diff, is_with),
+            WithGetRef {
+                atom,
+                diff,
+                is_with,
+            } => write!(f, " {} {} {}", atom.as_u32(), diff, is_with),
+            WithGetRefUndef {
+                atom,
+                diff,
+                is_with,
+            } => write!(f, " {} {} {}", atom.as_u32(), diff, is_with),
+            MakeLocRef { atom, idx } => write!(f, " {} {}", atom.as_u32(), idx),
+            MakeArgRef { atom, idx } => write!(f, " {} {}", atom.as_u32(), idx),
+            MakeVarRefRef { atom, idx } => write!(f, " {} {}", atom.as_u32(), idx),
+            MakeVarRef { atom } => write!(f, " {}", atom.as_u32()),
+            ForOfNext { offset } => write!(f, " {}", offset),
+            IteratorCall { flags } => write!(f, " {}", flags),
+            DecLoc { index } => write!(f, " {}", index.as_u32()),
+            IncLoc { index } => write!(f, " {}", index.as_u32()),
+            AddLoc { index } => write!(f, " {}", index.as_u32()),
+            DeleteVar { atom } => write!(f, " {}", atom.as_u32()),
+            PushI8 { val } => write!(f, " {}", val),
+            PushI16 { val } => write!(f, " {}", val),
+            PushConst8 { index } => write!(f, " {}", index),
+            FClosure8 { index } => write!(f, " {}", index.as_u32()),
+            GetLoc8 { index } => write!(f, " {}", index.as_u32()),
+            PutLoc8 { index } => write!(f, " {}", index.as_u32()),
+            SetLoc8 { index } => write!(f, " {}", index.as_u32()),
+            IfFalse8 { offset } => write!(f, " {}", offset),
+            IfTrue8 { offset } => write!(f, " {}", offset),
+            GoTo8 { offset } => write!(f, " {}", offset),
+            GoTo16 { offset } => write!(f, " {}", offset),
+            _ => Ok(()),
+        }
+    }
+}
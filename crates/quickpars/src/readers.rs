@@ -10,6 +10,8 @@ pub struct BinaryReader<'a> {
     data: &'a [u8],
     /// The offset of the binary reader.
     pub offset: usize,
+    /// The absolute offset of `data` in the original bytecode buffer.
+    origin: usize,
 }
 
 impl<'a> BinaryReader<'a> {
@@ -17,15 +19,32 @@ impl<'a> BinaryReader<'a> {
         Self {
             data: Default::default(),
             offset: 0,
+            origin: 0,
         }
     }
 
     pub fn new(data: &'a [u8]) -> Self {
-        Self { data, offset: 0 }
+        Self::with_origin(data, 0)
+    }
+
+    /// Creates a reader over `data`, which starts at `origin` in the
+    /// original bytecode buffer.
+    pub fn with_origin(data: &'a [u8], origin: usize) -> Self {
+        Self {
+            data,
+            offset: 0,
+            origin,
+        }
+    }
+
+    /// Returns the current position of the reader in the original bytecode
+    /// buffer.
+    pub fn absolute_offset(&self) -> usize {
+        self.origin + self.offset
     }
 
     /// Returns a reference to the underlying data.
-    pub(crate) fn data(&self) -> &'a [u8] {
+    pub fn data(&self) -> &'a [u8] {
         self.data
     }
 
@@ -33,6 +52,11 @@ impl<'a> BinaryReader<'a> {
         self.offset >= self.data.len()
     }
 
+    /// Moves the reader back to the start of the data.
+    pub fn reset(&mut self) {
+        self.offset = 0;
+    }
+
     /// Reads the requested amount of bytes, returning a slice of the bytes.
     fn read(&mut self, bytes: usize) -> Result<&'a [u8]> {
         self.ensure(bytes).map(|_| {
@@ -69,6 +93,12 @@ impl<'a> BinaryReader<'a> {
         Ok(u64::from_le_bytes(slice.try_into()?))
     }
 
+    /// Reads 8 bytes into an `f64`.
+    pub fn read_f64(&mut self) -> Result<f64> {
+        let slice = self.read(8)?;
+        Ok(f64::from_le_bytes(slice.try_into()?))
+    }
+
     /// Reads a single atom.
     pub fn read_atom(&mut self) -> Result<u32> {
         Ok(self.read_leb128()? >> 1)
@@ -118,11 +148,31 @@ impl<'a> BinaryReader<'a> {
 
 /// Creates a [BinaryReader] slice for a bytecode section.
 pub(crate) fn slice<'a>(reader: &mut BinaryReader<'a>, size: usize) -> Result<BinaryReader<'a>> {
-    let data = reader.data();
-    let slice = &data[reader.offset..(reader.offset + size)];
-    let res = BinaryReader::new(slice);
+    let start = reader.offset;
+    let origin = reader.absolute_offset();
     reader.skip(size)?;
-    Ok(res)
+    let slice = &reader.data()[start..reader.offset];
+    Ok(BinaryReader::with_origin(slice, origin))
+}
+
+/// Reads a QuickJS string.
+///
+/// Strings are encoded either as Latin-1 or, if they contain wide
+/// characters, as UTF-16.
+pub(crate) fn read_string(reader: &mut BinaryReader<'_>) -> Result<String> {
+    let len = reader.read_leb128()?;
+    let is_wide_char = len & 1 == 1;
+    let len = (len >> 1) as usize;
+    if is_wide_char {
+        let bytes = reader.read(len * 2)?;
+        let chars = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect::<Vec<_>>();
+        Ok(String::from_utf16_lossy(&chars))
+    } else {
+        Ok(reader.read(len)?.iter().map(|b| *b as char).collect())
+    }
 }
 
 /// Reads the bytes representing a QuickJS string.
@@ -133,8 +183,8 @@ pub(crate) fn read_str_bytes<'a>(reader: &mut BinaryReader<'a>) -> Result<&'a [u
     // Once we have read the `wide_char` bit, we clear it out.
     len >>= 1;
     let size = (len << is_wide_char) as usize;
-    let res = &reader.data()[reader.offset..(reader.offset + size)];
+    let start = reader.offset;
     reader.skip(size)?;
 
-    Ok(res)
+    Ok(&reader.data()[start..reader.offset])
 }
@@ -1,9 +1,11 @@
 //! Bytecode sections.
 
+use anyhow::{anyhow, ensure, Result};
 use core::fmt;
 
 use crate::AtomIndex;
 use crate::{op::Opcode, readers::BinaryReader};
+use crate::{PC2LINE_BASE, PC2LINE_OP_FIRST, PC2LINE_RANGE};
 
 pub type OpcodeList = Vec<(u32, Opcode)>;
 
@@ -13,7 +15,12 @@ pub struct HeaderSection {
     /// The number of interned atoms in the bytecode.
     pub atom_count: u32,
     /// The entire list of atom names accessible to the module, including built-in atoms.
+    ///
+    /// Only holds the built-in atoms if the interned atoms were skipped, see
+    /// [crate::Parser::skip_to_functions].
     pub atoms: Vec<String>,
+    /// The number of built-in atoms, which precede the interned atoms.
+    builtin_atom_count: usize,
 }
 
 impl Default for HeaderSection {
@@ -21,14 +28,33 @@ impl Default for HeaderSection {
         Self {
             atom_count: u32::MAX,
             atoms: Default::default(),
+            builtin_atom_count: 0,
         }
     }
 }
 
 impl HeaderSection {
     /// Creates a new [HeaderSection].
-    pub(crate) fn new(atom_count: u32, atoms: Vec<String>) -> Self {
-        Self { atom_count, atoms }
+    pub(crate) fn new(atom_count: u32, atoms: Vec<String>, builtin_atom_count: usize) -> Self {
+        Self {
+            atom_count,
+            atoms,
+            builtin_atom_count,
+        }
+    }
+
+    /// Returns the number of built-in atoms, which precede the interned
+    /// atoms in [HeaderSection::atoms].
+    pub fn builtin_atom_count(&self) -> usize {
+        self.builtin_atom_count
+    }
+
+    /// Returns the name of an atom, if any.
+    ///
+    /// Returns `None` for indices out of bounds, which include the interned
+    /// atoms when they were skipped, see [crate::Parser::skip_to_functions].
+    pub fn atom(&self, index: AtomIndex) -> Option<&str> {
+        self.atoms.get(index.as_u32() as usize).map(String::as_str)
     }
 }
 #[derive(Clone, Debug)]
@@ -124,6 +150,126 @@ pub struct FunctionSectionHeader {
     pub bytecode_len: u32,
     /// The number of locals.
     pub local_count: u32,
+    /// Whether the function was compiled in strict mode.
+    pub is_strict: bool,
+}
+
+impl FunctionSectionHeader {
+    /// Returns a builder for headers of functions built by hand, e.g., in
+    /// tests, which checks the invariants of headers read from bytecode.
+    pub fn builder() -> FunctionSectionHeaderBuilder {
+        FunctionSectionHeaderBuilder::default()
+    }
+
+    /// Decodes the kind of the function from its flags.
+    pub fn kind(&self) -> FunctionKind {
+        match (self.flags >> 4) & 0b11 {
+            0 => FunctionKind::Normal,
+            1 => FunctionKind::Generator,
+            2 => FunctionKind::Async,
+            _ => FunctionKind::AsyncGenerator,
+        }
+    }
+}
+
+/// Builder for [FunctionSectionHeader], see
+/// [FunctionSectionHeader::builder].
+#[derive(Debug, Default, Clone)]
+pub struct FunctionSectionHeaderBuilder {
+    header: FunctionSectionHeader,
+}
+
+impl FunctionSectionHeaderBuilder {
+    /// Sets the index of the function name.
+    pub fn with_name(mut self, name_index: AtomIndex) -> Self {
+        self.header.name_index = name_index;
+        self
+    }
+
+    /// Sets the kind of the function, encoded in its flags.
+    pub fn with_kind(mut self, kind: FunctionKind) -> Self {
+        let bits = match kind {
+            FunctionKind::Normal => 0,
+            FunctionKind::Generator => 1,
+            FunctionKind::Async => 2,
+            FunctionKind::AsyncGenerator => 3,
+        };
+        self.header.flags = (self.header.flags & !(0b11 << 4)) | (bits << 4);
+        self
+    }
+
+    /// Sets the argument count, i.e., the number of argument locals, and
+    /// the defined argument count, i.e., the number of formal parameters
+    /// before any default or rest parameter.
+    pub fn with_args(mut self, arg_count: u32, defined_arg_count: u32) -> Self {
+        self.header.arg_count = arg_count;
+        self.header.defined_arg_count = defined_arg_count;
+        self
+    }
+
+    /// Sets the variable count.
+    pub fn with_var_count(mut self, var_count: u32) -> Self {
+        self.header.var_count = var_count;
+        self
+    }
+
+    /// Sets the stack size.
+    pub fn with_stack_size(mut self, stack_size: u32) -> Self {
+        self.header.stack_size = stack_size;
+        self
+    }
+
+    /// Sets the number of closure variables.
+    pub fn with_closure_var_count(mut self, closure_var_count: u32) -> Self {
+        self.header.closure_var_count = closure_var_count;
+        self
+    }
+
+    /// Sets the number of elements in the constant pool.
+    pub fn with_constant_pool_size(mut self, constant_pool_size: u32) -> Self {
+        self.header.constant_pool_size = constant_pool_size;
+        self
+    }
+
+    /// Sets the length of the function bytecode.
+    pub fn with_bytecode_len(mut self, bytecode_len: u32) -> Self {
+        self.header.bytecode_len = bytecode_len;
+        self
+    }
+
+    /// Sets whether the function is in strict mode.
+    pub fn with_strict(mut self, is_strict: bool) -> Self {
+        self.header.is_strict = is_strict;
+        self
+    }
+
+    /// Builds the header, with one local per argument and variable, as
+    /// QuickJS does.
+    ///
+    /// Errors if the defined argument count exceeds the argument count.
+    pub fn build(self) -> Result<FunctionSectionHeader> {
+        let mut header = self.header;
+        ensure!(
+            header.defined_arg_count <= header.arg_count,
+            "The defined argument count {} exceeds the argument count {}",
+            header.defined_arg_count,
+            header.arg_count
+        );
+        header.local_count = header
+            .arg_count
+            .checked_add(header.var_count)
+            .ok_or_else(|| anyhow!("Too many locals"))?;
+        Ok(header)
+    }
+}
+
+/// The kind of a function.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FunctionKind {
+    Normal,
+    Generator,
+    Async,
+    AsyncGenerator,
 }
 
 /// Closure variable information.
@@ -134,11 +280,45 @@ pub struct FunctionClosureVar {
     pub flags: u8,
 }
 
+impl FunctionClosureVar {
+    /// Decodes the closure variable flags.
+    pub fn decoded_flags(&self) -> ClosureVarFlags {
+        ClosureVarFlags {
+            is_local: self.flags & 1 != 0,
+            is_arg: self.flags & (1 << 1) != 0,
+            is_const: self.flags & (1 << 2) != 0,
+            is_lexical: self.flags & (1 << 3) != 0,
+            var_kind: self.flags >> 4,
+        }
+    }
+}
+
+/// Decoded closure variable flags.
+///
+/// The captured variable is a local or an argument of the parent function;
+/// if neither, it is a closure variable of the parent function.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ClosureVarFlags {
+    /// Whether the variable is a local of the parent function.
+    pub is_local: bool,
+    /// Whether the variable is an argument of the parent function.
+    pub is_arg: bool,
+    /// Whether the variable is `const`.
+    pub is_const: bool,
+    /// Whether the variable is lexically scoped, i.e., `let`, `const` or
+    /// `class`, and therefore subject to TDZ checks.
+    pub is_lexical: bool,
+    /// The QuickJS variable kind.
+    pub var_kind: u8,
+}
+
 /// Function local variable information.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct FunctionLocal {
     pub name_index: AtomIndex,
     pub scope_level: u32,
+    /// The index of the next local in the scope chain, relative to the
+    /// first non-argument local, plus one; `0` terminates the chain.
     pub scope_next: u32,
     pub flags: u8,
 }
@@ -169,6 +349,94 @@ impl<'a> DebugInfo<'a> {
             col_debug_reader,
         }
     }
+
+    /// Returns the `(pc, line, col)` triples of the function, ordered by pc,
+    /// by replaying both the line and column debug streams.
+    ///
+    /// The first entry is always at pc `0`; decoding stops at the first
+    /// malformed entry of each stream, e.g., one overflowing the pc or
+    /// leading to a negative line.
+    pub fn line_table(&self) -> Vec<(u32, u32, u32)> {
+        let lines = decode_pc2line(self.line_debug_reader, self.lineno);
+        let cols = decode_pc2line(self.col_debug_reader, self.colno);
+
+        let mut table = vec![(0, self.lineno, self.colno)];
+        let (mut line, mut col) = (self.lineno, self.colno);
+        let (mut i, mut j) = (0, 0);
+        while i < lines.len() || j < cols.len() {
+            let pc = match (lines.get(i), cols.get(j)) {
+                (Some(l), Some(c)) => l.0.min(c.0),
+                (Some(l), None) => l.0,
+                (None, Some(c)) => c.0,
+                (None, None) => unreachable!(),
+            };
+            while i < lines.len() && lines[i].0 == pc {
+                line = lines[i].1;
+                i += 1;
+            }
+            while j < cols.len() && cols[j].0 == pc {
+                col = cols[j].1;
+                j += 1;
+            }
+            match table.last_mut() {
+                Some(last) if last.0 == pc => *last = (pc, line, col),
+                _ => table.push((pc, line, col)),
+            }
+        }
+        table
+    }
+
+    /// Returns the source `(line, col)` of the operator at the given pc,
+    /// i.e., the position of the last entry of the [DebugInfo::line_table]
+    /// at or before the pc.
+    ///
+    /// QuickJS encodes the column deltas in a stream separate from the line
+    /// deltas, so statements on the same line are told apart by column.
+    pub fn line_for_pc(&self, pc: u32) -> Option<(u32, u32)> {
+        self.line_table()
+            .into_iter()
+            .take_while(|(entry_pc, _, _)| *entry_pc <= pc)
+            .last()
+            .map(|(_, line, col)| (line, col))
+    }
+}
+
+/// Decodes a pc to line (or column) debug stream into `(pc, value)` pairs.
+///
+/// Each entry is either a single byte encoding both the pc and value
+/// deltas, or `0` followed by the pc delta as LEB-128 and the value delta
+/// as signed LEB-128. Decoding stops at the first truncated entry, or at
+/// the first entry overflowing the pc or leading to a negative value, as
+/// found in corrupt streams.
+fn decode_pc2line(mut reader: BinaryReader, initial: u32) -> Vec<(u32, u32)> {
+    reader.reset();
+    let mut entries = vec![];
+    let (mut pc, mut value) = (0u32, initial);
+    while !reader.done() {
+        let Ok(op) = reader.read_u8() else {
+            break;
+        };
+        let (diff_pc, diff) = if op == 0 {
+            let (Ok(diff_pc), Ok(diff)) = (reader.read_leb128(), reader.read_sleb128()) else {
+                break;
+            };
+            (diff_pc, diff as i64)
+        } else {
+            let op = op - PC2LINE_OP_FIRST;
+            (
+                (op / PC2LINE_RANGE) as u32,
+                (op % PC2LINE_RANGE) as i64 + PC2LINE_BASE as i64,
+            )
+        };
+        let (Some(next_pc), Ok(next_value)) =
+            (pc.checked_add(diff_pc), u32::try_from(value as i64 + diff))
+        else {
+            break;
+        };
+        (pc, value) = (next_pc, next_value);
+        entries.push((pc, value));
+    }
+    entries
 }
 
 impl fmt::Debug for DebugInfo<'_> {
@@ -0,0 +1,36 @@
+//! Bytecode rewriting.
+
+use crate::{Parser, Payload, DEBUG_FLAG};
+use anyhow::Result;
+
+/// Re-emits the bytecode without the functions' debug sections, clearing
+/// the debug flag in their headers.
+///
+/// Every other byte is copied verbatim: sections don't refer to each other
+/// through byte offsets, so removing the debug sections doesn't invalidate
+/// the rest of the bytecode.
+pub fn strip_debug_info(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut parser = Parser::new();
+    loop {
+        let start = parser.offset;
+        let Some(payload) = parser.parse_next(data) else {
+            break;
+        };
+        let payload = payload?;
+        let end = parser.offset.min(data.len());
+        match payload {
+            Payload::FunctionDebugInfo(_) => {}
+            Payload::FunctionHeader(header) => {
+                // The flags immediately follow the function tag.
+                let flags = header.flags & !DEBUG_FLAG;
+                out.push(data[start]);
+                out.extend_from_slice(&flags.to_le_bytes());
+                out.extend_from_slice(&data[start + 3..end]);
+            }
+            _ => out.extend_from_slice(&data[start..end]),
+        }
+    }
+
+    Ok(out)
+}
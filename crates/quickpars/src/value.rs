@@ -0,0 +1,159 @@
+//! Constant values.
+
+use crate::{AtomIndex, FuncIndex};
+use core::fmt;
+
+/// A value in a function's constant pool.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Null,
+    Undefined,
+    Bool(bool),
+    I32(i32),
+    F64(f64),
+    String(String),
+    /// The strings of a tagged template literal.
+    ///
+    /// Cooked strings are `None` when the literal contains invalid escape
+    /// sequences, in which case the cooked value is `undefined`.
+    TemplateObject {
+        cooked: Vec<Option<String>>,
+        raw: Vec<String>,
+    },
+    /// An array literal.
+    Array(Vec<ConstValue>),
+    /// An object literal, as its properties in definition order.
+    ///
+    /// Property names are atoms, resolved through the translation.
+    Object(Vec<(AtomIndex, ConstValue)>),
+    /// A function, identified by its index in the module.
+    ///
+    /// The parser emits function entries as function sections; this variant
+    /// is only produced at translation time.
+    Function(FuncIndex),
+    /// A primitive wrapped in an object, e.g., `new Number(1)`.
+    ObjectValue(Box<ConstValue>),
+    /// A reference to an object read earlier in the same bytecode object,
+    /// which allows shared and self-referential objects.
+    ///
+    /// Objects are numbered in the order in which they are read, starting
+    /// at `0` for the first object of the bytecode object, across all the
+    /// constants, see [ConstValue::object_count]. References are not
+    /// inlined, since they can form cycles; the translation resolves them
+    /// on demand.
+    ObjectRef(u32),
+}
+
+impl ConstValue {
+    /// Returns the number of object ids taken by the value, including its
+    /// nested values.
+    ///
+    /// Objects are numbered as QuickJS does: arrays, objects and object
+    /// values take one id, and template objects take two, the second one for
+    /// the raw strings array.
+    pub fn object_count(&self) -> u32 {
+        match self {
+            ConstValue::Array(elements) => {
+                1 + elements.iter().map(|e| e.object_count()).sum::<u32>()
+            }
+            ConstValue::Object(props) => {
+                1 + props.iter().map(|(_, v)| v.object_count()).sum::<u32>()
+            }
+            ConstValue::ObjectValue(value) => 1 + value.object_count(),
+            ConstValue::TemplateObject { .. } => 2,
+            _ => 0,
+        }
+    }
+
+    /// Returns the atoms referenced by the value, i.e., the property keys
+    /// of its objects, including the nested ones.
+    pub fn atom_references(&self) -> Vec<AtomIndex> {
+        fn visit(value: &ConstValue, atoms: &mut Vec<AtomIndex>) {
+            match value {
+                ConstValue::Array(elements) => elements.iter().for_each(|e| visit(e, atoms)),
+                ConstValue::Object(props) => {
+                    for (key, value) in props {
+                        atoms.push(*key);
+                        visit(value, atoms);
+                    }
+                }
+                ConstValue::ObjectValue(value) => visit(value, atoms),
+                _ => {}
+            }
+        }
+
+        let mut atoms = vec![];
+        visit(self, &mut atoms);
+        atoms
+    }
+
+    /// Returns the object numbered `index` within this value, counting from
+    /// `0` for the value itself, if any, see [ConstValue::object_count].
+    ///
+    /// The raw strings array of a template object doesn't resolve to any
+    /// value.
+    pub fn nested_object(&self, index: u32) -> Option<&ConstValue> {
+        fn visit<'v>(value: &'v ConstValue, index: u32, next: &mut u32) -> Option<&'v ConstValue> {
+            let ids = match value {
+                ConstValue::Array(_) | ConstValue::Object(_) | ConstValue::ObjectValue(_) => 1,
+                ConstValue::TemplateObject { .. } => 2,
+                _ => return None,
+            };
+            if *next == index {
+                return Some(value);
+            }
+            *next += ids;
+            match value {
+                ConstValue::Array(elements) => elements.iter().find_map(|e| visit(e, index, next)),
+                ConstValue::Object(props) => props.iter().find_map(|(_, v)| visit(v, index, next)),
+                ConstValue::ObjectValue(value) => visit(value, index, next),
+                _ => None,
+            }
+        }
+
+        visit(self, index, &mut 0)
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstValue::Null => write!(f, "null"),
+            ConstValue::Undefined => write!(f, "undefined"),
+            ConstValue::Bool(b) => write!(f, "{}", b),
+            ConstValue::I32(v) => write!(f, "{}", v),
+            // Rendered as JavaScript number literals.
+            ConstValue::F64(v) if v.is_nan() => write!(f, "NaN"),
+            ConstValue::F64(v) if v.is_infinite() => {
+                write!(f, "{}Infinity", if *v < 0.0 { "-" } else { "" })
+            }
+            ConstValue::F64(v) if *v == 0.0 && v.is_sign_negative() => write!(f, "-0"),
+            ConstValue::F64(v) => write!(f, "{}", v),
+            ConstValue::String(s) => write!(f, "{:?}", s),
+            ConstValue::TemplateObject { cooked, raw } => {
+                let cooked = cooked
+                    .iter()
+                    .map(|s| match s {
+                        Some(s) => format!("{:?}", s),
+                        None => "undefined".to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, "template [{}] raw {:?}", cooked.join(", "), raw)
+            }
+            ConstValue::Array(elements) => {
+                let elements = elements.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            ConstValue::Object(props) => {
+                let props = props
+                    .iter()
+                    .map(|(key, value)| format!("atom {}: {}", key.as_u32(), value))
+                    .collect::<Vec<_>>();
+                write!(f, "{{{}}}", props.join(", "))
+            }
+            ConstValue::Function(index) => write!(f, "function {}", index.as_u32()),
+            ConstValue::ObjectValue(value) => write!(f, "Object({})", value),
+            ConstValue::ObjectRef(id) => write!(f, "<ref {}>", id),
+        }
+    }
+}
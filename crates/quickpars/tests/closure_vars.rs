@@ -0,0 +1,19 @@
+use quickpars::{ClosureVarFlags, FunctionClosureVar};
+
+#[test]
+fn const_local_capture_flags_are_decoded() {
+    let var = FunctionClosureVar {
+        flags: 0b0010_1101,
+        ..Default::default()
+    };
+    assert_eq!(
+        var.decoded_flags(),
+        ClosureVarFlags {
+            is_local: true,
+            is_arg: false,
+            is_const: true,
+            is_lexical: true,
+            var_kind: 2,
+        }
+    );
+}
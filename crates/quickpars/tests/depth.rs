@@ -0,0 +1,34 @@
+use quickpars::{Parser, Payload, Tag, VERSION};
+
+/// Builds bytecode without atoms holding `arrays` nested single element
+/// arrays around `null`.
+fn nested_arrays(arrays: usize) -> Vec<u8> {
+    let mut bytes = vec![VERSION, 0];
+    for _ in 0..arrays {
+        bytes.extend([Tag::Array as u8, 1]);
+    }
+    bytes.push(Tag::Null as u8);
+    bytes
+}
+
+#[test]
+fn nesting_beyond_max_depth_is_an_error() {
+    let parser = Parser::new().with_max_depth(4);
+
+    let bytes = nested_arrays(4);
+    let payloads = parser
+        .parse_buffer(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(payloads.iter().any(|p| matches!(p, Payload::Value(_))));
+
+    let bytes = nested_arrays(5);
+    let err = parser
+        .parse_buffer(&bytes)
+        .find_map(|payload| payload.err())
+        .expect("parsing to fail");
+    assert!(
+        format!("{err:#}").contains("exceeds the maximum depth of 4"),
+        "unexpected error: {err:#}"
+    );
+}
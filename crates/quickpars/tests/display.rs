@@ -0,0 +1,23 @@
+use quickpars::{AtomIndex, LocalIndex, Opcode};
+
+#[test]
+fn immediates_follow_the_mnemonic() {
+    let get_loc = Opcode::GetLoc {
+        index: LocalIndex::from_u32(3),
+    };
+    assert_eq!(get_loc.to_string(), "GetLoc 3");
+    assert_eq!(Opcode::PushI32 { value: -1 }.to_string(), "PushI32 -1");
+}
+
+#[test]
+fn operators_without_immediates_are_bare() {
+    assert_eq!(Opcode::Add.to_string(), "Add");
+}
+
+#[test]
+fn atoms_are_rendered_as_indices() {
+    let get_var = Opcode::GetVar {
+        atom: AtomIndex::from_u32(228),
+    };
+    assert_eq!(get_var.to_string(), "GetVar 228");
+}
@@ -0,0 +1,25 @@
+use quickpars::{BinaryReader, Opcode};
+
+/// Decodes a single operator from its bytes.
+fn decode(bytes: &[u8]) -> anyhow::Result<Opcode> {
+    Opcode::from_reader(&mut BinaryReader::new(bytes)).map(|(_, op)| op)
+}
+
+#[test]
+fn zero_eval_scopes_are_rejected() {
+    // `Eval` with one argument and `ApplyEval`, with an encoded scope of 0.
+    for bytes in [&[49, 1, 0, 0, 0][..], &[50, 0, 0]] {
+        let err = decode(bytes).expect_err("the scope to be rejected");
+        assert_eq!(err.to_string(), "Invalid eval scope: 0");
+    }
+}
+
+#[test]
+fn eval_scopes_are_decoded_minus_one() -> anyhow::Result<()> {
+    assert_eq!(
+        decode(&[49, 1, 0, 1, 0])?,
+        Opcode::Eval { scope: 0, argc: 1 }
+    );
+    assert_eq!(decode(&[50, 3, 0])?, Opcode::ApplyEval { scope: 2 });
+    Ok(())
+}
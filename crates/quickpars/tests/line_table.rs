@@ -0,0 +1,33 @@
+use quickpars::{BinaryReader, DebugInfo, PC2LINE_BASE, PC2LINE_OP_FIRST, PC2LINE_RANGE};
+
+/// Encodes a pc2line entry in its short form.
+fn short(diff_pc: u8, diff: i32) -> u8 {
+    PC2LINE_OP_FIRST + diff_pc * PC2LINE_RANGE + (diff - PC2LINE_BASE) as u8
+}
+
+#[test]
+fn line_table_starts_at_the_function_position() {
+    // The line advances by 1 at pc 2 and by 2 at pc 5.
+    let lines = [short(2, 1), short(3, 2)];
+    // The column advances by 7 at pc 4, in the long form: a zero opcode
+    // followed by the pc and column deltas.
+    let cols = [0, 4, 7];
+    let debug = DebugInfo::new(0, 3, 5, BinaryReader::new(&lines), BinaryReader::new(&cols));
+
+    let table = debug.line_table();
+    assert_eq!(table, [(0, 3, 5), (2, 4, 5), (4, 4, 12), (5, 6, 12)]);
+    assert_eq!(table[0], (0, debug.lineno, debug.colno));
+    assert!(table.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(debug.line_for_pc(3), Some((4, 5)));
+}
+
+#[test]
+fn corrupt_deltas_end_the_line_table() {
+    // The line advances by 1 at pc 2, then moves before the first line.
+    let lines = [short(2, 1), 0, 1, 0x7b];
+    // The column advances by 1 at pc 3, then the pc overflows.
+    let cols = [short(3, 1), 0, 0xff, 0xff, 0xff, 0xff, 0x0f, 0];
+    let debug = DebugInfo::new(0, 3, 5, BinaryReader::new(&lines), BinaryReader::new(&cols));
+
+    assert_eq!(debug.line_table(), [(0, 3, 5), (2, 4, 5), (3, 4, 6)]);
+}
@@ -0,0 +1,76 @@
+use quickpars::{AtomIndex, ConstValue, Parser, Payload, Tag, VERSION};
+
+/// Builds bytecode without atoms holding `{ a: [1, <ref 0>] }`, i.e., an
+/// object whose array property refers back to the object.
+fn self_referential_object() -> Vec<u8> {
+    vec![
+        VERSION,
+        0,
+        Tag::Object as u8,
+        1,
+        // The first built-in atom, tagged as an atom index.
+        2,
+        Tag::Array as u8,
+        2,
+        Tag::I32 as u8,
+        1,
+        Tag::ObjectRef as u8,
+        0,
+    ]
+}
+
+#[test]
+fn back_references_are_parsed_as_ids() {
+    let bytes = self_referential_object();
+    let value = Parser::new()
+        .parse_buffer(&bytes)
+        .find_map(|payload| match payload.unwrap() {
+            Payload::Value(value) => Some(value),
+            _ => None,
+        })
+        .expect("a constant value");
+
+    let ConstValue::Object(props) = &value else {
+        panic!("expected an object, found {value}");
+    };
+    assert_eq!(props[0].0, AtomIndex::from_u32(1));
+    assert_eq!(
+        props[0].1,
+        ConstValue::Array(vec![ConstValue::I32(1), ConstValue::ObjectRef(0)])
+    );
+    assert_eq!(value.nested_object(0), Some(&value));
+    assert_eq!(value.nested_object(1), Some(&props[0].1));
+    assert_eq!(value.nested_object(2), None);
+}
+
+#[test]
+fn forward_references_are_an_error() {
+    let mut bytes = self_referential_object();
+    *bytes.last_mut().unwrap() = 2;
+    let err = Parser::new()
+        .parse_buffer(&bytes)
+        .find_map(|payload| payload.err())
+        .expect("parsing to fail");
+    assert!(
+        format!("{err:#}").contains("Invalid object reference 2 >= 2"),
+        "unexpected error: {err:#}"
+    );
+}
+
+#[test]
+fn references_span_the_bytecode_object() {
+    // `[<ref 1>]`, referring to the array of the first value.
+    let mut bytes = self_referential_object();
+    bytes.extend([Tag::Array as u8, 1, Tag::ObjectRef as u8, 1]);
+    let values = Parser::new()
+        .parse_buffer(&bytes)
+        .filter_map(|payload| match payload.unwrap() {
+            Payload::Value(value) => Some(value),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0].object_count(), 2);
+    assert_eq!(values[1], ConstValue::Array(vec![ConstValue::ObjectRef(1)]));
+}
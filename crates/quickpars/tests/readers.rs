@@ -0,0 +1,24 @@
+use quickpars::{BinaryReader, Opcode};
+
+#[test]
+fn reset_re_reads_the_first_opcode() {
+    let mut bytes = vec![];
+    for op in [
+        Opcode::PushI32 { value: 7 },
+        Opcode::Drop,
+        Opcode::ReturnUndef,
+    ] {
+        op.encode(&mut bytes);
+    }
+
+    let mut reader = BinaryReader::new(&bytes);
+    let first = Opcode::from_reader(&mut reader).unwrap();
+    while !reader.done() {
+        Opcode::from_reader(&mut reader).unwrap();
+    }
+
+    reader.reset();
+    assert!(!reader.done());
+    assert_eq!(Opcode::from_reader(&mut reader).unwrap(), first);
+    assert_eq!(first, (0, Opcode::PushI32 { value: 7 }));
+}
@@ -0,0 +1,104 @@
+use quickpars::{Opcode, Parser, Payload, Tag, VERSION};
+
+/// Encodes a function named after the first atom, without arguments,
+/// locals or constants, with the given operators.
+fn function(ops: &[Opcode]) -> Vec<u8> {
+    let mut operators = vec![];
+    for op in ops {
+        op.encode(&mut operators);
+    }
+    // Tag, flags and JS mode.
+    let mut bytes = vec![Tag::FunctionBytecode as u8, 0, 0, 0];
+    // Name atom, argument, variable and defined argument counts, stack
+    // size, closure variable count and constant pool size.
+    bytes.extend([0, 0, 0, 0, 1, 0, 0]);
+    // Bytecode length and local count.
+    bytes.extend([operators.len() as u8, 0]);
+    bytes.extend(operators);
+    bytes
+}
+
+/// Renders the payloads, to compare them.
+fn render(payloads: &[Payload]) -> Vec<String> {
+    payloads.iter().map(|p| format!("{p:?}")).collect()
+}
+
+#[test]
+fn resuming_after_the_first_function_matches_a_full_parse() {
+    let mut bytes = vec![VERSION, 1, 2, b'f'];
+    bytes.extend(function(&[Opcode::Undefined, Opcode::Return]));
+    bytes.extend(function(&[Opcode::Null, Opcode::Drop, Opcode::ReturnUndef]));
+    bytes.extend(function(&[Opcode::PushTrue, Opcode::Return]));
+
+    let full = Parser::new()
+        .parse_buffer(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    // Parse the first function, up to the boundary with the second one.
+    let mut parser = Parser::new();
+    let mut payloads = vec![];
+    let snapshot = loop {
+        let payload = parser.parse_next(&bytes).unwrap().unwrap();
+        let is_operators = matches!(payload, Payload::FunctionOperators(_));
+        payloads.push(payload);
+        if is_operators {
+            break parser.state_snapshot().unwrap();
+        }
+    };
+    assert_eq!(render(&payloads), render(&full[..payloads.len()]));
+
+    let rest = Parser::new()
+        .resume(snapshot, &bytes)
+        .unwrap()
+        .parse_buffer(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    payloads.extend(rest);
+    assert_eq!(render(&payloads), render(&full));
+}
+
+#[test]
+fn snapshots_are_only_taken_at_function_boundaries() {
+    let mut bytes = vec![VERSION, 0];
+    bytes.extend(function(&[Opcode::ReturnUndef]));
+
+    let mut parser = Parser::new();
+    parser.parse_next(&bytes).unwrap().unwrap();
+    parser.parse_next(&bytes).unwrap().unwrap();
+    assert!(parser.state_snapshot().is_ok());
+    // Right after the function header, the locals are expected next.
+    parser.parse_next(&bytes).unwrap().unwrap();
+    assert!(parser.state_snapshot().is_err());
+}
+
+#[test]
+fn resuming_keeps_the_configuration() {
+    let mut bytes = vec![VERSION, 0];
+    bytes.extend(function(&[Opcode::ReturnUndef]));
+    // A top-level value nested beyond the maximum depth.
+    for _ in 0..5 {
+        bytes.extend([Tag::Array as u8, 1]);
+    }
+    bytes.push(Tag::Null as u8);
+
+    let parser = Parser::new().with_max_depth(4);
+    let mut first = parser;
+    let snapshot = loop {
+        let payload = first.parse_next(&bytes).unwrap().unwrap();
+        if matches!(payload, Payload::FunctionOperators(_)) {
+            break first.state_snapshot().unwrap();
+        }
+    };
+
+    let err = parser
+        .resume(snapshot, &bytes)
+        .unwrap()
+        .parse_buffer(&bytes)
+        .find_map(|payload| payload.err())
+        .expect("parsing to fail");
+    assert!(
+        format!("{err:#}").contains("exceeds the maximum depth of 4"),
+        "unexpected error: {err:#}"
+    );
+}
@@ -0,0 +1,37 @@
+use proptest::prelude::*;
+use quickpars::{BinaryReader, Opcode};
+
+/// Decodes a single opcode from an opcode byte followed by immediates.
+fn decode(byte: u8, immediates: [u8; 9]) -> Option<Opcode> {
+    let mut bytes = vec![byte];
+    bytes.extend(immediates);
+    Opcode::from_reader(&mut BinaryReader::new(&bytes))
+        .ok()
+        .map(|(_, op)| op)
+}
+
+proptest! {
+    #[test]
+    fn decode_encode_is_identity(
+        candidates in prop::collection::vec((any::<u8>(), any::<[u8; 9]>()), 0..64)
+    ) {
+        let ops = candidates
+            .into_iter()
+            .filter_map(|(byte, immediates)| decode(byte, immediates))
+            .collect::<Vec<_>>();
+
+        let mut bytes = vec![];
+        let mut pcs = vec![];
+        for op in &ops {
+            pcs.push(bytes.len() as u32);
+            op.encode(&mut bytes);
+        }
+
+        let mut reader = BinaryReader::new(&bytes);
+        let mut decoded = vec![];
+        while !reader.done() {
+            decoded.push(Opcode::from_reader(&mut reader).unwrap());
+        }
+        prop_assert_eq!(decoded, pcs.into_iter().zip(ops).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,87 @@
+use quickpars::{AtomIndex, AtomTable, HeaderSection, Opcode, Parser, Payload, Tag, VERSION};
+
+/// Encodes a function named after the first atom, without arguments,
+/// locals or constants, with the given operators.
+fn function(ops: &[Opcode]) -> Vec<u8> {
+    let mut operators = vec![];
+    for op in ops {
+        op.encode(&mut operators);
+    }
+    // Tag, flags and JS mode.
+    let mut bytes = vec![Tag::FunctionBytecode as u8, 0, 0, 0];
+    // Name atom, argument, variable and defined argument counts, stack
+    // size, closure variable count and constant pool size.
+    bytes.extend([0, 0, 0, 0, 1, 0, 0]);
+    // Bytecode length and local count.
+    bytes.extend([operators.len() as u8, 0]);
+    bytes.extend(operators);
+    bytes
+}
+
+/// Builds bytecode interning the given atoms, followed by two functions,
+/// along with the offsets of the functions.
+fn two_functions(atoms: &[&str]) -> (Vec<u8>, Vec<usize>) {
+    let mut bytes = vec![VERSION, atoms.len() as u8];
+    for atom in atoms {
+        bytes.push((atom.len() as u8) << 1);
+        bytes.extend(atom.as_bytes());
+    }
+    let mut offsets = vec![];
+    for ops in [
+        &[Opcode::Undefined, Opcode::Return][..],
+        &[Opcode::ReturnUndef],
+    ] {
+        offsets.push(bytes.len());
+        bytes.extend(function(ops));
+    }
+    (bytes, offsets)
+}
+
+/// Parses the bytecode, returning the header and the offsets of the
+/// functions.
+fn parse(parser: Parser, bytes: &[u8]) -> (HeaderSection, Vec<usize>) {
+    let mut parser = parser;
+    let mut header = None;
+    let mut offsets = vec![];
+    let mut offset = 0;
+    while let Some(payload) = parser.parse_next(bytes) {
+        match payload.unwrap() {
+            Payload::Header(h) => header = Some(h),
+            Payload::FunctionHeader(_) => offsets.push(offset),
+            _ => {}
+        }
+        if let Ok(snapshot) = parser.state_snapshot() {
+            offset = snapshot.offset();
+        }
+    }
+    (header.unwrap(), offsets)
+}
+
+#[test]
+fn skipping_atoms_keeps_the_function_offsets() {
+    let (bytes, offsets) = two_functions(&["first", "second"]);
+
+    let (header, parsed) = parse(Parser::new(), &bytes);
+    assert_eq!(parsed, offsets);
+    assert_eq!(header.atoms.len(), header.builtin_atom_count() + 2);
+
+    let (header, skipped) = parse(Parser::new().skip_to_functions(), &bytes);
+    assert_eq!(skipped, offsets);
+    assert_eq!(header.atom_count, 2);
+    assert_eq!(header.atoms.len(), header.builtin_atom_count());
+    assert_eq!(
+        header.builtin_atom_count(),
+        AtomTable::default().names().count()
+    );
+}
+
+#[test]
+fn skipped_atoms_resolve_to_none() {
+    let (bytes, _) = two_functions(&["first"]);
+    let (header, _) = parse(Parser::new().skip_to_functions(), &bytes);
+
+    let builtin = AtomIndex::from_u32(1);
+    let interned = AtomIndex::from_u32(header.builtin_atom_count() as u32);
+    assert_eq!(header.atom(builtin), Some(header.atoms[1].as_str()));
+    assert_eq!(header.atom(interned), None);
+}
@@ -0,0 +1,20 @@
+use quickpars::{AtomIndex, Opcode, SpecialObjectKind};
+
+#[test]
+fn define_class_hints_at_a_class() {
+    let op = Opcode::DefineClass {
+        flags: 0,
+        atom: AtomIndex::from_u32(0),
+    };
+    assert_eq!(op.source_hint(), Some("class declaration"));
+}
+
+#[test]
+fn generic_operators_have_no_hint() {
+    assert_eq!(Opcode::Add.source_hint(), None);
+    assert_eq!(Opcode::Drop.source_hint(), None);
+    let this_func = Opcode::SpecialObject {
+        argument: SpecialObjectKind::ThisFunc as i32,
+    };
+    assert_eq!(this_func.source_hint(), None);
+}
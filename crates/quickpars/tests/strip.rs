@@ -0,0 +1,51 @@
+use quickpars::{strip_debug_info, Opcode, Parser, Payload, Tag, DEBUG_FLAG, VERSION};
+
+/// Encodes a function without arguments, locals or constants, returning
+/// `undefined`, followed by debug information.
+fn function_with_debug_info() -> Vec<u8> {
+    let mut operators = vec![];
+    Opcode::ReturnUndef.encode(&mut operators);
+    // Tag, flags and JS mode.
+    let mut bytes = vec![Tag::FunctionBytecode as u8];
+    bytes.extend(DEBUG_FLAG.to_le_bytes());
+    bytes.push(0);
+    // Name atom, argument, variable and defined argument counts, stack
+    // size, closure variable count and constant pool size.
+    bytes.extend([0, 0, 0, 0, 0, 0, 0]);
+    // Bytecode length and local count.
+    bytes.extend([operators.len() as u8, 0]);
+    bytes.extend(operators);
+    // Filename atom, line number and line table, column number and column
+    // table.
+    bytes.extend([0, 1, 2, 0, 0, 1, 2, 0, 0]);
+    bytes
+}
+
+#[test]
+fn stripped_bytecode_has_no_debug_info() {
+    let mut bytes = vec![VERSION, 0];
+    bytes.extend(function_with_debug_info());
+    bytes.extend(function_with_debug_info());
+    let count_debug_info = |bytes: &[u8]| {
+        Parser::new()
+            .parse_buffer(bytes)
+            .map(Result::unwrap)
+            .filter(|p| matches!(p, Payload::FunctionDebugInfo(_)))
+            .count()
+    };
+    assert_eq!(count_debug_info(&bytes), 2);
+
+    let stripped = strip_debug_info(&bytes).unwrap();
+    assert!(stripped.len() < bytes.len());
+    assert_eq!(count_debug_info(&stripped), 0);
+
+    let headers = Parser::new()
+        .parse_buffer(&stripped)
+        .filter_map(|p| match p.unwrap() {
+            Payload::FunctionHeader(header) => Some(header),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(headers.len(), 2);
+    assert!(headers.iter().all(|h| h.flags & DEBUG_FLAG == 0));
+}
@@ -0,0 +1,14 @@
+use quickpars::ConstValue;
+
+// Not an approximation of PI.
+#[allow(clippy::approx_constant)]
+#[test]
+fn f64_constants_render_as_number_literals() {
+    let render = |v: f64| ConstValue::F64(v).to_string();
+    assert_eq!(render(3.14), "3.14");
+    assert_eq!(render(-0.0), "-0");
+    assert_eq!(render(0.0), "0");
+    assert_eq!(render(f64::NAN), "NaN");
+    assert_eq!(render(f64::INFINITY), "Infinity");
+    assert_eq!(render(f64::NEG_INFINITY), "-Infinity");
+}
@@ -0,0 +1,20 @@
+use quickpars::{describe_version, VersionInfo, VERSION};
+
+#[test]
+fn version_67_has_bignum() {
+    assert_eq!(
+        describe_version(VERSION),
+        VersionInfo {
+            version: 67,
+            has_bignum: true,
+            quickjs_base: 3,
+        }
+    );
+}
+
+#[test]
+fn base_version_has_no_bignum() {
+    let info = describe_version(3);
+    assert!(!info.has_bignum);
+    assert_eq!(info.quickjs_base, 3);
+}
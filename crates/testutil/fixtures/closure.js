@@ -0,0 +1,11 @@
+function counter() {
+  let count = 0;
+  return function increment() {
+    count += 1;
+    return count;
+  };
+}
+
+const next = counter();
+next();
+console.log(next());
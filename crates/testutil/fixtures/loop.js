@@ -0,0 +1,9 @@
+function sum(n) {
+  let total = 0;
+  for (let i = 0; i < n; i++) {
+    total += i;
+  }
+  return total;
+}
+
+console.log(sum(10));
@@ -0,0 +1,91 @@
+//! Encoding of QuickJS bytecode, to build bytecode that can't be compiled
+//! from JavaScript, e.g., a function that isn't wrapped in a module.
+
+use jac_translate::quickpars::{
+    AtomIndex, AtomTable, FunctionClosureVar, FunctionLocal, FunctionSectionHeader, Tag,
+    DEBUG_FLAG, VERSION,
+};
+
+use crate::wasm::write_leb128;
+
+/// Returns the index of the interned atom at the given position of the
+/// atoms passed to [encode_bytecode], which follow the built-in atoms of
+/// the default [AtomTable].
+pub fn interned_atom(position: u32) -> AtomIndex {
+    AtomIndex::from_u32(AtomTable::default().names().count() as u32 + position)
+}
+
+/// Encodes a bytecode object interning the given atoms, followed by the
+/// given sections, e.g., functions encoded through [encode_function].
+///
+/// Atoms are encoded as 8-bit strings, so they are expected to be ASCII.
+pub fn encode_bytecode(atoms: &[&str], sections: &[u8]) -> Vec<u8> {
+    let mut out = vec![VERSION];
+    write_leb128(&mut out, atoms.len() as u32);
+    for atom in atoms {
+        assert!(atom.is_ascii(), "atom `{atom}` isn't ASCII");
+        write_leb128(&mut out, (atom.len() as u32) << 1);
+        out.extend_from_slice(atom.as_bytes());
+    }
+    out.extend_from_slice(sections);
+    out
+}
+
+/// Encodes a function section, with the given locals, closure variables
+/// and operators.
+///
+/// The constant pool, of `constant_pool_size` entries, is expected to
+/// follow the function. Panics if the counts in the header don't match the
+/// given parts, or if the header declares debug information, which isn't
+/// encoded.
+pub fn encode_function(
+    header: &FunctionSectionHeader,
+    locals: &[FunctionLocal],
+    closure_vars: &[FunctionClosureVar],
+    operators: &[u8],
+) -> Vec<u8> {
+    assert_eq!(header.local_count as usize, locals.len());
+    assert_eq!(header.closure_var_count as usize, closure_vars.len());
+    assert_eq!(header.bytecode_len as usize, operators.len());
+    assert_eq!(
+        header.flags & DEBUG_FLAG,
+        0,
+        "debug information isn't encoded"
+    );
+
+    let mut out = vec![Tag::FunctionBytecode as u8];
+    out.extend_from_slice(&header.flags.to_le_bytes());
+    out.push(header.is_strict as u8);
+    write_atom(&mut out, header.name_index);
+    for count in [
+        header.arg_count,
+        header.var_count,
+        header.defined_arg_count,
+        header.stack_size,
+        header.closure_var_count,
+        header.constant_pool_size,
+        header.bytecode_len,
+        header.local_count,
+    ] {
+        write_leb128(&mut out, count);
+    }
+    for local in locals {
+        write_atom(&mut out, local.name_index);
+        write_leb128(&mut out, local.scope_level);
+        write_leb128(&mut out, local.scope_next);
+        out.push(local.flags);
+    }
+    for var in closure_vars {
+        write_atom(&mut out, var.name_index);
+        write_leb128(&mut out, var.index);
+        out.push(var.flags);
+    }
+    out.extend_from_slice(operators);
+    out
+}
+
+/// Appends an atom reference, i.e., the atom index shifted to leave room
+/// for the tagged integer bit.
+fn write_atom(out: &mut Vec<u8>, atom: AtomIndex) {
+    write_leb128(out, atom.as_u32() << 1);
+}
@@ -0,0 +1,109 @@
+//! Test utilities to obtain QuickJS bytecode without setting up a Javy
+//! [`Runtime`] in every test, to build bytecode and translations by hand and
+//! to inspect compiled Wasm modules.
+
+use jac_translate::{
+    quickpars::{
+        AtomIndex, FuncIndex, FunctionSectionHeader, FunctionSectionHeaderBuilder, Opcode,
+    },
+    FunctionTranslation, Translation,
+};
+use javy::{Config, Runtime};
+
+mod bytecode;
+mod wasm;
+
+pub use bytecode::{encode_bytecode, encode_function, interned_atom};
+pub use wasm::{
+    export_functions, inspect_wasm, read_leb128, write_leb128, WasmFunction, WasmModule,
+};
+
+/// The file name under which fixtures are compiled.
+pub const FIXTURE_FILENAME: &str = "index.js";
+
+/// The directory holding the sources of [FIXTURES], e.g., to pass them to
+/// tools that read JavaScript files.
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// Named JavaScript sources covering common bytecode shapes.
+pub const FIXTURES: &[(&str, &str)] = &[
+    ("loop", include_str!("../fixtures/loop.js")),
+    ("class", include_str!("../fixtures/class.js")),
+    ("closure", include_str!("../fixtures/closure.js")),
+];
+
+thread_local! {
+    /// The runtime used to compile fixtures, created on first use.
+    ///
+    /// Runtimes can't be shared across threads, so each test thread gets
+    /// its own.
+    static RUNTIME: Runtime = Runtime::new(Config::default()).expect("a default Javy runtime");
+}
+
+/// Compiles JavaScript source to QuickJS bytecode, through a Javy
+/// [`Runtime`] with the default configuration.
+///
+/// Panics if the source can't be compiled, since fixtures are expected to
+/// be valid.
+pub fn compile_fixture(src: &str) -> Vec<u8> {
+    RUNTIME.with(|runtime| {
+        runtime
+            .compile_to_bytecode(FIXTURE_FILENAME, src)
+            .expect("valid fixture source")
+    })
+}
+
+/// Returns the source of the fixture with the given name, see [FIXTURES].
+pub fn fixture_source(name: &str) -> Option<&'static str> {
+    FIXTURES
+        .iter()
+        .find(|(fixture, _)| *fixture == name)
+        .map(|(_, src)| *src)
+}
+
+/// Compiles the fixture with the given name, see [FIXTURES].
+///
+/// Panics if there is no fixture with the given name.
+pub fn named_fixture(name: &str) -> Vec<u8> {
+    let src = fixture_source(name).unwrap_or_else(|| panic!("unknown fixture `{name}`"));
+    compile_fixture(src)
+}
+
+/// Builds a translation with a single function, named `f`, made of the
+/// given operators, with one stack slot per operator.
+///
+/// Panics if the operators can't form a function.
+pub fn translation_from_ops(ops: &[Opcode]) -> Translation<'static> {
+    let header = FunctionSectionHeader::builder().with_stack_size(ops.len() as u32);
+    translation_with_header(header, ops)
+}
+
+/// Builds a translation with a single function, named `f`, made of the
+/// given operators and the header built by `header`, whose bytecode length
+/// is set to the length of the encoded operators.
+///
+/// The encoded operators are leaked, so that the translation can outlive
+/// the call. Panics if the operators can't form a function.
+pub fn translation_with_header(
+    header: FunctionSectionHeaderBuilder,
+    ops: &[Opcode],
+) -> Translation<'static> {
+    let mut operators = vec![];
+    for op in ops {
+        op.encode(&mut operators);
+    }
+    let operators: &'static [u8] = Box::leak(operators.into_boxed_slice());
+    let header = header
+        .with_name(AtomIndex::from_u32(0))
+        .with_bytecode_len(operators.len() as u32)
+        .build()
+        .expect("a valid function header");
+
+    let mut translation = Translation::default();
+    translation.header.atoms = vec!["f".to_string()];
+    let function =
+        FunctionTranslation::from_parts(header, FuncIndex::from_u32(0), operators, vec![])
+            .expect("a valid function");
+    translation.module.functions.push(function);
+    translation
+}
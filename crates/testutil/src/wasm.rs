@@ -0,0 +1,152 @@
+//! Inspection of compiled Wasm modules.
+
+use wasmparser::{Parser, Payload, TypeRef};
+
+/// The id of the export section.
+const EXPORT_SECTION_ID: u8 = 0x07;
+
+/// A function defined by a Wasm module.
+#[derive(Debug, Clone)]
+pub struct WasmFunction {
+    /// The index of the function, which follows the imported functions.
+    pub index: u32,
+    /// The number of params of the function.
+    pub params: usize,
+    /// The number of results of the function.
+    pub results: usize,
+    /// The callees of the `call` operators in the body, in order. Imported
+    /// functions are named `<module>.<name>`, and defined functions
+    /// `func <index>`.
+    pub calls: Vec<String>,
+    /// The operators in the body, in their `Debug` representation.
+    pub operators: Vec<String>,
+}
+
+/// The functions of a Wasm module.
+#[derive(Debug, Clone, Default)]
+pub struct WasmModule {
+    /// The imported functions, named `<module>.<name>`.
+    pub imports: Vec<String>,
+    /// The defined functions.
+    pub functions: Vec<WasmFunction>,
+}
+
+impl WasmModule {
+    /// Returns the defined function with the given index.
+    ///
+    /// Panics if there is no defined function with the given index.
+    pub fn function(&self, index: u32) -> &WasmFunction {
+        self.functions
+            .iter()
+            .find(|f| f.index == index)
+            .unwrap_or_else(|| panic!("no defined function with index {index}"))
+    }
+}
+
+/// Reads an unsigned LEB-128 from the front of `data`, advancing it past
+/// the encoded integer, e.g., to decode custom sections.
+///
+/// Panics if `data` doesn't start with a valid `u32`.
+pub fn read_leb128(data: &mut &[u8]) -> u32 {
+    let value = leb128::read::unsigned(data).expect("a valid LEB-128");
+    u32::try_from(value).expect("a LEB-128 encoded u32")
+}
+
+/// Appends an unsigned LEB-128.
+pub fn write_leb128(out: &mut Vec<u8>, value: u32) {
+    leb128::write::unsigned(out, value as u64).expect("writing to a Vec can't fail");
+}
+
+/// Returns the module with the given functions exported as `f<index>`, so
+/// that they can be called from an engine.
+///
+/// Panics if the module already has an export section.
+pub fn export_functions(wasm: &[u8], indices: &[u32]) -> Vec<u8> {
+    let mut contents = vec![];
+    write_leb128(&mut contents, indices.len() as u32);
+    for index in indices {
+        let name = format!("f{index}");
+        write_leb128(&mut contents, name.len() as u32);
+        contents.extend(name.as_bytes());
+        contents.push(0x00);
+        write_leb128(&mut contents, *index);
+    }
+    let mut section = vec![EXPORT_SECTION_ID];
+    write_leb128(&mut section, contents.len() as u32);
+    section.extend(contents);
+
+    // The export section goes before the start, element, data count, code
+    // and data sections.
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        if matches!(id, 8..=12) {
+            break;
+        }
+        assert_ne!(id, EXPORT_SECTION_ID, "the module already has exports");
+        let mut rest = &wasm[pos + 1..];
+        let size = read_leb128(&mut rest) as usize;
+        pos = wasm.len() - rest.len() + size;
+    }
+    [&wasm[..pos], &section, &wasm[pos..]].concat()
+}
+
+/// Returns the functions of a Wasm module.
+///
+/// Panics if the module is invalid.
+pub fn inspect_wasm(wasm: &[u8]) -> WasmModule {
+    let mut types = vec![];
+    let mut function_types = vec![];
+    let mut module = WasmModule::default();
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload.expect("a valid Wasm module") {
+            Payload::TypeSection(reader) => {
+                for ty in reader.into_iter_err_on_gc_types() {
+                    let ty = ty.expect("a valid function type");
+                    types.push((ty.params().len(), ty.results().len()));
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import.expect("a valid import");
+                    if let TypeRef::Func(_) = import.ty {
+                        module
+                            .imports
+                            .push(format!("{}.{}", import.module, import.name));
+                    }
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                for ty in reader {
+                    function_types.push(ty.expect("a valid function type index"));
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                let defined = module.functions.len();
+                let (params, results) = types[function_types[defined] as usize];
+                let mut function = WasmFunction {
+                    index: (module.imports.len() + defined) as u32,
+                    params,
+                    results,
+                    calls: vec![],
+                    operators: vec![],
+                };
+                let reader = body.get_operators_reader().expect("a valid body");
+                for op in reader {
+                    let op = op.expect("a valid operator");
+                    if let wasmparser::Operator::Call { function_index } = op {
+                        let callee = match module.imports.get(function_index as usize) {
+                            Some(import) => import.clone(),
+                            None => format!("func {function_index}"),
+                        };
+                        function.calls.push(callee);
+                    }
+                    function.operators.push(format!("{op:?}"));
+                }
+                module.functions.push(function);
+            }
+            _ => {}
+        }
+    }
+    module
+}
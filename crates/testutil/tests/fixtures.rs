@@ -0,0 +1,23 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, named_fixture, FIXTURES};
+use jac_translate::{quickpars::VERSION, TranslationBuilder};
+
+#[test]
+fn compiles_expression_to_bytecode() -> Result<()> {
+    let bytecode = compile_fixture("1+1");
+    assert_eq!(bytecode[0], VERSION);
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert!(!translation.module.functions.is_empty());
+    Ok(())
+}
+
+#[test]
+fn named_fixtures_compile() -> Result<()> {
+    for (name, _) in FIXTURES {
+        TranslationBuilder::new().translate(&named_fixture(name))?;
+    }
+    let bytecode = named_fixture("closure");
+    let closure = TranslationBuilder::new().translate(&bytecode)?;
+    assert!(closure.function_by_name("increment").is_some());
+    Ok(())
+}
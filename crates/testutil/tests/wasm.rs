@@ -0,0 +1,40 @@
+use jac_testutil::{export_functions, inspect_wasm, read_leb128, write_leb128};
+use wasmparser::{Parser, Payload};
+
+/// A module with a single `() -> ()` function.
+const MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+    0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+    0x03, 0x02, 0x01, 0x00, // function section
+    0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // code section
+];
+
+#[test]
+fn leb128_round_trips() {
+    let mut out = vec![];
+    for value in [0, 127, 128, 624485, u32::MAX] {
+        write_leb128(&mut out, value);
+    }
+    let mut data = out.as_slice();
+    for value in [0, 127, 128, 624485, u32::MAX] {
+        assert_eq!(read_leb128(&mut data), value);
+    }
+    assert!(data.is_empty());
+}
+
+#[test]
+fn exports_functions_before_the_code_section() {
+    let wasm = export_functions(MODULE, &[0]);
+    assert_eq!(inspect_wasm(&wasm).functions.len(), 1);
+
+    let mut exports = vec![];
+    for payload in Parser::new(0).parse_all(&wasm) {
+        if let Payload::ExportSection(reader) = payload.unwrap() {
+            for export in reader {
+                let export = export.unwrap();
+                exports.push((export.name.to_string(), export.index));
+            }
+        }
+    }
+    assert_eq!(exports, [("f0".to_string(), 0)]);
+}
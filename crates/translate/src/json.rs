@@ -0,0 +1,270 @@
+//! Machine-readable representation of a [Translation].
+//!
+//! Atoms, functions and module entries are resolved to their names, so that
+//! consumers don't need to know about QuickJS' index spaces.
+
+use crate::{FunctionTranslation, Translation};
+use anyhow::Result;
+use quickpars::{ModuleExportEntry, Opcode};
+use serde::{Deserialize, Serialize};
+
+/// The JSON representation of a [Translation].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslationJson {
+    /// Every atom accessible to the module, including built-in atoms.
+    pub atoms: Vec<String>,
+    /// The module header.
+    pub module: ModuleJson,
+    /// The functions, in index order.
+    pub functions: Vec<FunctionJson>,
+}
+
+/// The JSON representation of a module header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleJson {
+    /// The module name, if any.
+    pub name: Option<String>,
+    /// The names of the required modules.
+    pub req_modules: Vec<String>,
+    /// The module imports.
+    pub imports: Vec<ImportJson>,
+    /// The module exports.
+    pub exports: Vec<ExportJson>,
+    /// The modules whose exports are all re-exported, i.e.,
+    /// `export * from "module"`.
+    #[serde(default)]
+    pub star_exports: Vec<String>,
+}
+
+/// The JSON representation of a module import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportJson {
+    /// The imported name.
+    pub name: String,
+    /// The module the name is imported from.
+    pub module: String,
+}
+
+/// The JSON representation of a module export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportJson {
+    /// The exported name.
+    pub name: String,
+    /// The index of the exported module variable, for local exports.
+    pub var_index: Option<u32>,
+    /// The re-exported name, for indirect exports.
+    pub local_name: Option<String>,
+    /// The module the name is re-exported from, for indirect exports.
+    pub module: Option<String>,
+}
+
+/// The JSON representation of a function.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionJson {
+    /// The function index.
+    pub index: u32,
+    /// The function name.
+    pub name: String,
+    /// The function header.
+    pub header: FunctionHeaderJson,
+    /// The function locals, arguments first.
+    pub locals: Vec<LocalJson>,
+    /// The closure variables.
+    pub closure_vars: Vec<ClosureVarJson>,
+    /// The decoded operators.
+    pub operators: Vec<OperatorJson>,
+    /// The constant pool entries, rendered.
+    pub constants: Vec<String>,
+}
+
+/// The JSON representation of a function header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionHeaderJson {
+    pub flags: u16,
+    pub arg_count: u32,
+    pub var_count: u32,
+    pub defined_arg_count: u32,
+    pub stack_size: u32,
+    pub closure_var_count: u32,
+    pub constant_pool_size: u32,
+    pub bytecode_len: u32,
+    pub local_count: u32,
+    pub is_strict: bool,
+}
+
+/// The JSON representation of a function local.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalJson {
+    pub name: String,
+    pub scope_level: u32,
+    pub scope_next: u32,
+    pub flags: u8,
+}
+
+/// The JSON representation of a closure variable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosureVarJson {
+    pub name: String,
+    pub index: u32,
+    pub flags: u8,
+}
+
+/// The JSON representation of an operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorJson {
+    /// The operator offset.
+    pub pc: u32,
+    /// The operator mnemonic followed by its raw immediates.
+    pub op: String,
+}
+
+impl<'data> Translation<'data> {
+    /// Serializes the translation as JSON, see [TranslationJson].
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.to_json_repr()?)?)
+    }
+
+    /// Serializes the module header, i.e., the required modules, imports
+    /// and exports of the module, as JSON, see [ModuleJson].
+    ///
+    /// Useful to build the dependency graph of a bundle without the rest
+    /// of the translation.
+    pub fn module_graph_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.module_json())?)
+    }
+
+    /// Converts the translation to its JSON representation.
+    pub fn to_json_repr(&self) -> Result<TranslationJson> {
+        let functions = self
+            .module
+            .functions
+            .iter()
+            .map(|f| self.function_json(f))
+            .collect::<Result<_>>()?;
+
+        Ok(TranslationJson {
+            atoms: self.header.atoms.clone(),
+            module: self.module_json(),
+            functions,
+        })
+    }
+
+    /// Converts the module header to its JSON representation.
+    fn module_json(&self) -> ModuleJson {
+        let header = &self.module.header;
+        let req_modules = header
+            .req_modules
+            .iter()
+            .map(|i| self.atom(*i))
+            .collect::<Vec<_>>();
+        let req_module = |i: u32| {
+            req_modules
+                .get(i as usize)
+                .cloned()
+                .unwrap_or_else(|| format!("<invalid module {}>", i))
+        };
+        let imports = header
+            .imports
+            .iter()
+            .map(|i| ImportJson {
+                name: self.atom(i.name_idx),
+                module: req_module(i.req_module_idx),
+            })
+            .collect();
+        let exports = header
+            .exports
+            .iter()
+            .map(|e| match e {
+                ModuleExportEntry::Local {
+                    var_idx,
+                    export_name_idx,
+                } => ExportJson {
+                    name: self.atom(*export_name_idx),
+                    var_index: Some(*var_idx),
+                    local_name: None,
+                    module: None,
+                },
+                ModuleExportEntry::Indirect {
+                    module_idx,
+                    local_name_idx,
+                    export_name_idx,
+                } => ExportJson {
+                    name: self.atom(*export_name_idx),
+                    var_index: None,
+                    local_name: Some(self.atom(*local_name_idx)),
+                    module: Some(req_module(*module_idx)),
+                },
+            })
+            .collect();
+        // Star exports refer to the required modules.
+        let star_exports = header.star_exports.iter().map(|i| req_module(*i)).collect();
+        ModuleJson {
+            name: self.header.atoms.get(header.name_index as usize).cloned(),
+            req_modules,
+            imports,
+            exports,
+            star_exports,
+        }
+    }
+
+    /// Converts a function to its JSON representation.
+    fn function_json(&self, func: &FunctionTranslation) -> Result<FunctionJson> {
+        let h = &func.header;
+        let mut operators = vec![];
+        let mut reader = func.operators_reader();
+        while !reader.done() {
+            let (pc, op) = Opcode::from_reader(&mut reader)?;
+            operators.push(OperatorJson {
+                pc,
+                op: op.to_string(),
+            });
+        }
+
+        Ok(FunctionJson {
+            index: func.index.as_u32(),
+            name: self.resolve_atom_name(h.name_index).to_string(),
+            header: FunctionHeaderJson {
+                flags: h.flags,
+                arg_count: h.arg_count,
+                var_count: h.var_count,
+                defined_arg_count: h.defined_arg_count,
+                stack_size: h.stack_size,
+                closure_var_count: h.closure_var_count,
+                constant_pool_size: h.constant_pool_size,
+                bytecode_len: h.bytecode_len,
+                local_count: h.local_count,
+                is_strict: h.is_strict,
+            },
+            locals: func
+                .locals
+                .iter()
+                .map(|l| LocalJson {
+                    name: self.resolve_atom_name(l.name_index).to_string(),
+                    scope_level: l.scope_level,
+                    scope_next: l.scope_next,
+                    flags: l.flags,
+                })
+                .collect(),
+            closure_vars: func
+                .closure_vars
+                .iter()
+                .map(|v| ClosureVarJson {
+                    name: self.resolve_atom_name(v.name_index).to_string(),
+                    index: v.index,
+                    flags: v.flags,
+                })
+                .collect(),
+            operators,
+            constants: func.constants().iter().map(|c| c.to_string()).collect(),
+        })
+    }
+
+    /// Resolves an atom from a raw index, tolerating invalid indices.
+    fn atom(&self, index: u32) -> String {
+        self.header
+            .atoms
+            .get(index as usize)
+            .cloned()
+            .unwrap_or_else(|| format!("<invalid atom {}>", index))
+    }
+}
@@ -2,14 +2,29 @@
 //! representation.
 
 use quickpars::{
-    AtomIndex, BinaryReader, ClosureVarIndex, ConstantPoolIndex, DebugInfo, FuncIndex,
-    FunctionClosureVar, FunctionLocal, FunctionSectionHeader, HeaderSection, LocalIndex,
-    ModuleSectionHeader, Parser, Payload,
+    AtomIndex, AtomTable, BinaryReader, ClosureVarIndex, ConstValue, ConstantPoolIndex, DebugInfo,
+    FuncIndex, FunctionClosureVar, FunctionKind, FunctionLocal, FunctionSectionHeader,
+    HeaderSection, LocalIndex, ModuleExportEntry, ModuleSectionHeader, Opcode, Parser, Payload,
+    DEBUG_FLAG,
 };
 
 pub use quickpars;
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, ensure, Result};
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde")]
+mod json;
+mod runs;
+mod stack;
+mod verify;
+
+#[cfg(feature = "serde")]
+pub use json::*;
+pub use runs::*;
+pub use verify::*;
 
 #[derive(Default, Clone, Debug)]
 pub struct Translation<'data> {
@@ -18,30 +33,144 @@ pub struct Translation<'data> {
     /// Module translation.
     // TODO: One module initially, but this should be extended to N modules.
     pub module: ModuleTranslation<'data>,
+    /// The top-level value, when the bytecode object is a primitive, e.g., a
+    /// bare number, instead of a module or a function.
+    pub value: Option<ConstValue>,
+    /// The values holding objects, by the id of their first object, in
+    /// read order, see [Translation::resolve_object_ref].
+    objects: Vec<(u32, ValueLocation)>,
 }
 
-/// When an instruction in a function references a function in the constant
-/// pool, we always need to account for 1 more element, given that in our
-/// translation layer we assume a flattened structure to represent function
-/// indices.
-const CONSTANT_POOL_OFFSET: u32 = 1;
+/// Where a value read from the bytecode is kept in the [Translation].
+#[derive(Debug, Clone, Copy)]
+enum ValueLocation {
+    /// An entry of the constant pool of a function.
+    Constant(FuncIndex, usize),
+    /// The top-level value.
+    TopLevel,
+}
 
 impl<'data> Translation<'data> {
+    /// Returns the object that a [ConstValue::ObjectRef] id refers to, if
+    /// any.
+    ///
+    /// Ids number the objects of the whole bytecode object, in read order,
+    /// so a reference can point to an object of a constant read earlier, in
+    /// any function.
+    pub fn resolve_object_ref(&self, id: u32) -> Option<&ConstValue> {
+        let next = self.objects.partition_point(|(first, _)| *first <= id);
+        let (first, location) = self.objects.get(next.checked_sub(1)?)?;
+        let value = match *location {
+            ValueLocation::Constant(func, index) => self
+                .module
+                .functions
+                .get(func.as_u32() as usize)?
+                .constants
+                .get(index)?,
+            ValueLocation::TopLevel => self.value.as_ref()?,
+        };
+        value.nested_object(id - first)
+    }
+
     // TODO: Asumes a single module.
     /// Resolves a function name from a given [`FuncIndex`].
+    ///
+    /// Panics if any of the indices is invalid, see
+    /// [`Translation::try_resolve_func_name`].
     pub fn resolve_func_name(
         &self,
         index: FuncIndex,
         pool_index: Option<ConstantPoolIndex>,
     ) -> &str {
+        self.try_resolve_func_name(index, pool_index)
+            .expect("valid function name indices")
+    }
+
+    /// Resolves a function name from a given [`FuncIndex`], or, if given,
+    /// the name of the closure at `pool_index` in its constant pool.
+    ///
+    /// Errors if the function, the constant or the name atom don't exist,
+    /// or if the constant is not a function, e.g., in corrupt bytecode.
+    pub fn try_resolve_func_name(
+        &self,
+        index: FuncIndex,
+        pool_index: Option<ConstantPoolIndex>,
+    ) -> Result<&str> {
         let index = match pool_index {
-            Some(i) => FuncIndex::from_u32(i.as_u32() + index.as_u32() + CONSTANT_POOL_OFFSET),
+            Some(i) => match self.try_function(index)?.constant(i) {
+                Some(ConstValue::Function(f)) => *f,
+                Some(_) => bail!(
+                    "Constant {} of function {} is not a function",
+                    i.as_u32(),
+                    index.as_u32()
+                ),
+                None => bail!(
+                    "Constant pool index {} out of bounds in function {}",
+                    i.as_u32(),
+                    index.as_u32()
+                ),
+            },
             None => index,
         };
 
-        let func = &self.module.functions[index.as_u32() as usize];
-        let index = func.header.name_index;
-        &self.header.atoms[index.as_u32() as usize]
+        let name_index = self.try_function(index)?.header.name_index;
+        self.header
+            .atoms
+            .get(name_index.as_u32() as usize)
+            .map(String::as_str)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Name atom {} of function {} out of bounds",
+                    name_index.as_u32(),
+                    index.as_u32()
+                )
+            })
+    }
+
+    /// Returns the function at the given index.
+    fn try_function(&self, index: FuncIndex) -> Result<&FunctionTranslation<'data>> {
+        self.module
+            .functions
+            .get(index.as_u32() as usize)
+            .ok_or_else(|| anyhow!("Function index {} out of bounds", index.as_u32()))
+    }
+
+    /// Returns the first function with the given name.
+    ///
+    /// Name lookups scan the functions linearly, resolving each name: the
+    /// functions are public and can be added to, e.g., through
+    /// [ModuleTranslation::push_func], so a cached name index could go
+    /// stale. Callers looking up many names should build their own index
+    /// from [Translation::resolve_func_name].
+    pub fn function_by_name(&self, name: &str) -> Option<&FunctionTranslation<'data>> {
+        self.module
+            .functions
+            .iter()
+            .find(|func| self.has_name(func, name))
+    }
+
+    /// Returns the index of the first function with the given name, see
+    /// [Translation::function_by_name].
+    pub fn function_index_by_name(&self, name: &str) -> Option<FuncIndex> {
+        self.function_by_name(name).map(|func| func.index)
+    }
+
+    /// Returns all the functions with the given name, e.g., multiple
+    /// anonymous functions, in module order, see
+    /// [Translation::function_by_name].
+    pub fn functions_by_name(&self, name: &str) -> Vec<&FunctionTranslation<'data>> {
+        self.module
+            .functions
+            .iter()
+            .filter(|func| self.has_name(func, name))
+            .collect()
+    }
+
+    /// Whether the function has the given name. Functions whose name can't
+    /// be resolved have none.
+    fn has_name(&self, func: &FunctionTranslation, name: &str) -> bool {
+        self.try_resolve_func_name(func.index, None)
+            .is_ok_and(|n| n == name)
     }
 
     /// Resolves a closure variable name.
@@ -68,9 +197,165 @@ impl<'data> Translation<'data> {
     }
 
     /// Resolves an atom name from an index.
+    ///
+    /// Panics if the atom doesn't exist, see
+    /// [`Translation::try_resolve_atom_name`].
     pub fn resolve_atom_name(&self, index: AtomIndex) -> &str {
         &self.header.atoms[index.as_u32() as usize]
     }
+
+    /// Resolves an atom name from an index, if the atom exists.
+    ///
+    /// Interned atoms don't exist if the header was parsed through
+    /// [`quickpars::Parser::skip_to_functions`].
+    pub fn try_resolve_atom_name(&self, index: AtomIndex) -> Option<&str> {
+        self.header.atom(index)
+    }
+
+    /// Drops the debug information of every function, clearing the debug
+    /// flag in their headers.
+    ///
+    /// See [quickpars::strip_debug_info] to strip the debug information
+    /// from the bytecode itself.
+    pub fn strip_debug(&mut self) {
+        for func in &mut self.module.functions {
+            func.debug = None;
+            func.header.flags &= !DEBUG_FLAG;
+        }
+    }
+
+    /// Returns an overview of the size of the module.
+    pub fn stats(&self) -> ModuleStats {
+        let functions = &self.module.functions;
+        ModuleStats {
+            function_count: functions.len(),
+            total_operator_bytes: functions.iter().map(|f| f.operators.data().len()).sum(),
+            atom_count: self.header.atoms.len(),
+            interned_atom_count: self.header.atoms.len() - self.header.builtin_atom_count(),
+            has_debug: functions.iter().any(|f| f.debug.is_some()),
+        }
+    }
+
+    /// Returns the interned atoms, i.e., excluding the built-in ones, that
+    /// are not referenced by the module header nor by any function, in
+    /// index order.
+    pub fn unused_atoms(&self) -> Result<Vec<(AtomIndex, &str)>> {
+        let module = &self.module.header;
+        let mut used = HashSet::new();
+        used.insert(module.name_index);
+        used.extend(module.req_modules.iter().copied());
+        for export in &module.exports {
+            match export {
+                ModuleExportEntry::Local {
+                    export_name_idx, ..
+                } => used.extend([*export_name_idx]),
+                ModuleExportEntry::Indirect {
+                    local_name_idx,
+                    export_name_idx,
+                    ..
+                } => used.extend([*local_name_idx, *export_name_idx]),
+            }
+        }
+        used.extend(module.imports.iter().map(|i| i.name_idx));
+        for func in &self.module.functions {
+            used.extend(func.atom_references()?.iter().map(AtomIndex::as_u32));
+        }
+        if let Some(value) = &self.value {
+            used.extend(value.atom_references().iter().map(AtomIndex::as_u32));
+        }
+
+        Ok(self
+            .header
+            .atoms
+            .iter()
+            .enumerate()
+            .skip(self.header.builtin_atom_count())
+            .filter(|(i, _)| !used.contains(&(*i as u32)))
+            .map(|(i, name)| (AtomIndex::from_u32(i as u32), name.as_str()))
+            .collect())
+    }
+
+    /// Returns the `(caller, offset)` pairs of every `FClosure` and
+    /// `FClosure8` operator that instantiates the given function, in module
+    /// and offset order.
+    ///
+    /// The operators of a function are inspected up to the first operator
+    /// that fails to decode.
+    pub fn call_sites(&self, target: FuncIndex) -> Vec<(FuncIndex, u32)> {
+        let mut sites = vec![];
+        for func in &self.module.functions {
+            let mut reader = func.operators_reader();
+            while !reader.done() {
+                let Ok((pc, op)) = Opcode::from_reader(&mut reader) else {
+                    break;
+                };
+                let index = match op {
+                    Opcode::FClosure { index } | Opcode::FClosure8 { index } => index,
+                    _ => continue,
+                };
+                if let Some(ConstValue::Function(f)) = func.constant(index) {
+                    if *f == target {
+                        sites.push((func.index, pc));
+                    }
+                }
+            }
+        }
+        sites
+    }
+
+    /// Groups the functions with the same [FunctionTranslation::body_fingerprint],
+    /// i.e., candidates for deduplication, in module order.
+    ///
+    /// Only groups of two or more functions are returned.
+    pub fn duplicate_functions(&self) -> Vec<Vec<FuncIndex>> {
+        let mut groups: Vec<Vec<FuncIndex>> = vec![];
+        let mut by_fingerprint: HashMap<u64, usize> = HashMap::new();
+        for func in &self.module.functions {
+            match by_fingerprint.entry(func.body_fingerprint()) {
+                Entry::Occupied(group) => groups[*group.get()].push(func.index),
+                Entry::Vacant(group) => {
+                    group.insert(groups.len());
+                    groups.push(vec![func.index]);
+                }
+            }
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Returns whether any function uses an opcode that QuickJS only emits
+    /// when compiled with BIGNUM support, i.e., `MulPow10` and `MathMod`.
+    ///
+    /// The operators of a function are inspected up to the first operator
+    /// that fails to decode.
+    pub fn uses_bignum_opcodes(&self) -> bool {
+        self.module.functions.iter().any(|func| {
+            let mut reader = func.operators_reader();
+            while !reader.done() {
+                match Opcode::from_reader(&mut reader) {
+                    Ok((_, Opcode::MulPow10 | Opcode::MathMod)) => return true,
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+            false
+        })
+    }
+}
+
+/// Size overview of a [Translation].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModuleStats {
+    /// The number of functions in the module.
+    pub function_count: usize,
+    /// The size in bytes of the operators of all the functions.
+    pub total_operator_bytes: usize,
+    /// The number of atoms, including the built-in ones.
+    pub atom_count: usize,
+    /// The number of atoms interned by the bytecode.
+    pub interned_atom_count: usize,
+    /// Whether any function carries debug information.
+    pub has_debug: bool,
 }
 
 /// A function translation.
@@ -92,6 +377,8 @@ pub struct FunctionTranslation<'data> {
     pub debug: Option<DebugInfo<'data>>,
     /// The index of this function in the module.
     pub index: FuncIndex,
+    /// The constant pool.
+    constants: Vec<ConstValue>,
 }
 
 impl<'data> FunctionTranslation<'data> {
@@ -103,7 +390,215 @@ impl<'data> FunctionTranslation<'data> {
             operators: BinaryReader::empty(),
             debug: Default::default(),
             index,
+            constants: Default::default(),
+        }
+    }
+
+    /// Creates a function from its parts rather than from bytecode, e.g.,
+    /// to test code generation without bytecode fixtures.
+    ///
+    /// Errors if the operators or the constants don't match the lengths
+    /// declared in the header.
+    pub fn from_parts(
+        header: FunctionSectionHeader,
+        index: FuncIndex,
+        operators: &'data [u8],
+        constants: Vec<ConstValue>,
+    ) -> Result<Self> {
+        ensure!(
+            operators.len() == header.bytecode_len as usize,
+            "Expected {} operator bytes, found {}",
+            header.bytecode_len,
+            operators.len()
+        );
+        ensure!(
+            constants.len() == header.constant_pool_size as usize,
+            "Expected {} constants, found {}",
+            header.constant_pool_size,
+            constants.len()
+        );
+        let mut func = Self::new(header, index);
+        func.operators = BinaryReader::new(operators);
+        func.constants = constants;
+        Ok(func)
+    }
+
+    /// Returns the entries of the constant pool.
+    pub fn constants(&self) -> &[ConstValue] {
+        &self.constants
+    }
+
+    /// Returns the constant pool entry at the given index, as encoded in
+    /// operators.
+    pub fn constant(&self, index: ConstantPoolIndex) -> Option<&ConstValue> {
+        self.constants.get(index.as_u32() as usize)
+    }
+
+    /// Returns the raw bytes of the function operators, e.g., to hash the
+    /// function body for caching.
+    pub fn operator_bytes(&self) -> &'data [u8] {
+        self.operators.data()
+    }
+
+    /// Returns a fresh reader over the function operators, positioned at the
+    /// first operator.
+    pub fn operators_reader(&self) -> BinaryReader<'data> {
+        let mut reader = self.operators;
+        reader.reset();
+        reader
+    }
+
+    /// Returns the atoms referenced by the function: its name, the names of
+    /// its locals and closure variables, its debug filename, the property
+    /// keys of its constant objects and the atom immediates of its
+    /// operators.
+    pub fn atom_references(&self) -> Result<HashSet<AtomIndex>> {
+        let mut atoms = HashSet::new();
+        atoms.insert(self.header.name_index);
+        atoms.extend(self.locals.iter().map(|l| l.name_index));
+        atoms.extend(self.closure_vars.iter().map(|v| v.name_index));
+        if let Some(debug) = &self.debug {
+            atoms.insert(AtomIndex::from_u32(debug.filename));
         }
+        // The property keys of constant objects.
+        for constant in &self.constants {
+            atoms.extend(constant.atom_references());
+        }
+        let mut reader = self.operators_reader();
+        while !reader.done() {
+            let (_, op) = Opcode::from_reader(&mut reader)?;
+            atoms.extend(op.atom());
+        }
+
+        Ok(atoms)
+    }
+
+    /// Validates that the operand stack is balanced across all the paths of
+    /// the function, by replaying its operators.
+    ///
+    /// Useful to catch decoding errors, e.g., an immediate read with the
+    /// wrong width.
+    pub fn check_stack_balance(&self) -> Result<()> {
+        stack::check_stack_balance(self)
+    }
+
+    /// Returns the locals in the scope chain of the given local, starting
+    /// with the local itself, by following the `scope_next` links.
+    ///
+    /// The chain visits the locals of the local's scope and then the ones
+    /// of the enclosing scopes, and ends at a `scope_next` of `0`. Locals
+    /// whose chains are disjoint are never live at the same time. Invalid
+    /// and cyclic links end the chain.
+    pub fn scope_chain(&self, local: LocalIndex) -> Vec<LocalIndex> {
+        let arg_count = self.header.arg_count as usize;
+        let mut chain = vec![];
+        let mut current = Some(local.as_u32());
+        while let Some(index) = current {
+            let Some(l) = self.locals.get(index as usize + arg_count) else {
+                break;
+            };
+            let index = LocalIndex::from_u32(index);
+            if chain.contains(&index) {
+                break;
+            }
+            chain.push(index);
+            current = l.scope_next.checked_sub(1);
+        }
+        chain
+    }
+
+    /// Returns a hash of the decoded operators of the function, including
+    /// their immediates, which is equal for functions with identical bodies.
+    ///
+    /// The header, constant pool and debug information are not hashed. The
+    /// bytes from the first operator that fails to decode are hashed as is.
+    pub fn body_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let mut reader = self.operators_reader();
+        while !reader.done() {
+            let offset = reader.offset;
+            match Opcode::from_reader(&mut reader) {
+                Ok((_, op)) => op.hash(&mut hasher),
+                Err(_) => {
+                    reader.data()[offset..].hash(&mut hasher);
+                    break;
+                }
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Returns whether the function declares a rest parameter, e.g.,
+    /// `function f(a, ...rest) {}`, see [`Self::rest_param_start`].
+    pub fn has_rest_param(&self) -> bool {
+        self.rest_param_start().is_some()
+    }
+
+    /// Returns the index of the first argument gathered into the rest
+    /// parameter, if any.
+    ///
+    /// The rest parameter is counted in `arg_count` but not in
+    /// `defined_arg_count`, and is initialized by a `Rest` operator in the
+    /// function prologue, whose `first` immediate is the index of the first
+    /// excess argument. The operators are inspected up to the first
+    /// operator that fails to decode.
+    pub fn rest_param_start(&self) -> Option<u16> {
+        if self.header.defined_arg_count >= self.header.arg_count {
+            return None;
+        }
+        let mut reader = self.operators_reader();
+        while !reader.done() {
+            match Opcode::from_reader(&mut reader) {
+                Ok((_, Opcode::Rest { first })) => return Some(first),
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        None
+    }
+
+    /// Returns the number of values returned by the compiled function.
+    ///
+    /// Normal functions return their completion value. Generator and async
+    /// functions suspend at each `yield` or `await`, so they also return
+    /// whether they completed or suspended, for the runtime to resume them.
+    pub fn return_arity(&self) -> usize {
+        match self.header.kind() {
+            FunctionKind::Normal => 1,
+            FunctionKind::Generator | FunctionKind::Async | FunctionKind::AsyncGenerator => 2,
+        }
+    }
+
+    /// Returns the number of leading operators whose opcodes match the ones
+    /// of `other`, ignoring immediates.
+    pub fn common_prefix_len(&self, other: &FunctionTranslation) -> usize {
+        self.opcodes()
+            .zip(other.opcodes())
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    /// Returns the longest run of operators whose opcodes match a run of
+    /// operators of `other`, ignoring immediates. Positions are operator
+    /// indices, not byte offsets.
+    pub fn longest_common_run(&self, other: &FunctionTranslation) -> CommonRun {
+        let a = self.opcodes().collect::<Vec<_>>();
+        let b = other.opcodes().collect::<Vec<_>>();
+        longest_common_run(&a, &b)
+    }
+
+    /// Returns the discriminants of the function operators, up to the first
+    /// operator that fails to decode.
+    fn opcodes(&self) -> impl Iterator<Item = u8> + 'data {
+        let mut reader = self.operators_reader();
+        std::iter::from_fn(move || {
+            if reader.done() {
+                return None;
+            }
+            Opcode::from_reader(&mut reader)
+                .ok()
+                .map(|(_, op)| op.discriminant())
+        })
     }
 
     /// Resolves the atom index of a local.
@@ -121,21 +616,19 @@ impl<'data> FunctionTranslation<'data> {
 #[derive(Default, Clone, Debug)]
 pub struct ModuleTranslation<'data> {
     /// The module section header.
+    ///
+    /// Bytecode compiled without the module wrapper, e.g., with script
+    /// semantics, holds a bare function, and keeps the default header.
     pub header: ModuleSectionHeader,
     /// The functions defined in the module.
     ///
     /// When functions are inserted, a [`FuncIndex`] handle is provided to the
     /// caller, which will serve as an indentifier of the function.
     ///
-    /// References to function indices found in function bytecode are relative
-    /// to the constant pool of each function, however given that in the
-    /// translation layer there is not concept of constant pool, indices to
-    /// functions are absolute to the functions found in the module.
-    ///
-    /// Each [`FunctionTranslation`] is associated to a [`FuncIndex`] therefore
-    /// is an operator references a function index, such index can be resolved
-    /// at the module by doing:
-    ///     target function = current function index + operator index
+    /// References to functions found in function bytecode are relative to
+    /// the constant pool of each function; the constant pool entry holds the
+    /// absolute [`FuncIndex`] of the referenced function, see
+    /// [`FunctionTranslation::constant`].
     pub functions: Vec<FunctionTranslation<'data>>,
 }
 
@@ -152,6 +645,17 @@ impl<'data> ModuleTranslation<'data> {
 pub struct TranslationBuilder<'data> {
     pub translation: Translation<'data>,
     current_func: FuncIndex,
+    /// The functions whose constant pool is being parsed, along with the
+    /// number of pending entries, innermost last.
+    ///
+    /// Constant pool entries are serialized depth-first after the function
+    /// sections, so nested functions' entries precede the remaining entries
+    /// of their parent.
+    pools: Vec<(FuncIndex, u32)>,
+    /// The built-in atoms preceding the interned atoms.
+    builtin_atoms: AtomTable,
+    /// The id of the next object read, see [ConstValue::ObjectRef].
+    next_object: u32,
 }
 
 impl<'data> TranslationBuilder<'data> {
@@ -159,40 +663,170 @@ impl<'data> TranslationBuilder<'data> {
         Self {
             translation: Default::default(),
             current_func: FuncIndex::default(),
+            pools: Default::default(),
+            builtin_atoms: AtomTable::default(),
+            next_object: 0,
         }
     }
 
+    /// Configures the built-in atoms of the QuickJS build that produced the
+    /// bytecode. See [Parser::with_builtin_atoms].
+    pub fn with_builtin_atoms(mut self, table: AtomTable) -> Self {
+        self.builtin_atoms = table;
+        self
+    }
+
     /// Parses, validates and converts QuickJS bytecode to an in-memory
     /// representation of a JavaScript module.
     pub fn translate(mut self, buffer: &'data [u8]) -> Result<Translation<'data>> {
-        for payload in Parser::new().parse_buffer(buffer) {
-            match payload? {
-                Payload::Header(h) => self.translation.header = h,
-                Payload::Version(_) => {}
-                Payload::ModuleHeader(h) => self.translation.module.header = h,
-                Payload::FunctionHeader(fh) => {
-                    self.current_func = self.translation.module.push_func(fh);
-                }
-                Payload::FunctionLocals(locals) => {
-                    self.translation.module.functions[self.current_func.as_u32() as usize].locals =
-                        locals;
-                }
-                Payload::FunctionDebugInfo(di) => {
-                    self.translation.module.functions[self.current_func.as_u32() as usize].debug =
-                        Some(di);
+        let mut objects = 0;
+        let parser = Parser::new().with_builtin_atoms(self.builtin_atoms);
+        for payload in parser.parse_buffer(buffer) {
+            let payload = payload?;
+            if let Payload::Version(_) = payload {
+                objects += 1;
+                ensure!(
+                    objects == 1,
+                    "Found multiple bytecode objects, use `parse_all_objects` instead"
+                );
+            }
+            self.handle_payload(payload)?;
+        }
+
+        Ok(self.finish())
+    }
+
+    /// Parses a buffer containing one or more concatenated bytecode
+    /// objects, converting each object to a separate [Translation].
+    ///
+    /// A new object starts at each version byte found after a complete
+    /// object.
+    pub fn parse_all_objects(
+        buffer: &'data [u8],
+    ) -> impl Iterator<Item = Result<Translation<'data>>> {
+        let mut payloads = Parser::new().parse_buffer(buffer).peekable();
+        std::iter::from_fn(move || {
+            let mut builder = TranslationBuilder::new();
+            let mut started = false;
+            loop {
+                match payloads.peek() {
+                    None => return started.then(|| Ok(builder.finish())),
+                    Some(Ok(Payload::Version(_))) if started => return Some(Ok(builder.finish())),
+                    _ => {}
                 }
-                Payload::FunctionClosureVars(vars) => {
-                    self.translation.module.functions[self.current_func.as_u32() as usize]
-                        .closure_vars = vars;
+                if let Err(e) = payloads
+                    .next()?
+                    .and_then(|payload| builder.handle_payload(payload))
+                {
+                    return Some(Err(e));
                 }
-                Payload::FunctionOperators(reader) => {
-                    self.translation.module.functions[self.current_func.as_u32() as usize]
-                        .operators = reader;
+                started = true;
+            }
+        })
+    }
+
+    /// Incorporates a payload into the translation.
+    fn handle_payload(&mut self, payload: Payload<'data>) -> Result<()> {
+        match payload {
+            Payload::Header(h) => self.translation.header = h,
+            Payload::Version(_) => {}
+            Payload::ModuleHeader(h) => self.translation.module.header = h,
+            Payload::FunctionHeader(fh) => {
+                self.validate_func_header(&fh)?;
+                let constant_pool_size = fh.constant_pool_size;
+                self.current_func = self.translation.module.push_func(fh);
+                self.push_constant(ConstValue::Function(self.current_func));
+                self.pools.push((self.current_func, constant_pool_size));
+            }
+            Payload::Value(value) => {
+                let objects = value.object_count();
+                let location = if self.pending_pool().is_some() {
+                    self.push_constant(value)
+                } else {
+                    self.translation.value = Some(value);
+                    Some(ValueLocation::TopLevel)
+                };
+                if let (Some(location), true) = (location, objects > 0) {
+                    self.translation.objects.push((self.next_object, location));
                 }
-                Payload::End => {}
+                self.next_object += objects;
+            }
+            Payload::FunctionLocals(locals) => {
+                self.translation.module.functions[self.current_func.as_u32() as usize].locals =
+                    locals;
+            }
+            Payload::FunctionDebugInfo(di) => {
+                self.translation.module.functions[self.current_func.as_u32() as usize].debug =
+                    Some(di);
+            }
+            Payload::FunctionClosureVars(vars) => {
+                self.translation.module.functions[self.current_func.as_u32() as usize]
+                    .closure_vars = vars;
             }
+            Payload::FunctionOperators(reader) => {
+                self.translation.module.functions[self.current_func.as_u32() as usize].operators =
+                    reader;
+            }
+            Payload::End => {}
+        }
+
+        Ok(())
+    }
+
+    /// Validates the ordering invariants of a function header, which the
+    /// resolution of argument and local names depends on: defined arguments
+    /// are a prefix of the arguments, which precede the locals.
+    fn validate_func_header(&self, header: &FunctionSectionHeader) -> Result<()> {
+        let index = self.translation.module.functions.len();
+        let name = self
+            .translation
+            .header
+            .atoms
+            .get(header.name_index.as_u32() as usize)
+            .map(String::as_str)
+            .unwrap_or("<unknown>");
+        ensure!(
+            header.defined_arg_count <= header.arg_count,
+            "Invalid header for function {} (`{}`): defined argument count {} exceeds argument count {}",
+            index,
+            name,
+            header.defined_arg_count,
+            header.arg_count
+        );
+        ensure!(
+            header.arg_count <= header.local_count,
+            "Invalid header for function {} (`{}`): argument count {} exceeds local count {}",
+            index,
+            name,
+            header.arg_count,
+            header.local_count
+        );
+
+        Ok(())
+    }
+
+    /// Returns the constant pool being parsed, if any, along with the number
+    /// of pending entries.
+    fn pending_pool(&mut self) -> Option<&mut (FuncIndex, u32)> {
+        while let Some((_, 0)) = self.pools.last() {
+            self.pools.pop();
         }
+        self.pools.last_mut()
+    }
+
+    /// Adds an entry to the constant pool being parsed, if any, returning
+    /// its location.
+    fn push_constant(&mut self, value: ConstValue) -> Option<ValueLocation> {
+        let (func, pending) = self.pending_pool()?;
+        *pending -= 1;
+        let func = *func;
+        let constants = &mut self.translation.module.functions[func.as_u32() as usize].constants;
+        constants.push(value);
+        Some(ValueLocation::Constant(func, constants.len() - 1))
+    }
 
-        Ok(self.translation)
+    /// Finishes the translation.
+    fn finish(self) -> Translation<'data> {
+        self.translation
     }
 }
@@ -0,0 +1,41 @@
+//! Common opcode runs between functions.
+
+/// A run of opcodes shared by two sequences.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CommonRun {
+    /// The index of the first opcode of the run in the first sequence.
+    pub start: usize,
+    /// The index of the first opcode of the run in the second sequence.
+    pub other_start: usize,
+    /// The number of opcodes in the run.
+    pub len: usize,
+}
+
+/// Returns the longest contiguous run of opcode discriminants present in
+/// both `a` and `b`.
+///
+/// Ties are resolved in favor of the run that starts first in `a`, and
+/// then in `b`. Sequences without opcodes in common produce an empty run
+/// at the start of both.
+pub fn longest_common_run(a: &[u8], b: &[u8]) -> CommonRun {
+    let mut longest = CommonRun::default();
+    // The length of the common run ending at each position of `b`, for the
+    // previous position of `a`.
+    let mut previous = vec![0; b.len() + 1];
+    let mut current = vec![0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = if x == y { previous[j] + 1 } else { 0 };
+            let len = current[j + 1];
+            if len > longest.len {
+                longest = CommonRun {
+                    start: i + 1 - len,
+                    other_start: j + 1 - len,
+                    len,
+                };
+            }
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    longest
+}
@@ -0,0 +1,179 @@
+//! Operand stack validation.
+
+use crate::FunctionTranslation;
+use anyhow::{anyhow, bail, ensure, Result};
+use quickpars::Opcode;
+use std::collections::HashMap;
+
+/// The kind of a value in the operand stack.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Slot {
+    Value,
+    /// A catch offset, pushed by `Catch` and by the start of `for...of`
+    /// loops, and removed up to by `NipCatch`.
+    CatchOffset,
+}
+
+/// Replays the operators of a function through [Opcode::stack_effect],
+/// following every path, and validates that:
+///
+/// * The stack never underflows nor exceeds the function's stack size.
+/// * Every operator is reached with the same stack depth through all paths.
+/// * The stack is empty after returning.
+pub(crate) fn check_stack_balance(func: &FunctionTranslation) -> Result<()> {
+    let mut reader = func.operators_reader();
+    let mut operators = vec![];
+    while !reader.done() {
+        operators.push(Opcode::from_reader(&mut reader)?);
+    }
+    if operators.is_empty() {
+        return Ok(());
+    }
+    let indices: HashMap<u32, usize> = operators
+        .iter()
+        .enumerate()
+        .map(|(i, (pc, _))| (*pc, i))
+        .collect();
+    let index_of = |target: i64| {
+        u32::try_from(target)
+            .ok()
+            .and_then(|target| indices.get(&target).copied())
+            .ok_or_else(|| anyhow!("Branch target {} is not an operator", target))
+    };
+
+    // The stack depth at each visited operator.
+    let mut depths: HashMap<usize, usize> = HashMap::new();
+    let mut worklist = vec![(0, vec![])];
+    while let Some((mut i, mut stack)) = worklist.pop() {
+        loop {
+            let (pc, op) = operators
+                .get(i)
+                .ok_or_else(|| anyhow!("Execution falls through the end of the function"))?;
+            if let Some(depth) = depths.get(&i) {
+                ensure!(
+                    *depth == stack.len(),
+                    "Inconsistent stack depth at {}: {} and {}",
+                    pc,
+                    depth,
+                    stack.len()
+                );
+                break;
+            }
+            depths.insert(i, stack.len());
+
+            apply(*pc, op, &mut stack)?;
+            ensure!(
+                stack.len() <= func.header.stack_size as usize,
+                "Stack size exceeded at {}: {} > {}",
+                pc,
+                stack.len(),
+                func.header.stack_size
+            );
+
+            let pc = *pc as i64;
+            use Opcode::*;
+            match *op {
+                Return | ReturnUndef | ReturnAsync | TailCall { .. } | TailCallMethod { .. } => {
+                    ensure!(
+                        stack.is_empty(),
+                        "Expected an empty stack after returning at {}, found {} values",
+                        pc,
+                        stack.len()
+                    );
+                    break;
+                }
+                Throw | ThrowError { .. } | Ret => break,
+                GoTo { offset } => {
+                    i = index_of(pc + 1 + offset as i64)?;
+                    continue;
+                }
+                GoTo8 { offset } => {
+                    i = index_of(pc + 1 + offset as i64)?;
+                    continue;
+                }
+                GoTo16 { offset } => {
+                    i = index_of(pc + 1 + offset as i64)?;
+                    continue;
+                }
+                IfFalse { offset } | IfTrue { offset } => {
+                    worklist.push((index_of(pc + 1 + offset as i64)?, stack.clone()));
+                }
+                IfFalse8 { offset } | IfTrue8 { offset } => {
+                    worklist.push((index_of(pc + 1 + offset as i64)?, stack.clone()));
+                }
+                Catch { diff } => {
+                    worklist.push((index_of(pc + 1 + diff as i32 as i64)?, stack.clone()));
+                }
+                GoSub { diff } => {
+                    // The `finally` block is entered with the return address.
+                    let mut target = stack.clone();
+                    target.push(Slot::Value);
+                    worklist.push((index_of(pc + 1 + diff as i32 as i64)?, target));
+                }
+                WithGetVar { diff, .. } | WithDeleteVar { diff, .. } => {
+                    let mut target = stack.clone();
+                    target.push(Slot::Value);
+                    worklist.push((index_of(pc + 5 + diff as i32 as i64)?, target));
+                }
+                WithMakeRef { diff, .. }
+                | WithGetRef { diff, .. }
+                | WithGetRefUndef { diff, .. } => {
+                    let mut target = stack.clone();
+                    target.extend([Slot::Value, Slot::Value]);
+                    worklist.push((index_of(pc + 5 + diff as i32 as i64)?, target));
+                }
+                WithPutVar { diff, .. } => {
+                    let mut target = stack.clone();
+                    target.pop();
+                    worklist.push((index_of(pc + 5 + diff as i32 as i64)?, target));
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the stack effect of an operator.
+///
+/// The values in the stack that the operator pops and pushes back keep their
+/// kind.
+fn apply(pc: u32, op: &Opcode, stack: &mut Vec<Slot>) -> Result<()> {
+    if let Opcode::NipCatch = op {
+        // Removes every value up to, and including, the catch offset,
+        // keeping the top of the stack.
+        let top = stack
+            .pop()
+            .ok_or_else(|| anyhow!("Stack underflow at {}", pc))?;
+        loop {
+            match stack.pop() {
+                Some(Slot::CatchOffset) => break,
+                Some(Slot::Value) => {}
+                None => bail!("No catch offset in the stack at {}", pc),
+            }
+        }
+        stack.push(top);
+        return Ok(());
+    }
+
+    let (pop, push) = op.stack_effect();
+    let (pop, push) = (pop as usize, push as usize);
+    ensure!(
+        stack.len() >= pop,
+        "Stack underflow at {}: {} pops {}, found {} values",
+        pc,
+        Opcode::name_from_byte(op.discriminant()),
+        pop,
+        stack.len()
+    );
+    let base = stack.len() - pop;
+    stack.truncate(base + pop.min(push));
+    stack.resize(base + push, Slot::Value);
+
+    if let Opcode::Catch { .. } | Opcode::ForOfStart | Opcode::ForAwaitOfStart = op {
+        *stack.last_mut().unwrap() = Slot::CatchOffset;
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Bytecode verification.
+
+use crate::{FunctionTranslation, Translation};
+use anyhow::Result;
+use quickpars::{ConstantPoolIndex, FuncIndex, Opcode};
+use std::fmt;
+
+/// An inconsistency found by [verify] in a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    /// The index of the function.
+    pub func: FuncIndex,
+    /// The name of the function.
+    pub func_name: String,
+    /// The offset of the offending operator, if the error is tied to one.
+    pub offset: Option<u32>,
+    /// The description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.offset {
+            Some(offset) => write!(f, "{}@{}: {}", self.func_name, offset, self.message),
+            None => write!(f, "{}: {}", self.func_name, self.message),
+        }
+    }
+}
+
+/// Verifies every function of the translation, in module order, checking
+/// that:
+///
+/// * The operators decode up to the end of the bytecode.
+/// * Constant pool references are in bounds.
+/// * The operand stack is balanced, see
+///   [FunctionTranslation::check_stack_balance].
+///
+/// The stack balance of a function is only checked if its operators
+/// decode.
+pub fn verify(translation: &Translation) -> Vec<VerifyError> {
+    let mut errors = vec![];
+    for func in &translation.module.functions {
+        let func_name = translation
+            .try_resolve_func_name(func.index, None)
+            .unwrap_or("<unknown>");
+        let mut error = |offset, message| {
+            errors.push(VerifyError {
+                func: func.index,
+                func_name: func_name.to_string(),
+                offset,
+                message,
+            })
+        };
+        let mut decoded = true;
+        for (offset, result) in operators(func) {
+            let index = match result {
+                Ok(Opcode::PushConst { index })
+                | Ok(Opcode::FClosure { index })
+                | Ok(Opcode::FClosure8 { index }) => Some(index),
+                Ok(Opcode::PushConst8 { index }) => Some(ConstantPoolIndex::from_u32(index as u32)),
+                Ok(_) => None,
+                Err(e) => {
+                    error(Some(offset), format!("Invalid operator: {e}"));
+                    decoded = false;
+                    None
+                }
+            };
+            if let Some(index) = index {
+                if func.constant(index).is_none() {
+                    error(
+                        Some(offset),
+                        format!(
+                            "Constant pool index {} out of bounds, the pool has {} entries",
+                            index.as_u32(),
+                            func.constants().len()
+                        ),
+                    );
+                }
+            }
+        }
+        if decoded {
+            if let Err(e) = func.check_stack_balance() {
+                error(None, e.to_string());
+            }
+        }
+    }
+    errors
+}
+
+/// Decodes the operators of a function, up to the first operator that
+/// fails to decode, along with their offsets.
+fn operators<'data>(
+    func: &FunctionTranslation<'data>,
+) -> impl Iterator<Item = (u32, Result<Opcode>)> + 'data {
+    let mut reader = func.operators_reader();
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed || reader.done() {
+            return None;
+        }
+        let offset = reader.offset as u32;
+        match Opcode::from_reader(&mut reader) {
+            Ok((_, op)) => Some((offset, Ok(op))),
+            Err(e) => {
+                failed = true;
+                Some((offset, Err(e)))
+            }
+        }
+    })
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FunctionLocal, FunctionSectionHeader, Opcode, DEBUG_FLAG},
+    TranslationBuilder,
+};
+
+/// The atoms interned by the generated modules.
+const ATOMS: [&str; 3] = ["f", "a", "b"];
+
+/// An arbitrary function, made encodable: it has no debug information,
+/// closure variables or constants, and its atoms are interned.
+#[derive(Debug)]
+struct Function {
+    header: FunctionSectionHeader,
+    locals: Vec<FunctionLocal>,
+    ops: Vec<Opcode>,
+}
+
+impl<'a> Arbitrary<'a> for Function {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut header = FunctionSectionHeader::arbitrary(u)?;
+        // Keep the number of locals small, preserving the consistency of
+        // the counts.
+        header.local_count %= 8;
+        header.arg_count = header.arg_count.min(header.local_count);
+        header.defined_arg_count = header.defined_arg_count.min(header.arg_count);
+        header.var_count = header.local_count - header.arg_count;
+        header.flags &= !DEBUG_FLAG;
+        header.name_index = interned_atom(0);
+        header.closure_var_count = 0;
+        header.constant_pool_size = 0;
+
+        let mut locals = vec![];
+        for _ in 0..header.local_count {
+            let mut local = FunctionLocal::arbitrary(u)?;
+            let atom = u.choose_index(ATOMS.len())?;
+            local.name_index = interned_atom(atom as u32);
+            locals.push(local);
+        }
+
+        let mut ops = vec![];
+        for _ in 0..u.int_in_range(0..=16)? {
+            // Skip the candidates that don't decode.
+            if let Ok(op) = Opcode::arbitrary(u) {
+                ops.push(op);
+            }
+        }
+        header.bytecode_len = encoded(&ops).len() as u32;
+
+        Ok(Self {
+            header,
+            locals,
+            ops,
+        })
+    }
+}
+
+/// Encodes the operators.
+fn encoded(ops: &[Opcode]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for op in ops {
+        op.encode(&mut bytes);
+    }
+    bytes
+}
+
+/// Returns `len` pseudo-random bytes generated from the seed.
+fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    (0..len)
+        .map(|_| {
+            // xorshift64.
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn arbitrary_functions_round_trip() -> anyhow::Result<()> {
+    for seed in 0..256 {
+        let data = random_bytes(seed, 1024);
+        let Ok(function) = Function::arbitrary(&mut Unstructured::new(&data)) else {
+            continue;
+        };
+        let bytecode = encode_bytecode(
+            &ATOMS,
+            &encode_function(
+                &function.header,
+                &function.locals,
+                &[],
+                &encoded(&function.ops),
+            ),
+        );
+
+        let translation = TranslationBuilder::new().translate(&bytecode)?;
+        let [func] = &translation.module.functions[..] else {
+            panic!("expected a single function, seed {seed}");
+        };
+        assert_eq!(
+            format!("{:?}", func.header),
+            format!("{:?}", function.header),
+            "seed {seed}"
+        );
+        assert_eq!(
+            format!("{:?}", func.locals),
+            format!("{:?}", function.locals),
+            "seed {seed}"
+        );
+        let mut reader = func.operators_reader();
+        let mut ops = vec![];
+        while !reader.done() {
+            ops.push(Opcode::from_reader(&mut reader)?.1);
+        }
+        assert_eq!(ops, function.ops, "seed {seed}");
+        assert_eq!(translation.resolve_func_name(func.index, None), "f");
+    }
+    Ok(())
+}
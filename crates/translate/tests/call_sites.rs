@@ -0,0 +1,52 @@
+use anyhow::{bail, Result};
+use jac_testutil::compile_fixture;
+use jac_translate::{
+    quickpars::{FuncIndex, Opcode},
+    Translation, TranslationBuilder,
+};
+
+/// Returns the operator at the given offset of a function.
+fn operator_at(translation: &Translation, func: FuncIndex, pc: u32) -> Result<Opcode> {
+    let mut reader = translation.module.functions[func.as_u32() as usize].operators_reader();
+    while !reader.done() {
+        let (offset, op) = Opcode::from_reader(&mut reader)?;
+        if offset == pc {
+            return Ok(op);
+        }
+    }
+    bail!("No operator at offset {pc}")
+}
+
+#[test]
+fn closures_are_call_sites() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function outer() {\n\
+           function helper() { return 1; }\n\
+           return [helper(), helper];\n\
+         }\n\
+         function other() { return () => 2; }\n\
+         outer(); other();",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let index = |name: &str| translation.function_index_by_name(name).unwrap();
+    // The top-level function comes first, and is instantiated by the
+    // runtime.
+    let top_level = translation.module.functions[0].index;
+    assert!(translation.call_sites(top_level).is_empty());
+
+    for (target, caller) in [
+        (index("helper"), index("outer")),
+        (index("outer"), top_level),
+        (index("other"), top_level),
+    ] {
+        let sites = translation.call_sites(target);
+        assert_eq!(sites.len(), 1, "unexpected call sites: {sites:?}");
+        let (func, pc) = sites[0];
+        assert_eq!(func, caller);
+        assert!(matches!(
+            operator_at(&translation, func, pc)?,
+            Opcode::FClosure { .. } | Opcode::FClosure8 { .. }
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::{
+    quickpars::{BinaryReader, Opcode},
+    TranslationBuilder,
+};
+
+fn opcodes(mut reader: BinaryReader) -> Result<Vec<u8>> {
+    let mut opcodes = vec![];
+    while !reader.done() {
+        opcodes.push(Opcode::from_reader(&mut reader)?.1.discriminant());
+    }
+    Ok(opcodes)
+}
+
+#[test]
+fn shared_prologue() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function double(x) {\n\
+           let y = x + 1;\n\
+           return y * 2;\n\
+         }\n\
+         function describe(x) {\n\
+           let y = x + 1;\n\
+           return `${y}`;\n\
+         }\n\
+         console.log(double(1), describe(2));",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let double = translation.function_by_name("double").unwrap();
+    let describe = translation.function_by_name("describe").unwrap();
+    let double_ops = opcodes(double.operators_reader())?;
+    let describe_ops = opcodes(describe.operators_reader())?;
+
+    // Both functions compute `x + 1` and store it in `y` before diverging.
+    let prefix = double.common_prefix_len(describe);
+    assert!(prefix >= 4);
+    assert_eq!(prefix, describe.common_prefix_len(double));
+    assert_eq!(double_ops[..prefix], describe_ops[..prefix]);
+    assert_ne!(double_ops[prefix], describe_ops[prefix]);
+
+    let run = double.longest_common_run(describe);
+    assert!(run.len >= prefix);
+    assert_eq!(
+        double_ops[run.start..run.start + run.len],
+        describe_ops[run.other_start..run.other_start + run.len]
+    );
+
+    assert_eq!(double.common_prefix_len(double), double_ops.len());
+    let run = double.longest_common_run(double);
+    assert_eq!(
+        (run.start, run.other_start, run.len),
+        (0, 0, double_ops.len())
+    );
+    Ok(())
+}
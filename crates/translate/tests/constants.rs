@@ -0,0 +1,124 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{ConstValue, ConstantPoolIndex, FunctionSectionHeader, Opcode, Tag},
+    TranslationBuilder,
+};
+
+#[test]
+fn constants_are_looked_up_by_pool_index() -> Result<()> {
+    let mut operators = vec![];
+    Opcode::PushConst {
+        index: ConstantPoolIndex::from_u32(1),
+    }
+    .encode(&mut operators);
+    Opcode::Return.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_constant_pool_size(3)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let mut sections = encode_function(&header, &[], &[], &operators);
+    // The constant pool follows the function: `7`, `1.5` and `true`.
+    sections.extend([Tag::I32 as u8, 7]);
+    sections.push(Tag::F64 as u8);
+    sections.extend(1.5f64.to_le_bytes());
+    sections.push(Tag::True as u8);
+    let bytecode = encode_bytecode(&["f"], &sections);
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = &translation.module.functions[0];
+    let expected = [
+        ConstValue::I32(7),
+        ConstValue::F64(1.5),
+        ConstValue::Bool(true),
+    ];
+    assert_eq!(func.constants(), expected);
+    for (i, value) in expected.iter().enumerate() {
+        let index = ConstantPoolIndex::from_u32(i as u32);
+        assert_eq!(func.constant(index), Some(value));
+    }
+    assert_eq!(func.constant(ConstantPoolIndex::from_u32(3)), None);
+    Ok(())
+}
+
+#[test]
+fn bare_top_level_numbers_are_kept() -> Result<()> {
+    let mut sections = vec![Tag::F64 as u8];
+    sections.extend(1.5f64.to_le_bytes());
+    let bytecode = encode_bytecode(&[], &sections);
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert_eq!(translation.value, Some(ConstValue::F64(1.5)));
+    assert!(translation.module.functions.is_empty());
+    Ok(())
+}
+
+/// Appends an 8-bit string value.
+fn push_string(bytes: &mut Vec<u8>, s: &str) {
+    bytes.push(Tag::String as u8);
+    bytes.push((s.len() as u8) << 1);
+    bytes.extend(s.as_bytes());
+}
+
+#[test]
+fn template_objects_keep_cooked_and_raw_strings() -> Result<()> {
+    let mut operators = vec![];
+    Opcode::ReturnUndef.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_constant_pool_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let mut sections = encode_function(&header, &[], &[], &operators);
+    // The strings of tag`a\n${x}\unicode`, where the invalid escape leaves
+    // the second cooked string `undefined`.
+    sections.extend([Tag::TemplateObject as u8, 2]);
+    push_string(&mut sections, "a\n");
+    sections.push(Tag::Undefined as u8);
+    sections.extend([Tag::Array as u8, 2]);
+    push_string(&mut sections, "a\\n");
+    push_string(&mut sections, "\\unicode");
+    let bytecode = encode_bytecode(&["f"], &sections);
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert_eq!(
+        translation.module.functions[0].constants(),
+        [ConstValue::TemplateObject {
+            cooked: vec![Some("a\n".into()), None],
+            raw: vec!["a\\n".into(), "\\unicode".into()],
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn object_refs_resolve_across_constants() -> Result<()> {
+    let mut operators = vec![];
+    Opcode::ReturnUndef.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_constant_pool_size(2)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let mut sections = encode_function(&header, &[], &[], &operators);
+    // `{ f: [1] }`, numbered 0 and 1, followed by `[<ref 1>]`, numbered 2,
+    // which refers to the array of the first constant.
+    let key = (interned_atom(0).as_u32() << 1) as u16;
+    sections.extend([Tag::Object as u8, 1, (key as u8) | 0x80, (key >> 7) as u8]);
+    sections.extend([Tag::Array as u8, 1, Tag::I32 as u8, 1]);
+    sections.extend([Tag::Array as u8, 1, Tag::ObjectRef as u8, 1]);
+    let bytecode = encode_bytecode(&["f"], &sections);
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let constants = translation.module.functions[0].constants();
+    let ConstValue::Object(props) = &constants[0] else {
+        panic!("expected an object, found {}", constants[0]);
+    };
+    assert_eq!(translation.resolve_object_ref(0), Some(&constants[0]));
+    assert_eq!(translation.resolve_object_ref(1), Some(&props[0].1));
+    assert_eq!(translation.resolve_object_ref(2), Some(&constants[1]));
+    assert_eq!(translation.resolve_object_ref(3), None);
+    Ok(())
+}
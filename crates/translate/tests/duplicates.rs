@@ -0,0 +1,24 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::TranslationBuilder;
+
+#[test]
+fn identical_functions_are_grouped() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function inc(x) { return x + 1; }\nfunction dec(x) { return x - 1; }\n\nfunction next(x) { return x + 1; }\nconsole.log(inc(1), dec(1), next(1));",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let index = |name| translation.function_index_by_name(name).unwrap();
+
+    let inc = translation.function_by_name("inc").unwrap();
+    let next = translation.function_by_name("next").unwrap();
+    let dec = translation.function_by_name("dec").unwrap();
+    assert_eq!(inc.body_fingerprint(), next.body_fingerprint());
+    assert_ne!(inc.body_fingerprint(), dec.body_fingerprint());
+
+    assert_eq!(
+        translation.duplicate_functions(),
+        vec![vec![index("inc"), index("next")]]
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FunctionLocal, FunctionSectionHeader, Opcode},
+    TranslationBuilder,
+};
+
+/// Translates a function named `f` with a single local, returning
+/// `undefined`, under the given argument counts, returning the error.
+fn translate_error(arg_count: u32, defined_arg_count: u32) -> String {
+    let mut operators = vec![];
+    Opcode::ReturnUndef.encode(&mut operators);
+    // Built by hand, since the builder rejects invalid counts.
+    let header = FunctionSectionHeader {
+        name_index: interned_atom(0),
+        arg_count,
+        defined_arg_count,
+        local_count: 1,
+        bytecode_len: operators.len() as u32,
+        ..Default::default()
+    };
+    let local = FunctionLocal {
+        name_index: interned_atom(1),
+        ..Default::default()
+    };
+    let bytecode = encode_bytecode(
+        &["f", "a"],
+        &encode_function(&header, &[local], &[], &operators),
+    );
+    TranslationBuilder::new()
+        .translate(&bytecode)
+        .expect_err("the header to be rejected")
+        .to_string()
+}
+
+#[test]
+fn defined_arguments_exceeding_arguments_are_rejected() {
+    assert_eq!(
+        translate_error(1, 3),
+        "Invalid header for function 0 (`f`): defined argument count 3 exceeds argument count 1"
+    );
+}
+
+#[test]
+fn arguments_exceeding_locals_are_rejected() {
+    assert_eq!(
+        translate_error(2, 2),
+        "Invalid header for function 0 (`f`): argument count 2 exceeds local count 1"
+    );
+}
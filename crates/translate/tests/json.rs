@@ -0,0 +1,53 @@
+#![cfg(feature = "serde")]
+
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{ConstantPoolIndex, FunctionLocal, FunctionSectionHeader, Opcode, Tag},
+    TranslationBuilder, TranslationJson,
+};
+
+#[test]
+fn json_deserializes_back_to_the_translation() -> Result<()> {
+    let mut operators = vec![];
+    Opcode::PushConst {
+        index: ConstantPoolIndex::from_u32(0),
+    }
+    .encode(&mut operators);
+    Opcode::Return.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_args(1, 1)
+        .with_stack_size(1)
+        .with_constant_pool_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let local = FunctionLocal {
+        name_index: interned_atom(1),
+        ..Default::default()
+    };
+    let mut sections = encode_function(&header, &[local], &[], &operators);
+    sections.extend([Tag::I32 as u8, 7]);
+    let bytecode = encode_bytecode(&["f", "a"], &sections);
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let json: TranslationJson = serde_json::from_str(&translation.to_json()?)?;
+    assert_eq!(json, translation.to_json_repr()?);
+
+    assert_eq!(json.atoms, translation.header.atoms);
+    let [func] = &json.functions[..] else {
+        panic!("expected a single function");
+    };
+    assert_eq!(func.index, 0);
+    assert_eq!(func.name, "f");
+    assert_eq!(func.header.arg_count, 1);
+    assert_eq!(func.header.bytecode_len, operators.len() as u32);
+    assert_eq!(func.locals.len(), 1);
+    assert_eq!(func.locals[0].name, "a");
+    assert!(func.closure_vars.is_empty());
+    let pcs = func.operators.iter().map(|op| op.pc).collect::<Vec<_>>();
+    assert_eq!(pcs[0], 0);
+    assert_eq!(pcs.len(), 2);
+    assert_eq!(func.constants, ["7"]);
+    Ok(())
+}
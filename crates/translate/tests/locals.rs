@@ -0,0 +1,32 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::{quickpars::LocalIndex, TranslationBuilder};
+
+#[test]
+fn sibling_block_scopes_have_disjoint_chains() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function blocks(g) {\n\
+           { let a = 1; g(a); }\n\
+           { let b = 2; g(b); }\n\
+         }\n\
+         blocks(console.log);",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = translation
+        .function_by_name("blocks")
+        .expect("`blocks` to be defined");
+    let local = |name: &str| -> LocalIndex {
+        let var_count = func.locals.len() - func.header.arg_count as usize;
+        (0..var_count as u32)
+            .map(LocalIndex::from_u32)
+            .find(|l| translation.resolve_func_local_name(func.index, *l) == name)
+            .expect("local to be defined")
+    };
+
+    let a = func.scope_chain(local("a"));
+    let b = func.scope_chain(local("b"));
+    assert_eq!(a.first(), Some(&local("a")));
+    assert_eq!(b.first(), Some(&local("b")));
+    assert!(a.iter().all(|l| !b.contains(l)));
+    Ok(())
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "serde")]
+
+use jac_translate::{
+    quickpars::{ModuleExportEntry, ModuleImportEntry},
+    Translation,
+};
+
+#[test]
+fn module_graph_resolves_atoms_and_modules() {
+    let mut translation = Translation::default();
+    translation.header.atoms = ["main.js", "dep.js", "foo", "bar", "reexported.js"]
+        .map(String::from)
+        .to_vec();
+    let header = &mut translation.module.header;
+    header.name_index = 0;
+    header.req_modules = vec![1, 4];
+    header.imports.push(ModuleImportEntry {
+        var_idx: 0,
+        name_idx: 2,
+        req_module_idx: 0,
+    });
+    header.exports.push(ModuleExportEntry::Local {
+        var_idx: 1,
+        export_name_idx: 3,
+    });
+    header.star_exports.push(1);
+
+    let json = translation.module_graph_json().unwrap();
+    let graph: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(graph["name"], "main.js");
+    assert_eq!(
+        graph["req_modules"],
+        serde_json::json!(["dep.js", "reexported.js"])
+    );
+    assert_eq!(graph["imports"][0]["name"], "foo");
+    assert_eq!(graph["imports"][0]["module"], "dep.js");
+    assert_eq!(graph["exports"][0]["name"], "bar");
+    assert_eq!(graph["exports"][0]["var_index"], 1);
+    assert_eq!(graph["star_exports"], serde_json::json!(["reexported.js"]));
+}
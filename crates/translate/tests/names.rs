@@ -0,0 +1,55 @@
+use anyhow::Result;
+use jac_testutil::{compile_fixture, translation_from_ops};
+use jac_translate::{
+    quickpars::{AtomIndex, FunctionSectionHeader, Opcode},
+    TranslationBuilder,
+};
+
+#[test]
+fn same_named_nested_functions() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function first() {\n\
+           function helper() { return 1; }\n\
+           return helper();\n\
+         }\n\
+         function second() {\n\
+           function helper() { return 2; }\n\
+           return helper();\n\
+         }\n\
+         first(); second();",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+
+    let helpers = translation.functions_by_name("helper");
+    assert_eq!(helpers.len(), 2);
+    assert!(helpers[0].index.as_u32() < helpers[1].index.as_u32());
+    assert_eq!(
+        translation.function_index_by_name("helper"),
+        Some(helpers[0].index)
+    );
+    assert_eq!(
+        translation.function_by_name("helper").unwrap().index,
+        helpers[0].index
+    );
+    // Each `helper` is a closure of a different function.
+    for (helper, parent) in helpers.iter().zip(["first", "second"]) {
+        let parent = translation.function_index_by_name(parent).unwrap();
+        assert_eq!(translation.call_sites(helper.index)[0].0, parent);
+    }
+    assert!(translation.function_by_name("missing").is_none());
+    Ok(())
+}
+
+#[test]
+fn lookups_see_functions_added_later() {
+    let mut translation = translation_from_ops(&[Opcode::Undefined, Opcode::Return]);
+    assert_eq!(translation.functions_by_name("f").len(), 1);
+
+    let index = translation.module.push_func(FunctionSectionHeader {
+        name_index: AtomIndex::from_u32(0),
+        ..Default::default()
+    });
+    let functions = translation.functions_by_name("f");
+    assert_eq!(functions.len(), 2);
+    assert_eq!(functions[1].index, index);
+}
@@ -0,0 +1,42 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FuncIndex, FunctionSectionHeader, Opcode},
+    TranslationBuilder,
+};
+
+/// Builds a bytecode object with a single function with the given name,
+/// returning `undefined`.
+fn object(name: &str) -> Result<Vec<u8>> {
+    let mut operators = vec![];
+    Opcode::ReturnUndef.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    Ok(encode_bytecode(
+        &[name],
+        &encode_function(&header, &[], &[], &operators),
+    ))
+}
+
+#[test]
+fn concatenated_objects_are_translated_separately() -> Result<()> {
+    let mut bytecode = object("first")?;
+    bytecode.extend(object("second")?);
+
+    let translations =
+        TranslationBuilder::parse_all_objects(&bytecode).collect::<Result<Vec<_>>>()?;
+    let names = translations
+        .iter()
+        .map(|t| {
+            assert_eq!(t.module.functions.len(), 1);
+            t.resolve_func_name(FuncIndex::from_u32(0), None)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["first", "second"]);
+
+    // A single translation only accepts a single object.
+    assert!(TranslationBuilder::new().translate(&bytecode).is_err());
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::TranslationBuilder;
+
+#[test]
+fn operator_bytes_span_the_function_bytecode() -> Result<()> {
+    let bytecode = compile_fixture("function add(a, b) { return a + b; }\nadd(1, 2);");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+
+    for func in &translation.module.functions {
+        assert_eq!(
+            func.operator_bytes().len(),
+            func.header.bytecode_len as usize
+        );
+    }
+    let add = translation.function_by_name("add").unwrap();
+    assert_eq!(add.operator_bytes(), add.operators_reader().data());
+    Ok(())
+}
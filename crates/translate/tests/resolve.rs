@@ -0,0 +1,25 @@
+use jac_translate::{
+    quickpars::{ConstantPoolIndex, FuncIndex, FunctionSectionHeader},
+    Translation,
+};
+
+#[test]
+fn out_of_range_indices_are_errors() {
+    let mut translation = Translation::default();
+    let index = translation
+        .module
+        .push_func(FunctionSectionHeader::default());
+
+    let err = translation
+        .try_resolve_func_name(index, Some(ConstantPoolIndex::from_u32(3)))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Constant pool index 3 out of bounds"));
+
+    assert!(translation
+        .try_resolve_func_name(FuncIndex::from_u32(7), None)
+        .is_err());
+    // The default header has no valid name atom.
+    assert!(translation.try_resolve_func_name(index, None).is_err());
+}
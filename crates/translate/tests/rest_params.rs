@@ -0,0 +1,21 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::TranslationBuilder;
+
+#[test]
+fn rest_params_are_detected() -> Result<()> {
+    let bytecode =
+        compile_fixture("function f(a, ...rest) {}\nfunction g(a, b) {}\nf(1, 2, 3); g(1, 2);");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+
+    let f = translation.function_by_name("f").unwrap();
+    assert_eq!(f.header.arg_count, 2);
+    assert_eq!(f.header.defined_arg_count, 1);
+    assert!(f.has_rest_param());
+    assert_eq!(f.rest_param_start(), Some(1));
+
+    let g = translation.function_by_name("g").unwrap();
+    assert!(!g.has_rest_param());
+    assert_eq!(g.rest_param_start(), None);
+    Ok(())
+}
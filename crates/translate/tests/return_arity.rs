@@ -0,0 +1,25 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::{quickpars::FunctionKind, TranslationBuilder};
+
+#[test]
+fn return_arity_depends_on_function_kind() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function normal() { return 1; }\n\
+         const arrow = () => 2;\n\
+         function* generator() { yield 3; }\n\
+         async function asynchronous() { return 4; }\n\
+         normal(); arrow(); generator(); asynchronous();",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = |name: &str| translation.function_by_name(name).unwrap();
+
+    for name in ["normal", "arrow"] {
+        assert_eq!(func(name).header.kind(), FunctionKind::Normal);
+        assert_eq!(func(name).return_arity(), 1);
+    }
+    assert_eq!(func("generator").header.kind(), FunctionKind::Generator);
+    assert_eq!(func("asynchronous").header.kind(), FunctionKind::Async);
+    assert_eq!(func("generator").return_arity(), 2);
+    Ok(())
+}
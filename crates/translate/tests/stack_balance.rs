@@ -0,0 +1,61 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FunctionSectionHeader, Opcode},
+    TranslationBuilder,
+};
+
+/// Returns the operators of `return cond ? 1 : 2`, where `cond` is `true`,
+/// along with the position of the immediate of the conditional branch.
+fn conditional() -> (Vec<u8>, usize) {
+    let ops = [
+        // 0
+        Opcode::PushTrue,
+        // 1: jump to the `else` branch, at 10.
+        Opcode::IfFalse8 { offset: 8 },
+        // 3
+        Opcode::PushI32 { value: 1 },
+        // 8: jump to the join point, at 15.
+        Opcode::GoTo8 { offset: 6 },
+        // 10
+        Opcode::PushI32 { value: 2 },
+        // 15
+        Opcode::Return,
+    ];
+    let mut operators = vec![];
+    for op in ops {
+        op.encode(&mut operators);
+    }
+    (operators, 2)
+}
+
+/// Checks the stack balance of a function made of the given operators.
+fn check(operators: &[u8]) -> Result<()> {
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let bytecode = encode_bytecode(&["f"], &encode_function(&header, &[], &[], operators));
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    translation.module.functions[0].check_stack_balance()
+}
+
+#[test]
+fn branches_are_balanced() -> Result<()> {
+    let (operators, _) = conditional();
+    check(&operators)
+}
+
+#[test]
+fn corrupted_branch_offset_is_detected() {
+    let (mut operators, immediate) = conditional();
+    // Jump into the immediate of the first `PushI32` instead.
+    operators[immediate] = 3;
+    let err = check(&operators).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Branch target 5 is not an operator"),
+        "unexpected error: {err}"
+    );
+}
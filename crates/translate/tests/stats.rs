@@ -0,0 +1,32 @@
+use anyhow::Result;
+use jac_testutil::named_fixture;
+use jac_translate::{quickpars::ATOM_NAMES, TranslationBuilder};
+
+#[test]
+fn closure_stats() -> Result<()> {
+    let bytecode = named_fixture("closure");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let stats = translation.stats();
+
+    // The top-level function, `counter` and `increment`.
+    assert_eq!(stats.function_count, 3);
+    assert_eq!(
+        stats.total_operator_bytes,
+        translation
+            .module
+            .functions
+            .iter()
+            .map(|f| f.operators.data().len())
+            .sum::<usize>()
+    );
+    assert!(stats.total_operator_bytes > 0);
+    assert_eq!(
+        stats.atom_count,
+        ATOM_NAMES.len() + stats.interned_atom_count
+    );
+    for name in ["counter", "count", "increment", "next"] {
+        assert!(translation.header.atoms.iter().any(|a| a == name));
+    }
+    assert!(stats.has_debug);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FunctionLocal, FunctionSectionHeader, Opcode, Parser},
+    TranslationBuilder,
+};
+
+/// Encodes a function named after the given interned atom, with a single
+/// argument, returning `undefined`.
+fn function(name: u32) -> Result<Vec<u8>> {
+    let mut operators = vec![];
+    Opcode::ReturnUndef.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(name))
+        .with_args(1, 1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let local = FunctionLocal {
+        name_index: interned_atom(2),
+        ..Default::default()
+    };
+    Ok(encode_function(&header, &[local], &[], &operators))
+}
+
+#[test]
+fn table_of_contents_matches_the_translation() -> Result<()> {
+    let mut sections = function(0)?;
+    sections.extend(function(1)?);
+    let bytecode = encode_bytecode(&["f", "g", "a"], &sections);
+
+    let toc = Parser::table_of_contents(&bytecode)?;
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let functions = &translation.module.functions;
+    assert_eq!(toc.len(), functions.len());
+    for (entry, func) in toc.iter().zip(functions) {
+        assert_eq!(entry.index, func.index);
+        assert_eq!(entry.name, translation.resolve_func_name(func.index, None));
+        assert_eq!(entry.arg_count, func.header.arg_count);
+        assert_eq!(entry.local_count, func.header.local_count);
+        assert_eq!(entry.bytecode_len, func.header.bytecode_len);
+    }
+    let names = toc.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["f", "g"]);
+    // The second function starts where the first one ends.
+    assert_eq!(toc[1].byte_offset - toc[0].byte_offset, sections.len() / 2);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{ConstantPoolIndex, FunctionLocal, FunctionSectionHeader, Opcode, Tag},
+    TranslationBuilder,
+};
+
+#[test]
+fn unreferenced_atoms_are_reported() -> Result<()> {
+    let mut operators = vec![];
+    Opcode::GetVar {
+        atom: interned_atom(2),
+    }
+    .encode(&mut operators);
+    Opcode::Return.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_var_count(1)
+        .with_stack_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let local = FunctionLocal {
+        name_index: interned_atom(1),
+        ..Default::default()
+    };
+    // Only `unused` isn't referenced, by either the function name, its
+    // local or its operators.
+    let bytecode = encode_bytecode(
+        &["f", "local", "global", "unused"],
+        &encode_function(&header, &[local], &[], &operators),
+    );
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert_eq!(translation.unused_atoms()?, [(interned_atom(3), "unused")]);
+    Ok(())
+}
+
+#[test]
+fn constant_object_keys_are_referenced() -> Result<()> {
+    let mut operators = vec![];
+    Opcode::PushConst {
+        index: ConstantPoolIndex::from_u32(0),
+    }
+    .encode(&mut operators);
+    Opcode::Return.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_constant_pool_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let mut sections = encode_function(&header, &[], &[], &operators);
+    // The constant `{a: 1}` of `const c = {a: 1}`, whose key is only
+    // referenced by the constant.
+    let key = interned_atom(1).as_u32() << 1;
+    sections.extend([Tag::Object as u8, 1, (key as u8) | 0x80, (key >> 7) as u8]);
+    sections.extend([Tag::I32 as u8, 1]);
+    let bytecode = encode_bytecode(&["f", "a", "unused"], &sections);
+
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert_eq!(translation.unused_atoms()?, [(interned_atom(2), "unused")]);
+    Ok(())
+}
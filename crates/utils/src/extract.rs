@@ -0,0 +1,21 @@
+//! Bytecode extraction from Wasm modules and components.
+
+use anyhow::{anyhow, Result};
+use wasmparser::{Parser, Payload};
+
+/// The name of the custom section that holds the bytecode, by default.
+pub const DEFAULT_SECTION: &str = "bytecode";
+
+/// Returns the contents of the first custom section with the given name,
+/// including the ones in nested modules and components.
+pub fn extract_bytecode<'a>(wasm: &'a [u8], section: &str) -> Result<&'a [u8]> {
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::CustomSection(reader) = payload? {
+            if reader.name() == section {
+                return Ok(reader.data());
+            }
+        }
+    }
+
+    Err(anyhow!("Custom section `{}` not found", section))
+}
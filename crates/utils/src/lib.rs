@@ -0,0 +1,7 @@
+//! Utilities for the development of JAC.
+
+pub mod extract;
+pub mod printer;
+
+pub use extract::extract_bytecode;
+pub use printer::{disassemble, disassemble_to, disassemble_with_source};
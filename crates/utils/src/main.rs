@@ -1,13 +1,16 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{ensure, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use jac_translate::{quickpars::AtomTable, verify, TranslationBuilder};
+use jac_utils::{extract, printer};
 use javy::{Config, Runtime};
-use parsetrace::trace;
+use parsetrace::{
+    chrome_trace, opcode_stats, opcode_stats_to_markdown, summary_with, to_csv, to_markdown,
+    trace_json_lines, trace_with, ReportOptions, ReportOrder,
+};
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
 
-mod printer;
-
 #[derive(Debug, Parser)]
 #[command(
     name = "jac-utils",
@@ -25,6 +28,10 @@ pub enum Command {
     Trace(TraceOptions),
     #[command(arg_required_else_help = true)]
     Print(PrintOptions),
+    #[command(arg_required_else_help = true)]
+    Extract(ExtractOptions),
+    #[command(arg_required_else_help = true)]
+    Validate(ValidateOptions),
 }
 
 #[derive(Debug, Parser)]
@@ -44,6 +51,79 @@ pub struct TraceOptions {
     /// Defaults to `trace_out.txt`
     #[arg(short = 'o', required = false, default_value = "trace_out.txt")]
     pub out: PathBuf,
+
+    /// The format of the report.
+    #[arg(long, value_enum, default_value_t = TraceFormat::Text)]
+    pub format: TraceFormat,
+
+    /// Prefix each line of the text report with the total fuel consumed up
+    /// to the event.
+    #[arg(long)]
+    pub cumulative: bool,
+
+    /// Prefix each opcode line of the text report with the opcode offset
+    /// recorded in the trace.
+    #[arg(long)]
+    pub offsets: bool,
+
+    /// Name intrinsic functions that executed identical opcodes alike, and
+    /// aggregate them as a single function in the summary.
+    #[arg(long)]
+    pub merge_intrinsics: bool,
+
+    /// The order of the lines of the text report.
+    #[arg(long, value_enum, default_value_t = SortBy::Time)]
+    pub sort_by: SortBy,
+
+    /// Suffix each opcode line of the text report with its share of the
+    /// fuel of all the opcodes of its function.
+    #[arg(long)]
+    pub pct_per_line: bool,
+
+    /// Report the count, total, average and maximum fuel of each opcode
+    /// kind, as a Markdown table, instead of the trace.
+    #[arg(long, conflicts_with_all = ["format", "cumulative", "offsets"])]
+    pub opcode_stats: bool,
+
+    /// The fuel consumed per microsecond in the Chrome trace timeline.
+    ///
+    /// Only affects the visualization of the timeline, not the fuel
+    /// accounting of the trace.
+    #[arg(long, value_name = "N", default_value_t = 1.0)]
+    pub fuel_per_us: f64,
+}
+
+/// Trace report formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TraceFormat {
+    /// The full trace, one line per event.
+    Text,
+    /// The per function summary, as a Markdown table.
+    Md,
+    /// The per function summary, as CSV.
+    Csv,
+    /// The function invocations, as a Chrome trace.
+    Chrome,
+    /// The full trace, one JSON object per line and event.
+    Jsonl,
+}
+
+/// Text report orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// Every event in execution order, indented by call depth.
+    Time,
+    /// Only the opcode executions, sorted by descending fuel.
+    Fuel,
+}
+
+impl From<SortBy> for ReportOrder {
+    fn from(sort_by: SortBy) -> Self {
+        match sort_by {
+            SortBy::Time => ReportOrder::Time,
+            SortBy::Fuel => ReportOrder::Fuel,
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -51,6 +131,117 @@ pub struct PrintOptions {
     /// Path to the JavaScript input file.
     #[arg(value_name = "JS", required = true)]
     pub input: PathBuf,
+
+    /// Print the bytecode version and the features inferred from it,
+    /// instead of the disassembly.
+    #[arg(long)]
+    pub version_info: bool,
+
+    /// Stop after printing the given number of functions.
+    #[arg(long, value_name = "N")]
+    pub max_funcs: Option<usize>,
+
+    /// Print only the function with the given name.
+    #[arg(long, value_name = "NAME", conflicts_with = "max_funcs")]
+    pub func: Option<String>,
+
+    /// Print the pc to line and column mapping of the function with the
+    /// given name, instead of the disassembly.
+    #[arg(long, value_name = "NAME")]
+    pub lines: Option<String>,
+
+    /// Print the operators of the function with the given name along with
+    /// their raw bytes, as hex and ASCII, instead of the disassembly.
+    #[arg(long, value_name = "NAME")]
+    pub hex: Option<String>,
+
+    /// Print the number of functions, operator bytes and atoms of the
+    /// module and whether it carries debug information, instead of the
+    /// disassembly.
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Print the interned atoms that are not referenced by the module nor
+    /// by any function, instead of the disassembly.
+    #[arg(long)]
+    pub unused_atoms: bool,
+
+    /// Write the disassembly of each function to its own
+    /// `<index>_<name>.txt` file in the given directory.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["max_funcs", "func"])]
+    pub split: Option<PathBuf>,
+
+    /// Path to a JSON file with notes to append to operators, as an array
+    /// of `{ "func": <index>, "pc": <offset>, "note": <text> }` objects.
+    #[arg(long, value_name = "FILE")]
+    pub annotations: Option<PathBuf>,
+
+    /// Path to the JavaScript source of the bytecode, whose lines are
+    /// printed above the operators they originate from.
+    #[arg(long, value_name = "FILE")]
+    pub source: Option<PathBuf>,
+
+    /// Append the JavaScript construct that typically emits each operator,
+    /// e.g., `class declaration`, as a trailing comment.
+    #[arg(long)]
+    pub hints: bool,
+
+    /// The built-in atom table of the QuickJS build that produced the
+    /// bytecode.
+    #[arg(long, value_enum, default_value_t = AtomTableVersion::Rquickjs)]
+    pub atom_table: AtomTableVersion,
+}
+
+#[derive(Debug, Parser)]
+pub struct ExtractOptions {
+    /// Path to the Wasm module or component embedding the bytecode.
+    #[arg(value_name = "WASM", required = true)]
+    pub input: PathBuf,
+
+    /// The name of the custom section that holds the bytecode.
+    #[arg(long, value_name = "NAME", default_value = extract::DEFAULT_SECTION)]
+    pub section: String,
+
+    /// Write the extracted bytecode to the given file, instead of printing
+    /// its disassembly.
+    #[arg(short = 'o', value_name = "FILE")]
+    pub out: Option<PathBuf>,
+
+    /// The built-in atom table of the QuickJS build that produced the
+    /// bytecode.
+    #[arg(long, value_enum, default_value_t = AtomTableVersion::Rquickjs)]
+    pub atom_table: AtomTableVersion,
+}
+
+#[derive(Debug, Parser)]
+pub struct ValidateOptions {
+    /// Path to the JavaScript input file, or to QuickJS bytecode if the
+    /// file has the `.bc` extension.
+    #[arg(value_name = "INPUT", required = true)]
+    pub input: PathBuf,
+
+    /// The built-in atom table of the QuickJS build that produced the
+    /// bytecode.
+    #[arg(long, value_enum, default_value_t = AtomTableVersion::Rquickjs)]
+    pub atom_table: AtomTableVersion,
+}
+
+/// Built-in atom tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AtomTableVersion {
+    /// QuickJS 2021-03-27 as patched by rquickjs, as used by Javy.
+    Rquickjs,
+    /// Upstream QuickJS 2021-03-27.
+    Upstream,
+}
+
+impl From<AtomTableVersion> for AtomTable {
+    fn from(version: AtomTableVersion) -> Self {
+        match version {
+            AtomTableVersion::Rquickjs => AtomTable::Rquickjs,
+            AtomTableVersion::Upstream => AtomTable::Upstream,
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -60,15 +251,114 @@ fn main() -> Result<()> {
         Command::Trace(opts) => {
             let bytecode = compile(&opts.input)?;
             let raw_trace = std::fs::read_to_string(&opts.trace)?;
-            let report = trace(&bytecode, &raw_trace)?;
             let mut file = File::create(&opts.out)?;
-            for line in report {
-                file.write_all(line.as_bytes())?;
+            if opts.opcode_stats {
+                let stats = opcode_stats(&bytecode, &raw_trace)?;
+                file.write_all(opcode_stats_to_markdown(&stats).as_bytes())?;
+                return Ok(());
+            }
+            let options = ReportOptions {
+                cumulative: opts.cumulative,
+                offsets: opts.offsets,
+                merge_intrinsics: opts.merge_intrinsics,
+                sort_by: opts.sort_by.into(),
+                pct_per_line: opts.pct_per_line,
+            };
+            match opts.format {
+                TraceFormat::Text => {
+                    let report = trace_with(&bytecode, &raw_trace, &options)?;
+                    for line in report {
+                        writeln!(file, "{}", line)?;
+                    }
+                }
+                TraceFormat::Md => {
+                    let summary = summary_with(&bytecode, &raw_trace, &options)?;
+                    file.write_all(to_markdown(&summary).as_bytes())?;
+                }
+                TraceFormat::Csv => {
+                    let summary = summary_with(&bytecode, &raw_trace, &options)?;
+                    file.write_all(to_csv(&summary).as_bytes())?;
+                }
+                TraceFormat::Chrome => {
+                    let trace = chrome_trace(&bytecode, &raw_trace, opts.fuel_per_us)?;
+                    file.write_all(trace.as_bytes())?;
+                }
+                TraceFormat::Jsonl => {
+                    for line in trace_json_lines(&bytecode, &raw_trace)? {
+                        writeln!(file, "{}", line)?;
+                    }
+                }
             }
         }
         Command::Print(opts) => {
             let bytecode = compile(&opts.input)?;
-            printer::print(&bytecode)?;
+            let atoms = opts.atom_table.into();
+            if opts.version_info {
+                printer::print_version_info(&bytecode)?;
+            } else if let Some(name) = &opts.lines {
+                printer::print_lines(&bytecode, name, atoms)?;
+            } else if let Some(name) = &opts.hex {
+                printer::print_hex(&bytecode, name, atoms)?;
+            } else if opts.summary {
+                printer::print_summary(&bytecode, atoms)?;
+            } else if opts.unused_atoms {
+                printer::print_unused_atoms(&bytecode, atoms)?;
+            } else if let Some(dir) = &opts.split {
+                printer::print_split(&bytecode, dir, atoms)?;
+            } else {
+                let annotations = match &opts.annotations {
+                    Some(path) => printer::read_annotations(path)?,
+                    None => Default::default(),
+                };
+                let source = match &opts.source {
+                    Some(path) => Some(std::fs::read_to_string(path)?),
+                    None => None,
+                };
+                printer::print(
+                    &bytecode,
+                    printer::PrintOptions {
+                        max_funcs: opts.max_funcs,
+                        func: opts.func.as_deref(),
+                        annotations,
+                        source: source.as_deref(),
+                        hints: opts.hints,
+                        atoms,
+                    },
+                )?;
+            }
+        }
+        Command::Extract(opts) => {
+            let wasm = std::fs::read(&opts.input)?;
+            let bytecode = extract::extract_bytecode(&wasm, &opts.section)?;
+            match &opts.out {
+                Some(out) => std::fs::write(out, bytecode)?,
+                None => printer::print(
+                    bytecode,
+                    printer::PrintOptions {
+                        atoms: opts.atom_table.into(),
+                        ..Default::default()
+                    },
+                )?,
+            }
+        }
+        Command::Validate(opts) => {
+            let bytecode = if opts.input.extension().is_some_and(|ext| ext == "bc") {
+                std::fs::read(&opts.input)?
+            } else {
+                compile(&opts.input)?
+            };
+            let translation = TranslationBuilder::new()
+                .with_builtin_atoms(opts.atom_table.into())
+                .translate(&bytecode)?;
+            let errors = verify(&translation);
+            for error in &errors {
+                println!("{}", error);
+            }
+            ensure!(
+                errors.is_empty(),
+                "Found {} verification errors",
+                errors.len()
+            );
         }
     }
 
@@ -1,18 +1,319 @@
 use anyhow::{anyhow, Result};
-use jac_translate::{quickpars::Opcode, FunctionTranslation, Translation, TranslationBuilder};
+use jac_translate::{
+    quickpars::{
+        debug_escape, describe_version, AtomTable, ConstValue, ConstantPoolIndex,
+        CopyDataPropertiesMask, FuncIndex, Opcode, SpecialObjectKind, ThrowErrorKind,
+    },
+    FunctionTranslation, Translation, TranslationBuilder,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::fmt::Write;
+use std::io;
+use std::path::Path;
 
-/// Pretty-print QuickJS bytecode.
-pub fn print(bytecode: &[u8]) -> Result<()> {
-    let builder = TranslationBuilder::new();
+/// Notes attached to operators, keyed by function index and operator
+/// offset.
+pub type Annotations = HashMap<(FuncIndex, u32), String>;
+
+/// An entry of an annotations file.
+#[derive(Deserialize)]
+struct Annotation {
+    /// The function index.
+    func: u32,
+    /// The operator offset.
+    pc: u32,
+    /// The note to attach to the operator.
+    note: String,
+}
+
+/// Reads annotations from a JSON file containing an array of
+/// `{ "func": <index>, "pc": <offset>, "note": <text> }` objects.
+pub fn read_annotations(path: &Path) -> Result<Annotations> {
+    let contents = std::fs::read_to_string(path)?;
+    let entries: Vec<Annotation> = serde_json::from_str(&contents)?;
+    Ok(entries
+        .into_iter()
+        .map(|a| ((FuncIndex::from_u32(a.func), a.pc), a.note))
+        .collect())
+}
+
+/// Options of [print].
+#[derive(Debug, Default, Clone)]
+pub struct PrintOptions<'a> {
+    /// Print at most this many functions.
+    pub max_funcs: Option<usize>,
+    /// Only print the first function with the given name, ignoring
+    /// `max_funcs`.
+    pub func: Option<&'a str>,
+    /// Notes to follow operators by, as `; <note>`.
+    pub annotations: Annotations,
+    /// The JavaScript source of the bytecode, to precede operators by the
+    /// line they originate from.
+    pub source: Option<&'a str>,
+    /// Follow operators specific to a JavaScript construct by
+    /// `; <construct>`, see [Opcode::source_hint].
+    pub hints: bool,
+    /// The built-in atoms to resolve atoms against.
+    pub atoms: AtomTable,
+}
+
+impl PrintOptions<'_> {
+    /// Creates a [Printer] with the annotations, source and hints of the
+    /// options.
+    fn into_printer(self) -> Printer {
+        let printer = Printer::new()
+            .with_annotations(self.annotations)
+            .with_hints(self.hints);
+        match self.source {
+            Some(source) => printer.with_source(source),
+            None => printer,
+        }
+    }
+}
+
+/// Pretty-print QuickJS bytecode, as configured by `options`.
+pub fn print(bytecode: &[u8], options: PrintOptions) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    match options.func {
+        Some(_) => {
+            io::Write::write_all(&mut stdout, disassemble_with(bytecode, options)?.as_bytes())?
+        }
+        // Stream the functions, since the whole module can be large.
+        None => {
+            let builder = TranslationBuilder::new().with_builtin_atoms(options.atoms);
+            let translation = builder.translate(bytecode)?;
+            let max_funcs = options.max_funcs;
+            options
+                .into_printer()
+                .print_to(&translation, max_funcs, &mut stdout)?;
+        }
+    }
+    io::Write::write_all(&mut stdout, b"\n")?;
+
+    Ok(())
+}
+
+/// Disassembles QuickJS bytecode, returning the text of all the functions.
+pub fn disassemble(bytecode: &[u8]) -> Result<String> {
+    disassemble_with(bytecode, PrintOptions::default())
+}
+
+/// Disassembles QuickJS bytecode into `writer`, one function at a time,
+/// without buffering the text of the whole module.
+///
+/// The output is the same as the one of [disassemble].
+pub fn disassemble_to<W: io::Write>(bytecode: &[u8], writer: W) -> Result<()> {
+    let translation = TranslationBuilder::new().translate(bytecode)?;
+    Printer::new().print_to(&translation, None, writer)
+}
+
+/// Disassembles QuickJS bytecode, preceding operators by the line of
+/// `source` they originate from, as a `; <line>: <text>` comment.
+pub fn disassemble_with_source(bytecode: &[u8], source: &str) -> Result<String> {
+    disassemble_with(
+        bytecode,
+        PrintOptions {
+            source: Some(source),
+            ..Default::default()
+        },
+    )
+}
+
+/// Disassembles QuickJS bytecode, following the same function selection as
+/// [print].
+fn disassemble_with(bytecode: &[u8], options: PrintOptions) -> Result<String> {
+    let builder = TranslationBuilder::new().with_builtin_atoms(options.atoms);
+    let translation = builder.translate(bytecode)?;
+    let (func, max_funcs) = (options.func, options.max_funcs);
+    let printer = options.into_printer();
+    match func {
+        Some(name) => printer.print_one(&translation, find_func(&translation, name)?),
+        None => printer.print(&translation, max_funcs),
+    }
+}
+
+/// Pretty-print QuickJS bytecode into `dir`, one `<index>_<name>.txt` file
+/// per function.
+pub fn print_split(bytecode: &[u8], dir: &Path, atoms: AtomTable) -> Result<()> {
+    let builder = TranslationBuilder::new().with_builtin_atoms(atoms);
+    let translation = builder.translate(bytecode)?;
+    std::fs::create_dir_all(dir)?;
+    for func in &translation.module.functions {
+        let name = translation.resolve_atom_name(func.header.name_index);
+        let path = dir.join(format!(
+            "{}_{}.txt",
+            func.index.as_u32(),
+            sanitize_file_name(name)
+        ));
+        let result = Printer::new().print_one(&translation, func.index)?;
+        std::fs::write(path, result)?;
+    }
+
+    Ok(())
+}
+
+/// Makes a function name safe to use as part of a file name.
+///
+/// Path separators, control characters (e.g., null bytes in computed names)
+/// and other characters reserved by common filesystems are replaced by `_`.
+/// Empty names are rendered as `anonymous`.
+fn sanitize_file_name(name: &str) -> String {
+    if name.is_empty() {
+        return "anonymous".into();
+    }
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Print the operators of the function with the given name along with their
+/// raw bytes, as hex and ASCII.
+pub fn print_hex(bytecode: &[u8], name: &str, atoms: AtomTable) -> Result<()> {
+    println!("{}", hex_dump(bytecode, name, atoms)?);
+
+    Ok(())
+}
+
+/// Returns the operators of the function with the given name along with
+/// their raw bytes, as printed by [print_hex].
+pub fn hex_dump(bytecode: &[u8], name: &str, atoms: AtomTable) -> Result<String> {
+    let builder = TranslationBuilder::new().with_builtin_atoms(atoms);
+    let translation = builder.translate(bytecode)?;
+    let index = find_func(&translation, name)?;
+    Printer::new().print_hex(&translation, index)
+}
+
+/// Print the pc to line and column mapping of the function with the given
+/// name.
+pub fn print_lines(bytecode: &[u8], name: &str, atoms: AtomTable) -> Result<()> {
+    let builder = TranslationBuilder::new().with_builtin_atoms(atoms);
+    let translation = builder.translate(bytecode)?;
+    let index = find_func(&translation, name)?;
+    let debug = translation.module.functions[index.as_u32() as usize]
+        .debug
+        .as_ref()
+        .ok_or_else(|| anyhow!("Function `{}` has no debug information", name))?;
+    println!("pc\tline\tcol");
+    for (pc, line, col) in debug.line_table() {
+        println!("{:#01x}\t{}\t{}", pc, line, col);
+    }
+
+    Ok(())
+}
+
+/// Renders a constant pool entry, resolving function names.
+fn const_name(
+    translation: &Translation,
+    func: &FunctionTranslation,
+    index: ConstantPoolIndex,
+) -> String {
+    match func.constant(index) {
+        Some(ConstValue::Function(f)) => match translation.try_resolve_func_name(*f, None) {
+            Ok(name) => debug_escape(name),
+            Err(_) => format!("<invalid function {}>", f.as_u32()),
+        },
+        Some(value) => const_value(translation, value),
+        None => format!("<invalid constant {}>", index.as_u32()),
+    }
+}
+
+/// Renders a constant value, resolving function names and the property
+/// names of objects.
+fn const_value(translation: &Translation, value: &ConstValue) -> String {
+    match value {
+        ConstValue::Function(f) => match translation.try_resolve_func_name(*f, None) {
+            Ok(name) => format!("func {}", debug_escape(name)),
+            Err(_) => format!("<invalid function {}>", f.as_u32()),
+        },
+        ConstValue::Array(elements) => {
+            let elements = elements
+                .iter()
+                .map(|e| const_value(translation, e))
+                .collect::<Vec<_>>();
+            format!("[{}]", elements.join(", "))
+        }
+        ConstValue::Object(props) => {
+            let props = props
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}: {}",
+                        debug_escape(translation.resolve_atom_name(*key)),
+                        const_value(translation, value)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", props.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+/// Finds the first function with the given name.
+fn find_func(translation: &Translation, name: &str) -> Result<FuncIndex> {
+    translation
+        .function_index_by_name(name)
+        .ok_or_else(|| anyhow!("Function `{}` not found", name))
+}
+
+/// Print the interned atoms that are not referenced by the module nor by any
+/// function.
+pub fn print_unused_atoms(bytecode: &[u8], atoms: AtomTable) -> Result<()> {
+    let builder = TranslationBuilder::new().with_builtin_atoms(atoms);
     let translation = builder.translate(bytecode)?;
-    let printer = Printer::new();
-    let result = printer.print(&translation)?;
-    println!("{}", result);
+    println!("atom\tname");
+    for (index, name) in translation.unused_atoms()? {
+        println!("{}\t{}", index.as_u32(), debug_escape(name));
+    }
 
     Ok(())
 }
 
+/// Print an overview of the size of the module.
+pub fn print_summary(bytecode: &[u8], atoms: AtomTable) -> Result<()> {
+    let builder = TranslationBuilder::new().with_builtin_atoms(atoms);
+    let translation = builder.translate(bytecode)?;
+    let stats = translation.stats();
+    println!("functions: {}", stats.function_count);
+    println!("operator bytes: {}", stats.total_operator_bytes);
+    println!(
+        "atoms: {} ({} interned)",
+        stats.atom_count, stats.interned_atom_count
+    );
+    println!("debug info: {}", if stats.has_debug { "yes" } else { "no" });
+
+    Ok(())
+}
+
+/// Print the bytecode version and the features inferred from it.
+pub fn print_version_info(bytecode: &[u8]) -> Result<()> {
+    let version = bytecode
+        .first()
+        .ok_or_else(|| anyhow!("Empty bytecode, expected a version"))?;
+    let info = describe_version(*version);
+    println!("version: {}", info.version);
+    println!("quickjs base version: {}", info.quickjs_base);
+    println!(
+        "bignum: {}",
+        if info.has_bignum {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+
+    Ok(())
+}
+
+/// The length in bytes of the longest operators, including their
+/// immediates, e.g., `WithGetVar`.
+const MAX_OPERATOR_LEN: usize = 10;
+
 /// Writer implementation.
 #[derive(Default)]
 struct Writer {
@@ -30,6 +331,13 @@ impl Write for Writer {
 struct Printer {
     /// Writer struct to accumulate the result.
     writer: Writer,
+    /// Notes to print after the annotated operators.
+    annotations: Annotations,
+    /// The lines of the JavaScript source, to print above the operators
+    /// they originate from.
+    source: Option<Vec<String>>,
+    /// Whether to print the source hints of the operators.
+    hints: bool,
 }
 
 impl Printer {
@@ -37,13 +345,123 @@ impl Printer {
     fn new() -> Self {
         Self {
             writer: Writer::default(),
+            annotations: Annotations::new(),
+            source: None,
+            hints: false,
         }
     }
 
-    /// Print.
-    fn print<'data>(mut self, translation: &Translation<'data>) -> Result<String> {
-        for func in &translation.module.functions {
+    /// Attaches notes to operators.
+    fn with_annotations(mut self, annotations: Annotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Appends the source hint of each operator, if any.
+    fn with_hints(mut self, hints: bool) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Interleaves the lines of the given source with the operators.
+    fn with_source(mut self, source: &str) -> Self {
+        self.source = Some(source.lines().map(str::to_string).collect());
+        self
+    }
+
+    /// Print, stopping after `max_funcs` functions if provided.
+    fn print<'data>(
+        self,
+        translation: &Translation<'data>,
+        max_funcs: Option<usize>,
+    ) -> Result<String> {
+        let mut out = vec![];
+        self.print_to(translation, max_funcs, &mut out)?;
+
+        Ok(String::from_utf8(out)?)
+    }
+
+    /// Same as [Printer::print], writing each function to `writer` as soon
+    /// as it's printed, instead of accumulating the whole result.
+    fn print_to<'data, W: io::Write>(
+        mut self,
+        translation: &Translation<'data>,
+        max_funcs: Option<usize>,
+        mut writer: W,
+    ) -> Result<()> {
+        let max_funcs = max_funcs.unwrap_or(usize::MAX);
+        for func in translation.module.functions.iter().take(max_funcs) {
             self.print_func(translation, func)?;
+            writer.write_all(self.writer.inner.as_bytes())?;
+            self.writer.inner.clear();
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Print a single function.
+    fn print_one<'data>(
+        mut self,
+        translation: &Translation<'data>,
+        index: FuncIndex,
+    ) -> Result<String> {
+        let func = translation
+            .module
+            .functions
+            .get(index.as_u32() as usize)
+            .ok_or_else(|| anyhow!("Function index {} out of bounds", index.as_u32()))?;
+        self.print_func(translation, func)?;
+
+        Ok(std::mem::take(&mut self.writer.inner))
+    }
+
+    /// Print a single function, prefixing each operator with its raw bytes.
+    fn print_hex<'data>(
+        mut self,
+        translation: &Translation<'data>,
+        index: FuncIndex,
+    ) -> Result<String> {
+        let func = translation
+            .module
+            .functions
+            .get(index.as_u32() as usize)
+            .ok_or_else(|| anyhow!("Function index {} out of bounds", index.as_u32()))?;
+        let func_name = debug_escape(translation.resolve_atom_name(func.header.name_index));
+        write!(self.writer, "func: {}", func_name)?;
+        self.nl()?;
+
+        let mut reader = func.operators_reader();
+        let data = reader.data();
+        while !reader.done() {
+            let (pc, op) = Opcode::from_reader(&mut reader)?;
+            let bytes = &data[pc as usize..reader.offset];
+            let hex = bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let ascii = bytes
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            write!(
+                self.writer,
+                "{:#06x}  {:<width$}  |{:<len$}|",
+                pc,
+                hex,
+                ascii,
+                width = MAX_OPERATOR_LEN * 3 - 1,
+                len = MAX_OPERATOR_LEN
+            )?;
+            self.print_op(op, translation, func)?;
+            self.nl()?;
         }
 
         Ok(std::mem::take(&mut self.writer.inner))
@@ -61,17 +479,79 @@ impl Printer {
 
     /// Print a function.
     fn print_func(&mut self, translation: &Translation, func: &FunctionTranslation) -> Result<()> {
-        let func_name = translation.resolve_atom_name(func.header.name_index);
+        let func_name = debug_escape(translation.resolve_atom_name(func.header.name_index));
         write!(&mut self.writer, "func: {}", func_name).map_err(|e| anyhow!("{}", e))?;
+        if func.header.is_strict {
+            write!(self.writer, " [strict]")?;
+        }
         self.nl()?;
 
-        let mut reader = func.operators.clone();
+        for (i, var) in func.closure_vars.iter().enumerate() {
+            let name = debug_escape(translation.resolve_atom_name(var.name_index));
+            let flags = var.decoded_flags();
+            let mut desc = vec![if flags.is_local {
+                "captures parent local"
+            } else if flags.is_arg {
+                "captures parent arg"
+            } else {
+                "captures parent closure var"
+            }];
+            if flags.is_const {
+                desc.push("const");
+            }
+            if flags.is_lexical {
+                desc.push("lexical");
+            }
+            self.space2()?;
+            write!(
+                self.writer,
+                "closure var {}: {} {} [{}]",
+                i,
+                name,
+                var.index,
+                desc.join(", ")
+            )?;
+            self.nl()?;
+        }
+
+        for (i, value) in func.constants().iter().enumerate() {
+            self.space2()?;
+            write!(
+                self.writer,
+                "const[{}] = {}",
+                i,
+                const_value(translation, value)
+            )?;
+            self.nl()?;
+        }
+
+        // The line table is only needed to interleave the source, and is
+        // decoded once, then walked alongside the operators, in pc order.
+        let line_table = match (&self.source, &func.debug) {
+            (Some(_), Some(debug)) => debug.line_table(),
+            _ => vec![],
+        };
+        let mut entries = line_table.iter().peekable();
+        let mut reader = func.operators_reader();
+        let mut line = None;
+        let mut last_line = None;
 
         while !reader.done() {
             let op = Opcode::from_reader(&mut reader)?;
+            while let Some((_, entry_line, _)) = entries.next_if(|(pc, _, _)| *pc <= op.0) {
+                line = Some(*entry_line);
+            }
+            self.print_source_line(line, &mut last_line)?;
             write!(self.writer, "{:#01x}", op.0)?;
             self.space2()?;
+            let hint = op.1.source_hint().filter(|_| self.hints);
             self.print_op(op.1, &translation, &func)?;
+            if let Some(note) = self.annotations.get(&(func.index, op.0)) {
+                write!(self.writer, " ; {}", note)?;
+            }
+            if let Some(hint) = hint {
+                write!(self.writer, " ; {}", hint)?;
+            }
             self.nl()?;
         }
         self.nl()?;
@@ -79,6 +559,26 @@ impl Printer {
         Ok(())
     }
 
+    /// Prints the source line of an operator, unless it's the same as the
+    /// one of the previous operator.
+    ///
+    /// Nothing is printed without source or line, or if the line is out of
+    /// the bounds of the source.
+    fn print_source_line(&mut self, line: Option<u32>, last_line: &mut Option<u32>) -> Result<()> {
+        let (Some(source), Some(line)) = (&self.source, line) else {
+            return Ok(());
+        };
+        if *last_line == Some(line) {
+            return Ok(());
+        }
+        *last_line = Some(line);
+        let Some(text) = line.checked_sub(1).and_then(|i| source.get(i as usize)) else {
+            return Ok(());
+        };
+        writeln!(self.writer, "; {}: {}", line, text.trim())?;
+        Ok(())
+    }
+
     /// Print an op code.
     //
     // TODO: Handle offsets.
@@ -95,19 +595,21 @@ impl Printer {
             Invalid => write!(&mut self.writer, "{}", "Invalid"),
             PushI32 { value } => write!(&mut self.writer, "PushI32 {}", value),
             PushConst { index } => {
-                let imm = translation.resolve_func_name(func.index, Some(index));
-                write!(&mut self.writer, "PushI32 {}", imm)
+                let imm = const_name(translation, func, index);
+                write!(&mut self.writer, "PushConst {}", imm)
             }
             FClosure { index } => {
-                let imm = translation.resolve_func_name(func.index, Some(index));
-                write!(self.writer, "FClosure {}", imm)
+                match translation.try_resolve_func_name(func.index, Some(index)) {
+                    Ok(imm) => write!(self.writer, "FClosure {}", imm),
+                    Err(_) => write!(self.writer, "FClosure <invalid closure {}>", index.as_u32()),
+                }
             }
             PushAtomValue { atom } => {
-                let v = translation.resolve_atom_name(atom);
+                let v = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "PushAtomValue {}", v)
             }
             PrivateSymbol { atom } => {
-                let sym = translation.resolve_atom_name(atom);
+                let sym = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "PrivateSymbol {}", sym)
             }
             Undefined => write!(self.writer, "Undefined"),
@@ -152,62 +654,72 @@ impl Printer {
             ReturnAsync => write!(self.writer, "ReturnAsync"),
             Throw => write!(self.writer, "Throw"),
             ThrowError { ty, atom } => {
-                let imm = translation.resolve_atom_name(atom);
-                write!(self.writer, "ThrowError {} {}", ty, imm)
+                let imm = debug_escape(translation.resolve_atom_name(atom));
+                match ThrowErrorKind::from_type(ty) {
+                    Some(kind) => write!(
+                        self.writer,
+                        "ThrowError {} {:?} {}",
+                        kind.error_name(),
+                        kind,
+                        imm
+                    ),
+                    None => write!(self.writer, "ThrowError {} {}", ty, imm),
+                }
             }
             Eval { scope, argc } => write!(self.writer, "Eval {} {}", scope, argc),
             ApplyEval { scope } => write!(self.writer, "ApplyEval {}", scope),
             Regexp => write!(self.writer, "Regexp"),
             GetSuper => write!(self.writer, "GetSuper"),
-            Import => write!(self.writer, "Import"),
+            // Static imports are part of the module header.
+            Import => write!(self.writer, "Import (dynamic)"),
             CheckVar { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "CheckVar {}", imm)
             }
             GetVarUndef { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "GetVarUndef {}", imm)
             }
             GetVar { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "GetVar {}", imm)
             }
             PutVar { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "PutVar {}", imm)
             }
             PutVarInit { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "PutVarInit {}", imm)
             }
             PutVarStrict { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "PutVarStrict {}", imm)
             }
             GetRefValue => write!(self.writer, "GetRefValue"),
             PutRefValue => write!(self.writer, "PutRefValue"),
             DefineVar { flags, atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DefineVar {} {}", imm, flags)
             }
             CheckDefineVar { flags, atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "CheckDefineVar {} {}", imm, flags)
             }
             DefineFunc { flags, atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DefineFunc {} {}", imm, flags)
             }
             GetField { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "GetField {}", imm)
             }
             GetField2 { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "GetField2 {}", imm)
             }
             PutField { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "PutField {}", imm)
             }
             GetPrivateField => write!(self.writer, "GetPrivateField"),
@@ -219,11 +731,11 @@ impl Printer {
             GetSuperValue => write!(self.writer, "GetSuperValue"),
             PutSuperValue => write!(self.writer, "PutSuperValue"),
             DefineField { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DefineField {}", imm)
             }
             SetName { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "SetName {}", imm)
             }
             SetNameComputed => write!(self.writer, "SetNameComputed"),
@@ -231,18 +743,27 @@ impl Printer {
             SetHomeObject => write!(self.writer, "SetHomeObject"),
             DefineArrayEl => write!(self.writer, "DefineArrayEl"),
             Append => write!(self.writer, "Append"),
-            CopyDataProperties { mask } => write!(self.writer, "CopyDataProperties {}", mask),
+            CopyDataProperties { mask } => {
+                let operands = CopyDataPropertiesMask::from_mask(mask);
+                write!(
+                    self.writer,
+                    "CopyDataProperties target=sp[-{}] source=sp[-{}] excluded=sp[-{}]",
+                    operands.target + 1,
+                    operands.source + 1,
+                    operands.excluded + 1
+                )
+            }
             DefineMethod { atom, flags } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DefineMethod {} {}", imm, flags)
             }
             DefineMethodComputed { flags } => write!(self.writer, "DefineMethodComputed {}", flags),
             DefineClass { flags, atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DefineClass {} {}", imm, flags)
             }
             DefineClassComputed { flags, atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DefineClassComputed {} {}", imm, flags)
             }
             GetLoc { index } => write!(self.writer, "GetLoc {}", index.as_u32()),
@@ -296,7 +817,7 @@ impl Printer {
                 diff,
                 is_with,
             } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "WithGetVar {} {} {}", imm, diff, is_with)
             }
             WithPutVar {
@@ -304,7 +825,7 @@ impl Printer {
                 diff,
                 is_with,
             } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "WithPutVar {} {} {}", imm, diff, is_with)
             }
             WithDeleteVar {
@@ -312,7 +833,7 @@ impl Printer {
                 diff,
                 is_with,
             } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "WithDeleteVar {} {} {}", imm, diff, is_with)
             }
             WithMakeRef {
@@ -320,7 +841,7 @@ impl Printer {
                 diff,
                 is_with,
             } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "WithMakeRef {} {} {}", imm, diff, is_with)
             }
             WithGetRef {
@@ -328,7 +849,7 @@ impl Printer {
                 diff,
                 is_with,
             } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "WithGetRef {} {} {}", imm, diff, is_with)
             }
             WithGetRefUndef {
@@ -336,23 +857,23 @@ impl Printer {
                 diff,
                 is_with,
             } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "WithGetRefUndef {} {} {}", imm, diff, is_with)
             }
             MakeLocRef { atom, idx } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "MakeLocRef {} {}", imm, idx)
             }
             MakeArgRef { atom, idx } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "MakeArgRef {} {}", imm, idx)
             }
             MakeVarRefRef { atom, idx } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "MakeVarRefRef {} {}", imm, idx)
             }
             MakeVarRef { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "MakeVarRef {}", imm)
             }
             ForInStart => write!(self.writer, "ForInStart"),
@@ -384,7 +905,7 @@ impl Printer {
             TypeOf => write!(self.writer, "TypeOf"),
             Delete => write!(self.writer, "Delete"),
             DeleteVar { atom } => {
-                let imm = translation.resolve_atom_name(atom);
+                let imm = debug_escape(translation.resolve_atom_name(atom));
                 write!(self.writer, "DeleteVar {}", imm)
             }
             Mul => write!(self.writer, "Mul"),
@@ -425,7 +946,10 @@ impl Printer {
             Push7 => write!(self.writer, "Push7"),
             PushI8 { val } => write!(self.writer, "PushI8 {}", val),
             PushI16 { val } => write!(self.writer, "PushI16 {}", val),
-            PushConst8 { index } => write!(self.writer, "PushConst8 {}", index),
+            PushConst8 { index } => {
+                let imm = const_name(translation, func, ConstantPoolIndex::from_u32(index as u32));
+                write!(self.writer, "PushConst8 {}", imm)
+            }
             // FIXME: Should be able to figure out the closure name.
             FClosure8 { index } => write!(self.writer, "FClosure8 {}", index.as_u32()),
             PushEmptyString => write!(self.writer, "PushEmptyString"),
@@ -481,7 +1005,10 @@ impl Printer {
             IsNull => write!(self.writer, "IsNull"),
             TypeOfIsUndefined => write!(self.writer, "TypeOfIsUndefined"),
             TypeOfIsFunction => write!(self.writer, "TypeOfIsFunction"),
-            SpecialObject { argument } => write!(self.writer, "SpecialObject {}", argument),
+            SpecialObject { argument } => match SpecialObjectKind::from_argument(argument) {
+                Some(kind) => write!(self.writer, "SpecialObject {:?}", kind),
+                None => write!(self.writer, "SpecialObject {}", argument),
+            },
         }?;
 
         Ok(())
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use jac_testutil::named_fixture;
+use jac_translate::{
+    quickpars::{AtomTable, ATOM_NAMES},
+    TranslationBuilder,
+};
+
+#[test]
+fn atom_table_shifts_interned_atoms() -> Result<()> {
+    let bytecode = named_fixture("closure");
+
+    let rquickjs = TranslationBuilder::new()
+        .with_builtin_atoms(AtomTable::Rquickjs)
+        .translate(&bytecode)?;
+    let upstream = TranslationBuilder::new()
+        .with_builtin_atoms(AtomTable::Upstream)
+        .translate(&bytecode)?;
+
+    assert_eq!(rquickjs.header.builtin_atom_count(), ATOM_NAMES.len());
+    assert_eq!(upstream.header.builtin_atom_count(), ATOM_NAMES.len() - 1);
+    assert!(!upstream.header.atoms.iter().any(|a| a == "columnNumber"));
+
+    // The interned atoms are the same, one index earlier.
+    let interned =
+        |t: &jac_translate::Translation| t.header.atoms[t.header.builtin_atom_count()..].to_vec();
+    assert_eq!(interned(&rquickjs), interned(&upstream));
+    assert!(interned(&upstream).iter().any(|a| a == "counter"));
+    Ok(())
+}
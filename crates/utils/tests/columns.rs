@@ -0,0 +1,46 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::{quickpars::Opcode, TranslationBuilder};
+
+#[test]
+fn statements_on_the_same_line_have_distinct_columns() -> Result<()> {
+    let bytecode =
+        compile_fixture("function oneLine(a) { const b = a + 1; return b * 2; }\noneLine(1);");
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = translation.function_by_name("oneLine").unwrap();
+    let debug = func.debug.as_ref().expect("debug information");
+
+    let mut add = None;
+    let mut mul = None;
+    let mut reader = func.operators_reader();
+    while !reader.done() {
+        match Opcode::from_reader(&mut reader)? {
+            (pc, Opcode::Add) => add = Some(pc),
+            (pc, Opcode::Mul) => mul = Some(pc),
+            _ => {}
+        }
+    }
+
+    let (add_line, add_col) = debug.line_for_pc(add.unwrap()).unwrap();
+    let (mul_line, mul_col) = debug.line_for_pc(mul.unwrap()).unwrap();
+    assert_eq!(add_line, 1);
+    assert_eq!(mul_line, 1);
+    assert!(add_col < mul_col, "{add_col} vs {mul_col}");
+    Ok(())
+}
+
+#[test]
+fn line_table_pcs_increase_from_zero() -> Result<()> {
+    let bytecode = compile_fixture(
+        "function twoLines(a) {\n  const b = a + 1;\n  return b * 2;\n}\ntwoLines(1);",
+    );
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let func = translation.function_by_name("twoLines").unwrap();
+    let debug = func.debug.as_ref().expect("debug information");
+
+    let table = debug.line_table();
+    assert_eq!(table[0].0, 0);
+    assert!(table.windows(2).all(|w| w[0].0 < w[1].0), "{table:?}");
+    assert!(table.iter().all(|(_, line, _)| *line >= debug.lineno));
+    Ok(())
+}
@@ -0,0 +1,362 @@
+use anyhow::Result;
+use jac_testutil::{
+    compile_fixture, encode_bytecode, encode_function, interned_atom, named_fixture,
+};
+use jac_translate::{
+    quickpars::{
+        ConstantPoolIndex, FunctionLocal, FunctionSectionHeader, Opcode, SpecialObjectKind, Tag,
+        ThrowErrorKind,
+    },
+    TranslationBuilder,
+};
+use jac_utils::{disassemble, disassemble_to, disassemble_with_source};
+use std::path::Path;
+
+/// Reads the source of the given fixture in `tests/fixtures`.
+fn fixture_source(name: &str) -> Result<String> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    Ok(std::fs::read_to_string(path)?)
+}
+
+/// Compiles the given fixture in `tests/fixtures` to bytecode and returns
+/// its disassembly.
+fn disassemble_fixture(name: &str) -> Result<String> {
+    disassemble(&compile_fixture(&fixture_source(name)?))
+}
+
+/// Encodes the given operators.
+fn encode_ops(ops: &[Opcode]) -> Vec<u8> {
+    let mut operators = vec![];
+    for op in ops {
+        op.encode(&mut operators);
+    }
+    operators
+}
+
+/// Builds the bytecode of a single function, named `main`, made of the
+/// given operators, with one stack slot per operator.
+fn hand_built_function(ops: &[Opcode]) -> Result<Vec<u8>> {
+    let operators = encode_ops(ops);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(ops.len() as u32)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    Ok(encode_bytecode(
+        &["main"],
+        &encode_function(&header, &[], &[], &operators),
+    ))
+}
+
+/// Builds the bytecode of a function, named `main`, pushing the given
+/// `F64` constants in turn.
+fn hand_built_f64_constants(values: &[f64]) -> Result<Vec<u8>> {
+    let mut ops = vec![];
+    for index in 0..values.len() as u32 {
+        ops.extend([
+            Opcode::PushConst {
+                index: ConstantPoolIndex::from_u32(index),
+            },
+            Opcode::Drop,
+        ]);
+    }
+    ops.push(Opcode::ReturnUndef);
+    let operators = encode_ops(&ops);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_constant_pool_size(values.len() as u32)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let mut sections = encode_function(&header, &[], &[], &operators);
+    for value in values {
+        sections.push(Tag::F64 as u8);
+        sections.extend(value.to_le_bytes());
+    }
+    Ok(encode_bytecode(&["main"], &sections))
+}
+
+/// Builds the bytecode of a top-level function creating a closure that
+/// adds its two arguments, without debug information.
+fn hand_built_closure() -> Result<Vec<u8>> {
+    let main_ops = encode_ops(&[
+        Opcode::FClosure8 {
+            index: ConstantPoolIndex::from_u32(0),
+        },
+        Opcode::Return,
+    ]);
+    let main = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_constant_pool_size(1)
+        .with_bytecode_len(main_ops.len() as u32)
+        .build()?;
+    let add_ops = encode_ops(&[
+        Opcode::GetArg0,
+        Opcode::GetArg1,
+        Opcode::Add,
+        Opcode::Return,
+    ]);
+    let add = FunctionSectionHeader::builder()
+        .with_name(interned_atom(1))
+        .with_args(2, 2)
+        .with_stack_size(2)
+        .with_strict(true)
+        .with_bytecode_len(add_ops.len() as u32)
+        .build()?;
+    let args = [2, 3].map(|atom| FunctionLocal {
+        name_index: interned_atom(atom),
+        ..Default::default()
+    });
+
+    // The closure is the only entry of the constant pool of `main`.
+    let mut sections = encode_function(&main, &[], &[], &main_ops);
+    sections.extend(encode_function(&add, &args, &[], &add_ops));
+    Ok(encode_bytecode(&["main", "add", "a", "b"], &sections))
+}
+
+#[test]
+fn hand_built() -> Result<()> {
+    insta::assert_snapshot!(disassemble(&hand_built_closure()?)?);
+    Ok(())
+}
+
+#[test]
+fn class() -> Result<()> {
+    insta::assert_snapshot!(disassemble(&named_fixture("class"))?);
+    Ok(())
+}
+
+#[test]
+fn closure() -> Result<()> {
+    insta::assert_snapshot!(disassemble(&named_fixture("closure"))?);
+    Ok(())
+}
+
+#[test]
+fn for_loop() -> Result<()> {
+    insta::assert_snapshot!(disassemble(&named_fixture("loop"))?);
+    Ok(())
+}
+
+#[test]
+fn try_catch() -> Result<()> {
+    insta::assert_snapshot!(disassemble_fixture("try_catch.js")?);
+    Ok(())
+}
+
+#[test]
+fn disassemble_lists_functions() -> Result<()> {
+    let output = disassemble(&named_fixture("loop"))?;
+    assert!(!output.is_empty());
+    assert!(output.contains("func: sum"));
+    Ok(())
+}
+
+#[test]
+fn object_spread_decodes_mask() -> Result<()> {
+    let output = disassemble_fixture("spread.js")?;
+    assert!(output.contains("CopyDataProperties target=sp[-3] source=sp[-2] excluded=sp[-1]"));
+    Ok(())
+}
+
+#[test]
+fn strict_functions_are_marked() -> Result<()> {
+    let output = disassemble_fixture("strict.js")?;
+    assert!(output.contains("func: strict [strict]"));
+    assert!(output.lines().any(|line| line == "func: sloppy"));
+    Ok(())
+}
+
+#[test]
+fn constant_pool_is_listed() -> Result<()> {
+    let output = disassemble_fixture("constants.js")?;
+    let func = output
+        .split("func: ")
+        .find(|f| f.starts_with("mixed"))
+        .expect("`mixed` to be disassembled");
+    let mut constants = func
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("const["))
+        .collect::<Vec<_>>();
+    constants.sort();
+    assert_eq!(constants.len(), 2);
+    assert!(constants[0].starts_with("0] = "));
+    assert!(constants[1].starts_with("1] = "));
+    assert!(constants.iter().any(|c| c.ends_with("] = func nested")));
+    assert!(constants.iter().any(|c| c.ends_with("] = 1.5")));
+    Ok(())
+}
+
+#[test]
+fn source_lines_are_interleaved() -> Result<()> {
+    let source = fixture_source("two_lines.js")?;
+    let bytecode = compile_fixture(&source);
+    let output = disassemble_with_source(&bytecode, &source)?;
+    let func = output
+        .split("func: ")
+        .find(|f| f.starts_with("twoLines"))
+        .expect("`twoLines` to be disassembled");
+    let lines = func.lines().collect::<Vec<_>>();
+
+    let first = lines
+        .iter()
+        .position(|l| *l == "; 2: const b = a + 1;")
+        .expect("line 2 to be printed");
+    let second = lines
+        .iter()
+        .position(|l| *l == "; 3: return b * 2;")
+        .expect("line 3 to be printed");
+    assert!(first < second);
+    // Each line is followed by the operators it maps to.
+    assert!(lines[first + 1].starts_with("0x"));
+    assert!(lines[second + 1].starts_with("0x"));
+    Ok(())
+}
+
+#[test]
+fn dynamic_import_is_labeled() -> Result<()> {
+    let output = disassemble_fixture("dynamic_import.js")?;
+    let func = output
+        .split("func: ")
+        .find(|f| f.starts_with("load"))
+        .expect("`load` to be disassembled");
+    assert!(func.contains("Import (dynamic)"));
+    Ok(())
+}
+
+#[test]
+fn streamed_disassembly_matches_buffered() -> Result<()> {
+    let bytecode = named_fixture("class");
+
+    let mut sink = vec![];
+    disassemble_to(&bytecode, &mut sink)?;
+    assert_eq!(String::from_utf8(sink)?, disassemble(&bytecode)?);
+    Ok(())
+}
+
+#[test]
+fn keys_with_newlines_are_escaped() -> Result<()> {
+    let output = disassemble(&compile_fixture(
+        "const o = { \"a\\nb\": 1 };\nconsole.log(o[\"a\\nb\"]);",
+    ))?;
+    assert!(output.contains(r#"DefineField "a\nb""#), "{output}");
+    assert!(!output.lines().any(|line| line.starts_with("b\"")));
+    Ok(())
+}
+
+#[test]
+fn special_objects_are_named() -> Result<()> {
+    let output = disassemble(&hand_built_function(&[
+        Opcode::SpecialObject {
+            argument: SpecialObjectKind::NewTarget as i32,
+        },
+        Opcode::SpecialObject { argument: 42 },
+        Opcode::Drop,
+        Opcode::Return,
+    ])?)?;
+    assert!(output.contains("SpecialObject NewTarget"), "{output}");
+    // Unknown arguments are printed as is.
+    assert!(output.contains("SpecialObject 42"), "{output}");
+    Ok(())
+}
+
+#[test]
+fn thrown_errors_are_named() -> Result<()> {
+    let output = disassemble(&compile_fixture(
+        "function fail() {\n  throw new TypeError(\"x\");\n}\n",
+    ))?;
+    let func = output
+        .split("func: ")
+        .find(|f| f.starts_with("fail"))
+        .expect("`fail` to be disassembled");
+    // User errors are constructed and thrown through `Throw`.
+    assert!(func.contains("TypeError"), "{func}");
+    assert!(func.lines().any(|line| line.ends_with(" Throw")), "{func}");
+
+    // Errors thrown by the engine itself are named after their kind.
+    let output = disassemble(&compile_fixture(
+        "function assign() {\n  const c = 1;\n  c = 2;\n}\n",
+    ))?;
+    assert!(
+        output.contains("ThrowError TypeError VarReadOnly c"),
+        "{output}"
+    );
+    Ok(())
+}
+
+#[test]
+fn throw_error_kinds_are_decoded() -> Result<()> {
+    let output = disassemble(&hand_built_function(&[
+        Opcode::ThrowError {
+            ty: ThrowErrorKind::VarUninitialized as u8,
+            atom: interned_atom(0),
+        },
+        Opcode::ThrowError {
+            ty: 42,
+            atom: interned_atom(0),
+        },
+    ])?)?;
+    assert!(
+        output.contains("ThrowError ReferenceError VarUninitialized main"),
+        "{output}"
+    );
+    // Unknown types are printed as is.
+    assert!(output.contains("ThrowError 42 main"), "{output}");
+    Ok(())
+}
+
+#[test]
+fn const_captures_are_described() -> Result<()> {
+    let source = "function outer() {\n  const c = 1;\n  return function inner() {\n    return c;\n  };\n}\nouter();\n";
+    let bytecode = compile_fixture(source);
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    let inner = translation.function_by_name("inner").unwrap();
+    let [var] = &inner.closure_vars[..] else {
+        panic!("expected a single closure variable");
+    };
+    assert_eq!(translation.resolve_atom_name(var.name_index), "c");
+    let flags = var.decoded_flags();
+    assert!(flags.is_local && !flags.is_arg);
+    assert!(flags.is_const && flags.is_lexical);
+
+    let output = disassemble(&bytecode)?;
+    let func = output
+        .split("func: ")
+        .find(|f| f.starts_with("inner"))
+        .expect("`inner` to be disassembled");
+    assert!(
+        func.contains(&format!(
+            "closure var 0: c {} [captures parent local, const, lexical]",
+            var.index
+        )),
+        "{func}"
+    );
+    Ok(())
+}
+
+// The literal of the source below, not an approximation of PI.
+#[allow(clippy::approx_constant)]
+#[test]
+fn f64_constants_are_rendered() -> Result<()> {
+    let output = disassemble(&hand_built_f64_constants(&[3.14, -0.0])?)?;
+    assert!(output.contains("const[0] = 3.14"), "{output}");
+    assert!(output.contains("const[1] = -0"), "{output}");
+    Ok(())
+}
+
+#[test]
+fn f64_literals_are_constants() -> Result<()> {
+    let output = disassemble(&compile_fixture(
+        "function floats() {\n  const x = 3.14;\n  const y = -0.0;\n  return [x, y];\n}\nfloats();\n",
+    ))?;
+    let func = output
+        .split("func: ")
+        .find(|f| f.starts_with("floats"))
+        .expect("`floats` to be disassembled");
+    assert!(func.contains("] = 3.14"), "{func}");
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_utils::{disassemble, extract_bytecode};
+
+/// Appends a LEB128 encoded `u32` to `out`.
+fn leb128(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Returns an empty Wasm module with a custom section with the given name
+/// and contents.
+fn module_with_custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut section = vec![];
+    leb128(&mut section, name.len() as u32);
+    section.extend_from_slice(name.as_bytes());
+    section.extend_from_slice(data);
+
+    let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+    wasm.push(0);
+    leb128(&mut wasm, section.len() as u32);
+    wasm.extend(section);
+    wasm
+}
+
+#[test]
+fn extracts_bytecode_from_custom_section() -> Result<()> {
+    let bytecode = compile_fixture("console.log(42);");
+    let wasm = module_with_custom_section("bytecode", &bytecode);
+
+    let extracted = extract_bytecode(&wasm, "bytecode")?;
+    assert_eq!(extracted, bytecode.as_slice());
+    assert_eq!(disassemble(extracted)?, disassemble(&bytecode)?);
+    assert!(extract_bytecode(&wasm, "other").is_err());
+    Ok(())
+}
@@ -0,0 +1,6 @@
+function mixed() {
+  const f = function nested() {};
+  return [1.5, f];
+}
+
+console.log(mixed());
@@ -0,0 +1,5 @@
+function load(specifier) {
+  return import(specifier);
+}
+
+load("./module.js");
@@ -0,0 +1,3 @@
+const a = { x: 1 };
+const b = { ...a };
+console.log(b.x);
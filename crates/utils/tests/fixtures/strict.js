@@ -0,0 +1,10 @@
+function strict() {
+  "use strict";
+  return this;
+}
+
+function sloppy() {
+  return this;
+}
+
+console.log(strict(), sloppy());
@@ -0,0 +1,11 @@
+function parse(input) {
+  try {
+    return JSON.parse(input);
+  } catch (e) {
+    return null;
+  } finally {
+    console.log("parsed");
+  }
+}
+
+parse("{}");
@@ -0,0 +1,6 @@
+function twoLines(a) {
+  const b = a + 1;
+  return b * 2;
+}
+
+twoLines(1);
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use jac_testutil::compile_fixture;
+use jac_translate::{
+    quickpars::{Parser, Payload},
+    TranslationBuilder,
+};
+
+#[test]
+fn debug_section_error_offset_is_absolute() -> Result<()> {
+    let bytecode = compile_fixture("function add(a, b) { return a + b; }\nadd(1, 2);");
+
+    // Find the start and length of the last non-empty column debug buffer.
+    let mut col_buffer = None;
+    for payload in Parser::new().parse_buffer(&bytecode) {
+        if let Payload::FunctionDebugInfo(info) = payload? {
+            let reader = info.col_debug_reader;
+            if !reader.data().is_empty() {
+                col_buffer = Some((reader.absolute_offset(), reader.data().len()));
+            }
+        }
+    }
+    let (start, len) = col_buffer.expect("debug information to be present");
+
+    // Truncate the bytecode so that the column buffer is one byte short.
+    let truncated = &bytecode[..start + len - 1];
+    let err = Parser::new()
+        .parse_buffer(truncated)
+        .find_map(|payload| payload.err())
+        .expect("parsing to fail");
+
+    assert!(
+        format!("{err:#}").contains(&format!("at offset: {start} ")),
+        "unexpected error: {err:#}"
+    );
+    Ok(())
+}
+
+#[test]
+fn recovery_skips_corrupt_function() -> Result<()> {
+    let mut bytecode = compile_fixture(
+        "function first() { return 1; }\n\
+         function second() { return 2; }\n\
+         function third() { return 3; }\n\
+         first(); second(); third();",
+    );
+    let names = |bytecode: &[u8]| -> Result<Vec<String>> {
+        Ok(Parser::table_of_contents(bytecode)?
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect())
+    };
+    let expected = names(&bytecode)?;
+
+    // The debug information of `second` follows its operators.
+    let (atoms, debug_start) = {
+        let translation = TranslationBuilder::new().translate(&bytecode)?;
+        let operators = translation
+            .function_by_name("second")
+            .expect("`second` to be defined")
+            .operators;
+        (
+            translation.header.atoms.clone(),
+            operators.absolute_offset() + operators.data().len(),
+        )
+    };
+    // Corrupt it with an overlong LEB128.
+    bytecode[debug_start..debug_start + 5].fill(0xff);
+
+    let payloads = Parser::new()
+        .with_recovery()
+        .parse_buffer(&bytecode)
+        .collect::<Vec<_>>();
+    assert_eq!(payloads.iter().filter(|p| p.is_err()).count(), 1);
+    let recovered = payloads
+        .into_iter()
+        .filter_map(|payload| match payload {
+            Ok(Payload::FunctionHeader(header)) => Some(header.name_index.as_u32() as usize),
+            _ => None,
+        })
+        .map(|index| atoms[index].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(recovered, expected);
+
+    assert!(Parser::new()
+        .parse_buffer(&bytecode)
+        .any(|payload| payload.is_err()));
+    assert!(names(&bytecode).is_err());
+    Ok(())
+}
+
+#[test]
+fn corrupt_module_section_is_caught_at_has_tla() -> Result<()> {
+    let mut bytecode = compile_fixture("const x = 1;\nconsole.log(x);");
+    let first = Parser::table_of_contents(&bytecode)?
+        .into_iter()
+        .next()
+        .expect("a function to be present");
+
+    // `has_tla` is the last byte of the module section, right before the
+    // tag of the first function.
+    bytecode[first.byte_offset - 1] = 7;
+    let err = Parser::new()
+        .parse_buffer(&bytecode)
+        .find_map(|payload| payload.err())
+        .expect("parsing to fail");
+
+    assert!(
+        format!("{err:#}").contains("Invalid module has_tla value 7"),
+        "unexpected error: {err:#}"
+    );
+    Ok(())
+}
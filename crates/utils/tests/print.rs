@@ -0,0 +1,160 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::quickpars::{AtomTable, ConstantPoolIndex, FunctionSectionHeader, Opcode};
+use jac_utils::printer::{hex_dump, print_split};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Returns the path of the given fixture in `tests/fixtures`.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// Runs the `print` command over the input with the given arguments,
+/// returning its standard output.
+fn print(input: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_jac-utils"))
+        .arg("print")
+        .arg(input)
+        .args(args)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn func_prints_a_single_function() -> Result<()> {
+    let input = fixture("constants.js");
+    let all = print(&input, &[])?;
+    assert!(all.lines().filter(|l| l.starts_with("func: ")).count() > 1);
+
+    let output = print(&input, &["--func", "mixed"])?;
+    let headers = output
+        .lines()
+        .filter(|line| line.starts_with("func: "))
+        .collect::<Vec<_>>();
+    assert_eq!(headers, ["func: mixed"]);
+    Ok(())
+}
+
+/// Builds the bytecode of a function named `main`, creating two closures
+/// whose names aren't safe to use in file names.
+fn unsafe_names() -> Result<Vec<u8>> {
+    let mut main_ops = vec![];
+    for index in 0..2 {
+        Opcode::FClosure8 {
+            index: ConstantPoolIndex::from_u32(index),
+        }
+        .encode(&mut main_ops);
+        Opcode::Drop.encode(&mut main_ops);
+    }
+    Opcode::ReturnUndef.encode(&mut main_ops);
+    let main = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_constant_pool_size(2)
+        .with_bytecode_len(main_ops.len() as u32)
+        .build()?;
+    let mut sections = encode_function(&main, &[], &[], &main_ops);
+
+    let mut closure_ops = vec![];
+    Opcode::ReturnUndef.encode(&mut closure_ops);
+    for atom in [1, 2] {
+        let closure = FunctionSectionHeader::builder()
+            .with_name(interned_atom(atom))
+            .with_bytecode_len(closure_ops.len() as u32)
+            .build()?;
+        sections.extend(encode_function(&closure, &[], &[], &closure_ops));
+    }
+    Ok(encode_bytecode(&["main", "get a/b", "x\0y"], &sections))
+}
+
+#[test]
+fn split_writes_a_file_per_function() -> Result<()> {
+    let dir = std::env::temp_dir().join("jac-utils-split");
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    print_split(&unsafe_names()?, &dir, AtomTable::default())?;
+
+    let mut files = std::fs::read_dir(&dir)?
+        .map(|entry| Ok(entry?.file_name().into_string().unwrap()))
+        .collect::<Result<Vec<_>>>()?;
+    files.sort();
+    assert_eq!(files, ["0_main.txt", "1_get a_b.txt", "2_x_y.txt"]);
+    let main = std::fs::read_to_string(dir.join("0_main.txt"))?;
+    assert!(main.starts_with("func: main"), "{main}");
+    Ok(())
+}
+
+#[test]
+fn annotations_are_appended_to_the_targeted_operator() -> Result<()> {
+    let annotations = std::env::temp_dir().join("jac-utils-annotations.json");
+    std::fs::write(
+        &annotations,
+        r#"[{ "func": 0, "pc": 0, "note": "entry point" }]"#,
+    )?;
+    let output = print(
+        &fixture("constants.js"),
+        &["--annotations", annotations.to_str().unwrap()],
+    )?;
+
+    let annotated = output
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains("entry point"))
+        .collect::<Vec<_>>();
+    let [(i, line)] = annotated[..] else {
+        panic!("expected a single annotated line:\n{output}");
+    };
+    // The first operator of the first function.
+    assert!(line.starts_with("0x0 "), "{line}");
+    assert!(line.ends_with(" ; entry point"), "{line}");
+    assert!(output.lines().take(i).all(|l| !l.starts_with("0x")));
+    Ok(())
+}
+
+#[test]
+fn hex_dump_shows_the_bytes_of_each_operator() -> Result<()> {
+    let ops = [Opcode::PushI32 { value: 0x4443_4241 }, Opcode::Return];
+    let mut operators = vec![];
+    let mut encoded = vec![];
+    for op in &ops {
+        let start = operators.len();
+        op.encode(&mut operators);
+        encoded.push(operators[start..].to_vec());
+    }
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    let bytecode = encode_bytecode(&["f"], &encode_function(&header, &[], &[], &operators));
+
+    let output = hex_dump(&bytecode, "f", AtomTable::default())?;
+    let lines = output
+        .lines()
+        .filter(|line| line.starts_with("0x"))
+        .collect::<Vec<_>>();
+    assert_eq!(lines.len(), encoded.len(), "{output}");
+    for (line, bytes) in lines.iter().zip(&encoded) {
+        let hex = bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (_, columns) = line.split_once("  ").unwrap();
+        let (column, _) = columns.split_once("  |").unwrap();
+        assert_eq!(column.trim_end(), hex, "{line}");
+    }
+    // The immediate is printable, so it shows in the ASCII column.
+    assert!(lines[0].contains("|.ABCD"), "{}", lines[0]);
+    Ok(())
+}
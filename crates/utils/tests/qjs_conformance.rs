@@ -0,0 +1,226 @@
+//! Compares the disassembly against the bytecode dumps of the reference
+//! QuickJS implementation.
+//!
+//! The comparisons are ignored unless the `qjs-conformance` feature is
+//! enabled, since they require a `qjs` binary built with `DUMP_BYTECODE`,
+//! found through the `QJS` environment variable or in the `PATH`:
+//!
+//! ```text
+//! QJS=/path/to/qjs cargo test -p jac-utils --features qjs-conformance
+//! ```
+
+use anyhow::{bail, ensure, Context, Result};
+use jac_testutil::{compile_fixture, FIXTURES_DIR};
+use jac_utils::disassemble;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+/// A normalized operator: its offset, if known, and its mnemonic.
+type Operator = (Option<u32>, String);
+
+/// Normalizes a mnemonic, so that `PushI32` and `push_i32` compare equal.
+fn normalize_mnemonic(mnemonic: &str) -> String {
+    mnemonic
+        .chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Extracts the operators of each function from our disassembly, keyed by
+/// function name.
+///
+/// Functions start with a `func: <name>` line, and operators are printed
+/// as `<hex offset>  <Mnemonic> <immediates>`.
+fn parse_disassembly(output: &str) -> BTreeMap<String, Vec<Operator>> {
+    let mut functions = BTreeMap::new();
+    for func in output.split("func: ").skip(1) {
+        let mut lines = func.lines();
+        let name = lines.next().unwrap_or_default();
+        let name = name.strip_suffix(" [strict]").unwrap_or(name);
+        let operators = lines
+            .filter_map(|line| {
+                let (offset, rest) = line.split_once(char::is_whitespace)?;
+                let offset = u32::from_str_radix(offset.strip_prefix("0x")?, 16).ok()?;
+                let mnemonic = rest.split_whitespace().next()?;
+                Some((Some(offset), normalize_mnemonic(mnemonic)))
+            })
+            .collect();
+        functions.insert(name.to_string(), operators);
+    }
+    functions
+}
+
+/// Extracts the operators of each function from a `qjs -d` dump, keyed by
+/// function name.
+///
+/// Functions start with a `<file>:<line>: function: <name>` line, and
+/// their operators are listed, one per line, after an `opcodes:` line.
+/// Operators may be prefixed by their decimal offset, e.g. for branch
+/// targets, and followed by immediates and comments.
+fn parse_qjs_dump(output: &str) -> BTreeMap<String, Vec<Operator>> {
+    let mut functions = BTreeMap::new();
+    let mut current: Option<(String, Vec<Operator>)> = None;
+    let mut in_opcodes = false;
+    for line in output.lines() {
+        if let Some((_, name)) = line.split_once("function: ") {
+            if let Some((name, operators)) = current.take() {
+                functions.insert(name, operators);
+            }
+            current = Some((name.trim().to_string(), vec![]));
+            in_opcodes = false;
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed == "opcodes:" {
+            in_opcodes = true;
+            continue;
+        }
+        // Operators end at the first blank line.
+        if trimmed.is_empty() {
+            in_opcodes = false;
+            continue;
+        }
+        if !in_opcodes {
+            continue;
+        }
+        let Some((_, operators)) = current.as_mut() else {
+            continue;
+        };
+        let (offset, rest) = match trimmed.split_once(':') {
+            Some((offset, rest)) if offset.chars().all(|c| c.is_ascii_digit()) => {
+                (offset.parse().ok(), rest.trim())
+            }
+            _ => (None, trimmed),
+        };
+        let Some(mnemonic) = rest.split_whitespace().next() else {
+            continue;
+        };
+        // Skip source annotations and other non operator lines.
+        if !mnemonic.starts_with(|c: char| c.is_ascii_lowercase()) {
+            continue;
+        }
+        operators.push((offset, normalize_mnemonic(mnemonic)));
+    }
+    if let Some((name, operators)) = current {
+        functions.insert(name, operators);
+    }
+    functions
+}
+
+/// Compares the operators of a function, mnemonic by mnemonic, and the
+/// offsets printed by `qjs`.
+fn compare_function(name: &str, ours: &[Operator], reference: &[Operator]) -> Result<()> {
+    ensure!(
+        ours.len() == reference.len(),
+        "`{}`: {} operators, expected {}\nours: {:?}\nqjs: {:?}",
+        name,
+        ours.len(),
+        reference.len(),
+        ours,
+        reference
+    );
+    for (i, ((offset, mnemonic), (expected_offset, expected))) in
+        ours.iter().zip(reference).enumerate()
+    {
+        ensure!(
+            mnemonic == expected,
+            "`{}`: operator {} is `{}`, expected `{}`",
+            name,
+            i,
+            mnemonic,
+            expected
+        );
+        if let Some(expected_offset) = expected_offset {
+            ensure!(
+                offset == &Some(*expected_offset),
+                "`{}`: `{}` at offset {:?}, expected {}",
+                name,
+                mnemonic,
+                offset,
+                expected_offset
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Compares the disassembly of the fixture at the given path against
+/// `qjs -d`, for every function found in both.
+fn check_fixture(path: &Path) -> Result<()> {
+    let source = std::fs::read_to_string(path)?;
+    let bytecode = compile_fixture(&source);
+    let ours = parse_disassembly(&disassemble(&bytecode)?);
+
+    let qjs = std::env::var("QJS").unwrap_or_else(|_| "qjs".into());
+    let output = Command::new(&qjs)
+        .arg("-d")
+        .arg("--module")
+        .arg(path)
+        .output()
+        .with_context(|| format!("Failed to run `{}`", qjs))?;
+    let reference = parse_qjs_dump(&String::from_utf8_lossy(&output.stdout));
+    if reference.is_empty() {
+        bail!(
+            "`{} -d` didn't dump any function, is it built with DUMP_BYTECODE?",
+            qjs
+        );
+    }
+
+    let mut compared = 0;
+    for (func, operators) in &ours {
+        if let Some(expected) = reference.get(func) {
+            compare_function(func, operators, expected)?;
+            compared += 1;
+        }
+    }
+    ensure!(
+        compared > 0,
+        "No function of `{}` found in both dumps",
+        path.display()
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(not(feature = "qjs-conformance"), ignore = "requires `qjs`")]
+fn closure() -> Result<()> {
+    check_fixture(&Path::new(FIXTURES_DIR).join("closure.js"))
+}
+
+#[test]
+#[cfg_attr(not(feature = "qjs-conformance"), ignore = "requires `qjs`")]
+fn for_loop() -> Result<()> {
+    check_fixture(&Path::new(FIXTURES_DIR).join("loop.js"))
+}
+
+#[test]
+#[cfg_attr(not(feature = "qjs-conformance"), ignore = "requires `qjs`")]
+fn try_catch() -> Result<()> {
+    check_fixture(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("try_catch.js"),
+    )
+}
+
+#[test]
+#[cfg_attr(not(feature = "qjs-conformance"), ignore = "requires `qjs`")]
+fn class() -> Result<()> {
+    check_fixture(&Path::new(FIXTURES_DIR).join("class.js"))
+}
+
+#[test]
+fn normalization() {
+    let ours = parse_disassembly("func: add [strict]\n0x0    GetArg0\n0x1    PushI32 1\n\n");
+    let reference = parse_qjs_dump(
+        "add.js:1: function: add\n  mode: strict\n  opcodes:\n        get_arg0 0: a\n    1:  push_i32 1\n",
+    );
+    assert_eq!(
+        ours["add"],
+        vec![(Some(0), "getarg0".into()), (Some(1), "pushi32".into())]
+    );
+    assert!(compare_function("add", &ours["add"], &reference["add"]).is_ok());
+}
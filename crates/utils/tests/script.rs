@@ -0,0 +1,54 @@
+use anyhow::Result;
+use jac_testutil::{encode_bytecode, encode_function, interned_atom};
+use jac_translate::{
+    quickpars::{FuncIndex, FunctionSectionHeader, ModuleSectionHeader, Opcode},
+    TranslationBuilder,
+};
+use jac_utils::disassemble;
+
+/// Builds the bytecode of `42`, evaluated with script semantics, i.e., a
+/// bare function named `main` instead of a module.
+fn script() -> Result<Vec<u8>> {
+    let mut operators = vec![];
+    Opcode::PushI32 { value: 42 }.encode(&mut operators);
+    Opcode::Return.encode(&mut operators);
+    let header = FunctionSectionHeader::builder()
+        .with_name(interned_atom(0))
+        .with_stack_size(1)
+        .with_bytecode_len(operators.len() as u32)
+        .build()?;
+    Ok(encode_bytecode(
+        &["main"],
+        &encode_function(&header, &[], &[], &operators),
+    ))
+}
+
+#[test]
+fn scripts_keep_the_default_module_header() -> Result<()> {
+    let bytecode = script()?;
+    let translation = TranslationBuilder::new().translate(&bytecode)?;
+    assert_eq!(translation.module.functions.len(), 1);
+    assert_eq!(
+        translation.resolve_func_name(FuncIndex::from_u32(0), None),
+        "main"
+    );
+
+    let header = &translation.module.header;
+    let default = ModuleSectionHeader::default();
+    assert_eq!(header.name_index, default.name_index);
+    assert_eq!(header.has_tla, default.has_tla);
+    assert!(header.req_modules.is_empty());
+    assert!(header.imports.is_empty());
+    assert!(header.exports.is_empty());
+    assert!(header.star_exports.is_empty());
+    Ok(())
+}
+
+#[test]
+fn scripts_disassemble() -> Result<()> {
+    let output = disassemble(&script()?)?;
+    assert!(output.lines().any(|line| line == "func: main"), "{output}");
+    assert!(output.contains("PushI32 42"), "{output}");
+    assert!(output.contains("Return"), "{output}");
+    Ok(())
+}
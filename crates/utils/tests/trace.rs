@@ -0,0 +1,46 @@
+use anyhow::Result;
+use jac_testutil::FIXTURES_DIR;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn text_report_has_one_event_per_line() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let trace = dir.join("jac-utils-trace-text.trace");
+    let out = dir.join("jac-utils-trace-text.txt");
+    std::fs::write(
+        &trace,
+        "header\n0,0,00,7,\n1,0,START,0,\n1,4,0c,10,\n1,0,END,0,\n",
+    )?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_jac-utils"))
+        .arg("trace")
+        .arg(PathBuf::from(FIXTURES_DIR).join("loop.js"))
+        .arg("--trace")
+        .arg(&trace)
+        .arg("-o")
+        .arg(&out)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let report = std::fs::read_to_string(&out)?;
+    assert!(report.ends_with('\n'), "{report:?}");
+    let lines = report.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("SYSTEM COST"), "{report:?}");
+    assert!(lines[1].starts_with("FUNCTION START"), "{report:?}");
+    assert!(
+        lines.last().unwrap().starts_with("FUNCTION END"),
+        "{report:?}"
+    );
+    assert!(
+        lines
+            .iter()
+            .all(|line| line.matches("FUNCTION ").count() <= 1),
+        "{report:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use jac_testutil::{named_fixture, FIXTURES_DIR};
+use jac_translate::TranslationBuilder;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn validate(input: &Path) -> Result<std::process::Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_jac-utils"))
+        .arg("validate")
+        .arg(input)
+        .output()?)
+}
+
+#[test]
+fn valid_bytecode_passes() -> Result<()> {
+    let output = validate(&PathBuf::from(FIXTURES_DIR).join("loop.js"))?;
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    Ok(())
+}
+
+#[test]
+fn corrupted_bytecode_fails() -> Result<()> {
+    let mut bytecode = named_fixture("loop");
+
+    // Replace the last operator of the top-level function with a `PushI32`,
+    // whose immediate runs past the end of the operators.
+    let last = {
+        let translation = TranslationBuilder::new().translate(&bytecode)?;
+        let operators = translation.module.functions[0].operators_reader();
+        operators.absolute_offset() + operators.data().len() - 1
+    };
+    bytecode[last] = 0x01;
+
+    let input = std::env::temp_dir().join("jac-utils-validate-corrupted.bc");
+    std::fs::write(&input, &bytecode)?;
+    let output = validate(&input)?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Invalid operator"), "{stdout}");
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Found 1 verification errors"), "{stderr}");
+    Ok(())
+}